@@ -0,0 +1,36 @@
+//! Entry points for embedding voyager as a library without going through the
+//! CLI commands.
+
+use crate::config::Manifest;
+use crate::error::Result;
+use crate::infra::GitHubApi;
+use crate::lock::Lockfile;
+use crate::services::{FetchProgressReporter, FetcherConfig, PackageFetcher};
+use std::sync::Arc;
+
+/// Fetches the releases of every package in `manifest` into `lockfile` and
+/// returns the updated lock file.
+///
+/// Nothing is read from or written to disk: `config.checkpoint_path` and
+/// `config.resume` are ignored, and the caller decides whether to save the
+/// result and which `manifest_hash` to record. A partial failure is returned
+/// as [`Error::FetchPartialFailure`](crate::error::Error::FetchPartialFailure)
+/// and drops the lock file, as the fetch command does without `--on-failure keep`.
+pub async fn fetch_in_memory<G: GitHubApi>(
+    manifest: &Manifest,
+    mut lockfile: Lockfile,
+    github: Arc<G>,
+    config: FetcherConfig,
+    progress: Option<&dyn FetchProgressReporter>,
+) -> Result<Lockfile> {
+    let fetcher = PackageFetcher::new(
+        github,
+        FetcherConfig {
+            checkpoint_path: None,
+            resume: false,
+            ..config
+        },
+    );
+    fetcher.fetch(manifest, &mut lockfile, progress).await?;
+    Ok(lockfile)
+}
@@ -1,9 +1,21 @@
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use crate::config::{ConfigFormat, validation};
+use crate::error::Error;
+use crate::infra::{
+    AssetMatchStrategy, DEFAULT_ASSET_TIMEOUT_SECS, DEFAULT_CONNECT_TIMEOUT_SECS,
+    DEFAULT_TIMEOUT_SECS, GitHubAppAuth, GitHubAuth, HostRetries, HttpTimeouts, UrlCheckMethod,
+};
+use crate::lock::{HashAlgorithm, Lockfile};
+use crate::services::{
+    DEFAULT_MAX_INDEX_SIZE, LockWriter, PackageOrder, VersionOrder, serialize_lock,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
+use regex::Regex;
 use std::path::{Path, PathBuf};
 
 /// Default configuration file name.
 pub const DEFAULT_CONFIG_FILE: &str = "voyager.toml";
+pub const DEFAULT_LOCK_EXTENSION: &str = "lock";
 
 /// Runtime configuration paths.
 #[derive(Debug, Clone)]
@@ -12,14 +24,66 @@ pub struct ConfigPaths {
     config: PathBuf,
     /// Path to the lock file (voyager.lock).
     lock: PathBuf,
+    /// Write a "generated by voyager" header comment into saved lock files.
+    lock_comment: bool,
+    /// Format of the manifest file.
+    config_format: ConfigFormat,
 }
 
 impl ConfigPaths {
     /// Create new ConfigPaths from a config file path.
     /// Lock file path is derived by changing the extension to `.lock`.
     pub fn new(config: PathBuf) -> Self {
-        let lock = config.with_extension("lock");
-        Self { config, lock }
+        let lock = config.with_extension(DEFAULT_LOCK_EXTENSION);
+        Self {
+            config,
+            lock,
+            lock_comment: false,
+            config_format: ConfigFormat::Auto,
+        }
+    }
+
+    /// Derives the lock file path with a different extension
+    /// (e.g. `lock.toml` for `voyager.lock.toml`).
+    pub fn with_lock_extension(mut self, extension: &str) -> Self {
+        self.lock = self.config.with_extension(extension);
+        self
+    }
+
+    /// Uses an explicit lock file path instead of deriving it from the config
+    /// path. The transaction log is kept next to it.
+    pub fn with_lock_path(mut self, lock: PathBuf) -> Self {
+        self.lock = lock;
+        self
+    }
+
+    /// Enables the header comment on lock files saved by commands.
+    pub fn with_lock_comment(mut self, enabled: bool) -> Self {
+        self.lock_comment = enabled;
+        self
+    }
+
+    /// Acquires the writer every command saves the manifest and lock file
+    /// through, so the header comment setting applies to all of them. Hold it
+    /// from loading the files until they are saved.
+    pub fn lock_writer(&self) -> crate::error::Result<LockWriter> {
+        Ok(LockWriter::acquire(&self.config, &self.lock)?.with_header_comment(self.lock_comment))
+    }
+
+    /// Serializes `lockfile` exactly as [`Self::lock_writer`] would save it.
+    pub fn lock_content(&self, lockfile: &Lockfile) -> crate::error::Result<String> {
+        serialize_lock(lockfile, &self.lock, self.lock_comment)
+    }
+
+    /// Sets the manifest format (`Auto` picks by extension).
+    pub fn with_config_format(mut self, format: ConfigFormat) -> Self {
+        self.config_format = format;
+        self
+    }
+
+    /// Format the manifest is read and written in.
+    pub fn config_format(&self) -> ConfigFormat {
+        self.config_format
     }
 
     /// Get the config file path.
@@ -27,6 +91,11 @@ impl ConfigPaths {
         &self.config
     }
 
+    /// Whether `--config -` asked for the manifest on stdin.
+    pub fn config_is_stdin(&self) -> bool {
+        self.config.as_os_str() == "-"
+    }
+
     /// Get the lock file path.
     pub fn lock_path(&self) -> &Path {
         &self.lock
@@ -39,6 +108,16 @@ impl Default for ConfigPaths {
     }
 }
 
+fn parse_concurrency(s: &str) -> Result<usize, String> {
+    let value: usize = parse_number(s)?;
+
+    if !(1..=50).contains(&value) {
+        return Err("concurrency must be between 1 and 50".to_string());
+    }
+
+    Ok(value)
+}
+
 fn parse_max_concurrent(s: &str) -> Result<usize, String> {
     let value: usize = parse_number(s)?;
 
@@ -53,6 +132,47 @@ fn parse_max_concurrent(s: &str) -> Result<usize, String> {
     Ok(value)
 }
 
+fn parse_lock_path(s: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(s);
+
+    if path.file_name().is_none() {
+        return Err("lock must be a file path".to_string());
+    }
+
+    if path.extension().is_some_and(|ext| ext == "txn") {
+        return Err("lock must not use the .txn extension of the transaction log".to_string());
+    }
+
+    Ok(path)
+}
+
+fn parse_lock_ext(s: &str) -> Result<String, String> {
+    let ext = s.trim_start_matches('.');
+
+    if ext.is_empty() {
+        return Err("lock-ext must not be empty".to_string());
+    }
+
+    if ext.contains(['/', '\\']) {
+        return Err("lock-ext must not contain path separators".to_string());
+    }
+
+    if ext == "toml" || ext == "txn" {
+        return Err(format!(
+            "lock-ext '{}' would clash with voyager's own files",
+            ext
+        ));
+    }
+
+    Ok(ext.to_string())
+}
+
+fn parse_unity_floor(s: &str) -> Result<String, String> {
+    validation::validate_unity_version(s)
+        .map(|()| s.to_string())
+        .map_err(|_| "min-unity must be in MAJOR.MINOR format (e.g. 2022.3)".to_string())
+}
+
 fn parse_max_retries(s: &str) -> Result<u32, String> {
     let value: u32 = parse_number(s)?;
 
@@ -63,6 +183,69 @@ fn parse_max_retries(s: &str) -> Result<u32, String> {
     Ok(value)
 }
 
+fn parse_host_retries(s: &str) -> Result<HostRetries, String> {
+    let (host, retries) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected HOST=RETRIES, got '{}'", s))?;
+    let host = host.trim().to_ascii_lowercase();
+    if host.is_empty() || host.contains('/') {
+        return Err(format!("invalid host '{}'", host));
+    }
+
+    Ok(HostRetries {
+        host,
+        max_retries: parse_max_retries(retries.trim())?,
+    })
+}
+
+fn parse_asset_regex(s: &str) -> Result<Regex, String> {
+    Regex::new(s).map_err(|e| e.to_string())
+}
+
+fn parse_asset_timeout(s: &str) -> Result<u64, String> {
+    let value: u64 = parse_number(s)?;
+
+    if value == 0 {
+        return Err("asset-timeout must be at least 1".to_string());
+    }
+
+    if value > 3600 {
+        return Err("asset-timeout must be at most 3600".to_string());
+    }
+
+    Ok(value)
+}
+
+fn parse_timeout(s: &str) -> Result<u64, String> {
+    let value: u64 = parse_number(s)?;
+
+    if !(1..=300).contains(&value) {
+        return Err("timeout must be between 1 and 300 seconds".to_string());
+    }
+
+    Ok(value)
+}
+
+fn parse_keep_latest(s: &str) -> Result<usize, String> {
+    let value: usize = parse_number(s)?;
+
+    if value == 0 {
+        return Err("keep-latest must be at least 1".to_string());
+    }
+
+    Ok(value)
+}
+
+fn parse_max_asset_bytes(s: &str) -> Result<u64, String> {
+    let value: u64 = parse_number(s)?;
+
+    if value == 0 {
+        return Err("max-asset-bytes must be at least 1".to_string());
+    }
+
+    Ok(value)
+}
+
 fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
     s.parse()
         .map_err(|_| format!("'{s}' is not a valid number"))
@@ -78,6 +261,22 @@ pub struct Cli {
     #[arg(long, global = true, default_value = DEFAULT_CONFIG_FILE)]
     pub config: PathBuf,
 
+    /// Extension of the lock file derived from the config path
+    #[arg(long, global = true, value_name = "EXT", default_value = DEFAULT_LOCK_EXTENSION, value_parser = parse_lock_ext)]
+    pub lock_ext: String,
+
+    /// Path to the lock file, instead of deriving it from --config
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "lock_ext", value_parser = parse_lock_path)]
+    pub lock: Option<PathBuf>,
+
+    /// Start saved lock files with a "generated by voyager" comment (kept once present)
+    #[arg(long, global = true)]
+    pub lock_comment: bool,
+
+    /// Manifest format; `auto` reads `.json` files as JSON and others as TOML
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    pub config_format: ConfigFormatArg,
+
     /// Verbosity level (-v, -vv, -vvv)
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
@@ -86,11 +285,146 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// Print the full chain of underlying errors (implied by -vv)
+    #[arg(long, global = true)]
+    pub error_context: bool,
+
+    /// How errors are printed to stderr
+    #[arg(long, value_enum, default_value = "text", global = true)]
+    pub error_format: ErrorFormat,
+
     /// Control color output
     #[arg(long, value_enum, default_value = "auto", global = true)]
     pub color: ColorChoice,
 }
 
+/// `validate --method` values, mapped onto [`UrlCheckMethod`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum UrlCheckMethodArg {
+    /// HEAD, falling back to a range-limited GET when HEAD is blocked
+    #[default]
+    Auto,
+    /// HEAD only, never falling back
+    Head,
+    /// Range-limited GET only
+    Get,
+}
+
+impl From<UrlCheckMethodArg> for UrlCheckMethod {
+    fn from(method: UrlCheckMethodArg) -> Self {
+        match method {
+            UrlCheckMethodArg::Auto => Self::Auto,
+            UrlCheckMethodArg::Head => Self::Head,
+            UrlCheckMethodArg::Get => Self::Get,
+        }
+    }
+}
+
+/// `--config-format` values, mapped onto [`ConfigFormat`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigFormatArg {
+    /// JSON for `.json` files, TOML otherwise
+    #[default]
+    Auto,
+    /// TOML
+    Toml,
+    /// JSON with the same structure as the TOML manifest
+    Json,
+}
+
+impl From<ConfigFormatArg> for ConfigFormat {
+    fn from(format: ConfigFormatArg) -> Self {
+        match format {
+            ConfigFormatArg::Auto => Self::Auto,
+            ConfigFormatArg::Toml => Self::Toml,
+            ConfigFormatArg::Json => Self::Json,
+        }
+    }
+}
+
+/// `--hash-algo` and `--manifest-hash-algo` values, mapped onto [`HashAlgorithm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HashAlgorithmArg {
+    /// SHA-256
+    #[default]
+    Sha256,
+    /// SHA-512
+    Sha512,
+}
+
+impl From<HashAlgorithmArg> for HashAlgorithm {
+    fn from(algorithm: HashAlgorithmArg) -> Self {
+        match algorithm {
+            HashAlgorithmArg::Sha256 => Self::Sha256,
+            HashAlgorithmArg::Sha512 => Self::Sha512,
+        }
+    }
+}
+
+/// `generate --sort` values, mapped onto [`PackageOrder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PackageOrderArg {
+    /// Keep the order packages are declared in the manifest
+    #[default]
+    Manifest,
+    /// Sort packages alphabetically by ID
+    #[value(name = "packages")]
+    Alphabetical,
+}
+
+impl From<PackageOrderArg> for PackageOrder {
+    fn from(order: PackageOrderArg) -> Self {
+        match order {
+            PackageOrderArg::Manifest => Self::Manifest,
+            PackageOrderArg::Alphabetical => Self::Alphabetical,
+        }
+    }
+}
+
+/// `generate --sort-versions` values, mapped onto [`VersionOrder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum VersionOrderArg {
+    /// Keep the lockfile order (newest fetched first)
+    #[default]
+    Lock,
+    /// Oldest version first (SemVer order)
+    Asc,
+    /// Newest version first (SemVer order)
+    Desc,
+}
+
+impl From<VersionOrderArg> for VersionOrder {
+    fn from(order: VersionOrderArg) -> Self {
+        match order {
+            VersionOrderArg::Lock => Self::Lock,
+            VersionOrderArg::Asc => Self::Asc,
+            VersionOrderArg::Desc => Self::Desc,
+        }
+    }
+}
+
+/// `fetch --asset-match` values, mapped onto [`AssetMatchStrategy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AssetMatchArg {
+    /// The asset whose name equals the configured asset name
+    #[default]
+    Exact,
+    /// The first asset of the release, regardless of name
+    First,
+    /// The largest asset of the release, regardless of name
+    Largest,
+}
+
+impl From<AssetMatchArg> for AssetMatchStrategy {
+    fn from(strategy: AssetMatchArg) -> Self {
+        match strategy {
+            AssetMatchArg::Exact => Self::Exact,
+            AssetMatchArg::First => Self::First,
+            AssetMatchArg::Largest => Self::Largest,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
 pub enum ColorChoice {
     #[default]
@@ -99,10 +433,18 @@ pub enum ColorChoice {
     Never,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    /// A JSON object with `code`, `message` and `exit_code`
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Fetch package data from GitHub releases and update voyager.lock
-    Fetch(FetchArgs),
+    Fetch(Box<FetchArgs>),
 
     /// Generate VPM package index from voyager.lock
     Generate(GenerateArgs),
@@ -113,6 +455,9 @@ pub enum Commands {
     /// Initialize a new voyager.toml configuration file
     Init(InitArgs),
 
+    /// Import an existing index.json into voyager.toml and voyager.lock
+    Migrate(MigrateArgs),
+
     /// Add a package to voyager.toml
     Add(AddArgs),
 
@@ -125,25 +470,66 @@ pub enum Commands {
     /// Remove a package from voyager.toml
     Remove(RemoveArgs),
 
+    /// Drop all but the newest locked versions of packages
+    Prune(PruneArgs),
+
     /// Show detailed information about a package
     Info(InfoArgs),
 
+    /// Compare the index generated from voyager.lock with a published index
+    Diff(DiffArgs),
+
+    /// Re-download locked package.json assets and check their hashes
+    Verify(VerifyArgs),
+
+    /// Report packages with GitHub releases that are not in voyager.lock yet
+    Outdated(OutdatedArgs),
+
+    /// List every version URL of the index generated from voyager.lock
+    Urls(UrlsArgs),
+
     /// Generate shell completions
     Completions(CompletionsArgs),
 }
 
 #[derive(Args, Debug)]
 pub struct CompletionsArgs {
-    /// Shell to generate completions for
-    #[arg(value_enum)]
-    pub shell: Shell,
+    /// Shell to generate completions for (with --out-dir, omit for every shell)
+    #[arg(value_enum, required_unless_present = "out_dir")]
+    pub shell: Option<Shell>,
+
+    /// Write completion files (voy.bash, _voy, ...) into this directory instead of stdout
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
 }
 
 impl CompletionsArgs {
     /// Generates and prints shell completions to stdout.
     pub fn generate(&self) {
+        if let Some(shell) = self.shell {
+            let mut cmd = Cli::command();
+            clap_complete::generate(shell, &mut cmd, "voy", &mut std::io::stdout());
+        }
+    }
+
+    /// Writes completion files for the chosen shell, or every shell, into
+    /// `dir`, creating it if needed. Returns the written paths.
+    pub fn write_to(&self, dir: &Path) -> crate::error::Result<Vec<PathBuf>> {
+        let to_error = |e| Error::FileWrite {
+            path: dir.display().to_string(),
+            source: e,
+        };
+        std::fs::create_dir_all(dir).map_err(to_error)?;
+
+        let shells = match self.shell {
+            Some(shell) => vec![shell],
+            None => Shell::value_variants().to_vec(),
+        };
         let mut cmd = Cli::command();
-        clap_complete::generate(self.shell, &mut cmd, "voy", &mut std::io::stdout());
+        shells
+            .into_iter()
+            .map(|shell| clap_complete::generate_to(shell, &mut cmd, "voy", dir).map_err(to_error))
+            .collect()
     }
 }
 
@@ -151,12 +537,38 @@ impl CompletionsArgs {
 pub struct ListArgs {
     /// Package ID to show versions for (omit to list all packages)
     pub package_id: Option<String>,
+
+    /// Only list packages carrying this tag
+    #[arg(long, conflicts_with = "package_id")]
+    pub tag: Option<String>,
+
+    /// Print packages as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Include each version's tag, url and hash in the JSON output
+    #[arg(long, requires = "json")]
+    pub detailed: bool,
 }
 
 #[derive(Args, Debug)]
 pub struct RemoveArgs {
     /// Package ID to remove
     pub package_id: String,
+
+    /// Keep the package's locked versions so it can be re-added later
+    #[arg(long)]
+    pub keep_lock: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// Package ID to prune (omit to prune every package)
+    pub package_id: Option<String>,
+
+    /// Number of newest versions (by semver) to keep per package
+    #[arg(long, value_name = "N", value_parser = parse_keep_latest)]
+    pub keep_latest: usize,
 }
 
 #[derive(Args, Debug)]
@@ -165,39 +577,383 @@ pub struct LockArgs {
     #[arg(long)]
     pub check: bool,
 
-    /// GitHub personal access token (for repository verification)
+    /// Print the lock file that would be written instead of writing it
+    #[arg(long, conflicts_with = "check")]
+    pub print: bool,
+
+    /// Also drop stale packages and reorder the lock file to match the manifest (no network access)
+    #[arg(long, conflicts_with_all = ["check", "print"])]
+    pub fix: bool,
+
+    /// Hash algorithm for the manifest hash (defaults to the one already in the lock file)
+    #[arg(long, value_enum)]
+    pub manifest_hash_algo: Option<HashAlgorithmArg>,
+
+    #[command(flatten)]
+    pub github_auth: GitHubAuthArgs,
+
+    /// GitLab personal access token (for `gitlab:` repositories)
+    #[arg(long, env = "VOYAGER_GITLAB_TOKEN")]
+    pub gitlab_token: Option<String>,
+
+    /// Gitea access token (for `gitea:` repositories)
+    #[arg(long, env = "VOYAGER_GITEA_TOKEN")]
+    pub gitea_token: Option<String>,
+}
+
+/// GitHub credentials: a token given directly or read from a file, or GitHub
+/// App installation credentials used instead of a token when given.
+#[derive(Args, Debug, Default)]
+pub struct GitHubAuthArgs {
+    /// GitHub personal access token
     #[arg(long, env = "VOYAGER_GITHUB_TOKEN")]
     pub github_token: Option<String>,
+
+    /// Read the GitHub token from a file instead of passing it on the command line
+    #[arg(
+        long,
+        env = "VOYAGER_GITHUB_TOKEN_FILE",
+        value_name = "PATH",
+        conflicts_with = "github_token"
+    )]
+    pub github_token_file: Option<PathBuf>,
+
+    /// GitHub App ID (authenticate as an app installation instead of a token)
+    #[arg(long, env = "VOYAGER_GITHUB_APP_ID", requires_all = ["github_installation_id", "github_app_key"])]
+    pub github_app_id: Option<u64>,
+
+    /// Installation ID of the GitHub App
+    #[arg(long, env = "VOYAGER_GITHUB_INSTALLATION_ID", requires_all = ["github_app_id", "github_app_key"])]
+    pub github_installation_id: Option<u64>,
+
+    /// Path to the GitHub App private key (PEM)
+    #[arg(long, env = "VOYAGER_GITHUB_APP_KEY", value_name = "PATH", requires_all = ["github_app_id", "github_installation_id"])]
+    pub github_app_key: Option<PathBuf>,
+}
+
+impl GitHubAuthArgs {
+    /// Returns the app credentials when all of them were given.
+    pub fn credentials(&self) -> Option<GitHubAppAuth> {
+        Some(GitHubAppAuth {
+            app_id: self.github_app_id?,
+            installation_id: self.github_installation_id?,
+            private_key_path: self.github_app_key.clone()?,
+        })
+    }
+
+    /// Resolves how to authenticate, preferring app credentials over the
+    /// token from `--github-token` or `--github-token-file`.
+    pub fn auth(&self) -> crate::error::Result<GitHubAuth> {
+        let file_token = self
+            .github_token_file
+            .as_deref()
+            .map(read_token_file)
+            .transpose()?;
+        Ok(GitHubAuth::resolve(
+            file_token.as_deref().or(self.github_token.as_deref()),
+            self.credentials(),
+        ))
+    }
+}
+
+/// Reads a token file, dropping the trailing newline editors and `echo` add.
+fn read_token_file(path: &Path) -> crate::error::Result<String> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::FileRead {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    let token = content.trim_end();
+    if token.is_empty() {
+        return Err(Error::ConfigValidation(format!(
+            "GitHub token file '{}' is empty",
+            path.display()
+        )));
+    }
+    Ok(token.to_string())
+}
+
+/// Timeouts of the HTTP clients talking to APIs and checking URLs.
+#[derive(Args, Debug)]
+pub struct TimeoutArgs {
+    /// Timeout in seconds for each HTTP request (1-300)
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_TIMEOUT_SECS, value_parser = parse_timeout)]
+    pub timeout: u64,
+
+    /// Timeout in seconds for establishing a connection (1-300)
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_CONNECT_TIMEOUT_SECS, value_parser = parse_timeout)]
+    pub connect_timeout: u64,
+}
+
+impl TimeoutArgs {
+    pub fn timeouts(&self) -> HttpTimeouts {
+        HttpTimeouts::from_secs(self.timeout, self.connect_timeout)
+    }
 }
 
 #[derive(Args, Debug)]
 pub struct FetchArgs {
-    /// GitHub personal access token
-    #[arg(long, env = "VOYAGER_GITHUB_TOKEN")]
-    pub github_token: Option<String>,
+    #[command(flatten)]
+    pub github_auth: GitHubAuthArgs,
+
+    /// GitLab personal access token (for `gitlab:` repositories)
+    #[arg(long, env = "VOYAGER_GITLAB_TOKEN")]
+    pub gitlab_token: Option<String>,
+
+    /// Gitea access token (for `gitea:` repositories)
+    #[arg(long, env = "VOYAGER_GITEA_TOKEN")]
+    pub gitea_token: Option<String>,
 
     /// Maximum number of concurrent downloads (1-50)
     #[arg(long, env = "VOYAGER_MAX_CONCURRENT", default_value = "5", value_parser = parse_max_concurrent)]
     pub max_concurrent: usize,
 
+    /// Packages fetched at once (1-50; defaults to --max-concurrent)
+    #[arg(long, value_name = "N", value_parser = parse_concurrency)]
+    pub package_concurrency: Option<usize>,
+
+    /// Downloads per package at once (1-50; defaults to --max-concurrent split across packages)
+    #[arg(long, value_name = "N", value_parser = parse_concurrency)]
+    pub download_concurrency: Option<usize>,
+
     /// Name of the asset file to download from releases
     #[arg(long, env = "VOYAGER_ASSET_NAME", default_value = "package.json")]
     pub asset_name: String,
 
+    /// How to pick the asset from each release (`first`/`largest` ignore --asset-name)
+    #[arg(long, env = "VOYAGER_ASSET_MATCH", value_enum, default_value = "exact")]
+    pub asset_match: AssetMatchArg,
+
+    /// Pick the first release asset whose name matches this regex (overrides --asset-name and --asset-match)
+    #[arg(long, value_name = "REGEX", value_parser = parse_asset_regex)]
+    pub asset_regex: Option<Regex>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+
+    /// Timeout in seconds for downloading each release asset (1-3600)
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_ASSET_TIMEOUT_SECS, value_parser = parse_asset_timeout)]
+    pub asset_timeout: u64,
+
+    /// Reject release assets larger than this many bytes
+    #[arg(long, value_name = "BYTES", value_parser = parse_max_asset_bytes)]
+    pub max_asset_bytes: Option<u64>,
+
     /// Maximum number of retries for failed downloads (0-8)
     #[arg(long, env = "VOYAGER_MAX_RETRIES", default_value = "3", value_parser = parse_max_retries)]
     pub max_retries: u32,
 
+    /// Override --max-retries for downloads from a host (`*.domain` matches subdomains)
+    #[arg(long = "retries-per-host", value_name = "HOST=RETRIES", value_parser = parse_host_retries)]
+    pub retries_per_host: Vec<HostRetries>,
+
     /// Clear all cached versions and re-fetch everything
     #[arg(long)]
     pub wipe: bool,
+
+    /// Only fetch packages that have no locked versions yet
+    #[arg(long, conflicts_with = "wipe")]
+    pub only_missing: bool,
+
+    /// Only fetch the package with this ID (repeatable); others stay locked as-is
+    #[arg(long = "only", value_name = "PACKAGE_ID", conflicts_with = "wipe")]
+    pub only: Vec<String>,
+
+    /// Only fetch packages whose manifest entries changed since this git ref
+    #[arg(long, value_name = "REF")]
+    pub only_changed_since: Option<String>,
+
+    /// Only fetch packages whose repository no earlier fetch has contacted
+    #[arg(long, alias = "fetch-only-new-repos", conflicts_with = "wipe")]
+    pub only_new_repos: bool,
+
+    /// Resume an interrupted fetch, skipping packages it already completed
+    #[arg(long, conflicts_with = "wipe")]
+    pub resume: bool,
+
+    /// Drop `+build` metadata from versions before locking them (tags are kept)
+    #[arg(long)]
+    pub strip_build_metadata: bool,
+
+    /// Run the fetch in memory and report versions it would add, prune or fail to fetch
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Reject versions whose package.json `unity` is older than this (e.g. 2022.3)
+    #[arg(long, value_name = "MAJOR.MINOR", value_parser = parse_unity_floor)]
+    pub min_unity: Option<String>,
+
+    /// Reject versions whose package.json has no `unity` field
+    #[arg(long)]
+    pub require_unity: bool,
+
+    /// Only accept vpmDependencies under this package ID prefix (repeatable)
+    #[arg(long = "allow-dep-prefix", value_name = "PREFIX")]
+    pub allow_dep_prefixes: Vec<String>,
+
+    /// Download each new version's zip to fill in or check its zipSHA256
+    #[arg(long)]
+    pub verify_zip: bool,
+
+    /// Keep each new version's raw package.json in the lock file for auditing
+    #[arg(long)]
+    pub store_raw: bool,
+
+    /// Reject versions with blank package.json keywords instead of dropping them
+    #[arg(long)]
+    pub strict_keywords: bool,
+
+    /// Skip releases with a semver prerelease version such as 1.0.0-rc.1
+    #[arg(long)]
+    pub no_prerelease: bool,
+
+    /// With --no-prerelease, keep prereleases that are already locked instead of pruning them
+    #[arg(long, requires = "no_prerelease")]
+    pub keep_existing: bool,
+
+    /// Reject versions whose package.json url is on another host than the release asset (default: warn)
+    #[arg(long)]
+    pub strict_url: bool,
+
+    /// Reject versions whose package.json legacyFolders/legacyFiles paths are malformed (default: warn)
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Hash algorithm for the hashes of newly fetched versions
+    #[arg(long, value_enum, default_value = "sha256")]
+    pub hash_algo: HashAlgorithmArg,
+
+    /// What to do with the lock file when some versions fail to fetch
+    #[arg(long, value_enum, default_value = "revert")]
+    pub on_failure: OnFailure,
+
+    /// Output format of the fetch summary
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// List releases afresh instead of revalidating those cached in `*.cache`
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Store asset URLs rewritten by `[registry] url_rewrite` in the lock file
+    #[arg(long)]
+    pub persist_rewrite: bool,
+}
+
+/// Lock file policy for `fetch` runs that end in a partial failure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnFailure {
+    /// Leave the lock file untouched
+    #[default]
+    Revert,
+    /// Save the successfully fetched versions, then exit non-zero
+    Keep,
 }
 
 #[derive(Args, Debug)]
 pub struct GenerateArgs {
-    /// Path to the output file
+    /// Path to the output file, or `-` to write the index to stdout
     #[arg(short, long, env = "VOYAGER_OUTPUT_PATH", default_value = "index.json")]
     pub output: PathBuf,
+
+    /// Order of packages in the index (manifest order or alphabetical)
+    #[arg(long, value_enum, default_value = "manifest")]
+    pub sort: PackageOrderArg,
+
+    /// Order of version keys in the index
+    #[arg(long, value_enum, default_value = "lock")]
+    pub sort_versions: VersionOrderArg,
+
+    /// Only include packages carrying this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Fail if any package has fewer than this many versions
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_versions: usize,
+
+    /// Include packages that have no versions in the index
+    #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+    pub emit_empty_packages: bool,
+
+    /// Fail if the newest versions of two packages share a display name
+    #[arg(long)]
+    pub no_duplicate_display_names: bool,
+
+    /// Sort all JSON object keys for byte-stable output (overrides --sort/--sort-versions order)
+    #[arg(long)]
+    pub sort_keys: bool,
+
+    /// Write compact JSON without indentation or newlines
+    #[arg(long)]
+    pub minify: bool,
+
+    /// Drop package.json fields that are not part of the VPM schema
+    #[arg(long, alias = "sanitize-extra")]
+    pub drop_extra: bool,
+
+    /// Leave author emails out of the index (voyager.lock keeps them)
+    #[arg(long)]
+    pub redact_email: bool,
+
+    /// Record the voyager version, generation time and manifest hash under a top-level `voyager` key
+    #[arg(long)]
+    pub emit_metadata: bool,
+
+    /// Write a checksum of the generated index (defaults to <output>.sha256)
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub output_hash_file: Option<Option<PathBuf>>,
+
+    /// Format of the checksum written by --output-hash-file
+    #[arg(
+        long,
+        value_enum,
+        default_value = "prefixed",
+        requires = "output_hash_file"
+    )]
+    pub hash_format: HashFormat,
+
+    /// Also write a flat JSON list of every locked version's zip URL and zipSHA256
+    #[arg(long, value_name = "PATH")]
+    pub assets: Option<PathBuf>,
+
+    /// Warn when the generated index is larger than this many bytes
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_INDEX_SIZE)]
+    pub max_index_size: u64,
+
+    /// Treat generate warnings as errors
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Never access the network, skipping any check that would need it
+    #[arg(long)]
+    pub offline: bool,
+}
+
+impl GenerateArgs {
+    /// Whether `--output -` asked for the index on stdout.
+    pub fn writes_to_stdout(&self) -> bool {
+        self.output.as_os_str() == "-"
+    }
+
+    /// Resolves the checksum sidecar path, if one was requested.
+    pub fn output_hash_path(&self) -> Option<PathBuf> {
+        self.output_hash_file.as_ref().map(|path| {
+            path.clone().unwrap_or_else(|| {
+                let mut name = self.output.as_os_str().to_os_string();
+                name.push(".sha256");
+                PathBuf::from(name)
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HashFormat {
+    /// `sha256:<hex>`, matching the lockfile convention
+    #[default]
+    Prefixed,
+    /// Bare hex digest
+    Hex,
 }
 
 #[derive(Args, Debug)]
@@ -212,6 +968,98 @@ pub struct ValidateArgs {
     /// Maximum number of retries for failed URL checks (0-8)
     #[arg(long, env = "VOYAGER_MAX_RETRIES", default_value = "3", value_parser = parse_max_retries)]
     pub max_retries: u32,
+
+    /// HTTP method for URL checks (auto: HEAD with GET fallback)
+    #[arg(long, value_enum, default_value = "auto")]
+    pub method: UrlCheckMethodArg,
+
+    /// Fail on plain http:// download URLs, reporting them without requesting them
+    #[arg(long)]
+    pub https_only: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+
+    /// Write a JSON report with the outcome of every URL (also on failure)
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Print wall time and URLs checked per second after the run
+    #[arg(long)]
+    pub concurrency_report: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub github_auth: GitHubAuthArgs,
+
+    /// GitLab personal access token (for `gitlab:` repositories)
+    #[arg(long, env = "VOYAGER_GITLAB_TOKEN")]
+    pub gitlab_token: Option<String>,
+
+    /// Gitea access token (for `gitea:` repositories)
+    #[arg(long, env = "VOYAGER_GITEA_TOKEN")]
+    pub gitea_token: Option<String>,
+
+    /// Maximum number of concurrent downloads (1-50)
+    #[arg(long, env = "VOYAGER_MAX_CONCURRENT", default_value = "5", value_parser = parse_max_concurrent)]
+    pub max_concurrent: usize,
+
+    /// Maximum number of retries for failed downloads (0-8)
+    #[arg(long, env = "VOYAGER_MAX_RETRIES", default_value = "3", value_parser = parse_max_retries)]
+    pub max_retries: u32,
+}
+
+#[derive(Args, Debug)]
+pub struct OutdatedArgs {
+    #[command(flatten)]
+    pub github_auth: GitHubAuthArgs,
+
+    /// GitLab personal access token (for `gitlab:` repositories)
+    #[arg(long, env = "VOYAGER_GITLAB_TOKEN")]
+    pub gitlab_token: Option<String>,
+
+    /// Gitea access token (for `gitea:` repositories)
+    #[arg(long, env = "VOYAGER_GITEA_TOKEN")]
+    pub gitea_token: Option<String>,
+
+    /// Maximum number of packages checked concurrently (1-50)
+    #[arg(long, env = "VOYAGER_MAX_CONCURRENT", default_value = "5", value_parser = parse_max_concurrent)]
+    pub max_concurrent: usize,
+
+    /// Name of the asset file releases must contain
+    #[arg(long, env = "VOYAGER_ASSET_NAME", default_value = "package.json")]
+    pub asset_name: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct UrlsArgs {
+    /// Write the URL list to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Request the first byte of every URL to prime caches in front of them
+    #[arg(long)]
+    pub warm: bool,
+
+    /// Maximum number of concurrent warming requests (1-50)
+    #[arg(long, env = "VOYAGER_MAX_CONCURRENT", default_value = "5", value_parser = parse_max_concurrent)]
+    pub max_concurrent: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct MigrateArgs {
+    /// Path to the index.json to import
+    pub index: PathBuf,
+
+    /// Overwrite an existing voyager.toml and voyager.lock
+    #[arg(long)]
+    pub force: bool,
 }
 
 #[derive(Args, Debug)]
@@ -235,6 +1083,10 @@ pub struct InitArgs {
     /// Overwrite existing file without confirmation
     #[arg(long)]
     pub force: bool,
+
+    /// Prompt for every field, offering given flags as defaults (requires a terminal)
+    #[arg(long)]
+    pub interactive: bool,
 }
 
 #[derive(Args, Debug)]
@@ -246,15 +1098,76 @@ pub struct AddArgs {
     #[arg(long)]
     pub id: Option<String>,
 
-    /// GitHub personal access token (for repository verification)
-    #[arg(long, env = "VOYAGER_GITHUB_TOKEN")]
-    pub github_token: Option<String>,
+    /// Use the `name` of the latest release's package.json as the package ID
+    #[arg(long, conflicts_with = "id")]
+    pub infer_id: bool,
+
+    /// Add the package without checking that the repository exists (offline use)
+    #[arg(long, conflicts_with = "infer_id")]
+    pub skip_verify: bool,
+
+    #[command(flatten)]
+    pub github_auth: GitHubAuthArgs,
+
+    /// GitLab personal access token (for `gitlab:` repositories)
+    #[arg(long, env = "VOYAGER_GITLAB_TOKEN")]
+    pub gitlab_token: Option<String>,
+
+    /// Gitea access token (for `gitea:` repositories)
+    #[arg(long, env = "VOYAGER_GITEA_TOKEN")]
+    pub gitea_token: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: OutputFormat,
 }
 
 #[derive(Args, Debug)]
 pub struct InfoArgs {
     /// Package ID to show information for
     pub package_id: String,
+
+    /// Print each locked version's hash, tag, URL and stored manifest as JSON
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Show what changed in the stored manifest between two locked versions
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["VERSION1", "VERSION2"],
+        conflicts_with = "raw"
+    )]
+    pub compare: Option<Vec<String>>,
+
+    /// Output format for --compare
+    #[arg(long, value_enum, default_value = "text", requires = "compare")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Index files to compare: OLD NEW, or just NEW with --against
+    #[arg(value_name = "PATH", num_args = 0..=2)]
+    pub indexes: Vec<String>,
+
+    /// Published index to compare against (URL or path); without NEW, the
+    /// index generated from the lock file is compared
+    #[arg(long, visible_alias = "remote-url", value_name = "URL|PATH")]
+    pub against: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable output
+    #[default]
+    Text,
+    /// JSON on stdout
+    Json,
 }
 
 #[cfg(test)]
@@ -282,6 +1195,14 @@ mod tests {
         assert!(parse_max_concurrent("abc").is_err());
     }
 
+    #[test]
+    fn parse_concurrency_accepts_only_1_to_50() {
+        assert_eq!(parse_concurrency("1").unwrap(), 1);
+        assert_eq!(parse_concurrency("50").unwrap(), 50);
+        assert!(parse_concurrency("0").is_err());
+        assert!(parse_concurrency("51").is_err());
+    }
+
     #[test]
     fn parse_max_retries_accepts_valid_range() {
         assert_eq!(parse_max_retries("0").unwrap(), 0);
@@ -297,4 +1218,137 @@ mod tests {
     fn parse_max_retries_rejects_non_numeric() {
         assert!(parse_max_retries("abc").is_err());
     }
+
+    #[test]
+    fn parse_max_asset_bytes_rejects_zero() {
+        assert_eq!(parse_max_asset_bytes("1048576").unwrap(), 1_048_576);
+        assert!(parse_max_asset_bytes("0").is_err());
+        assert!(parse_max_asset_bytes("1MB").is_err());
+    }
+
+    #[test]
+    fn parse_asset_regex_rejects_invalid_pattern() {
+        assert!(parse_asset_regex(r"^package-.*\.json$").is_ok());
+        assert!(parse_asset_regex("package-(").is_err());
+    }
+
+    #[test]
+    fn parse_asset_timeout_accepts_valid_range() {
+        assert_eq!(parse_asset_timeout("1").unwrap(), 1);
+        assert_eq!(parse_asset_timeout("3600").unwrap(), 3600);
+    }
+
+    #[test]
+    fn parse_asset_timeout_rejects_out_of_range() {
+        assert!(parse_asset_timeout("0").is_err());
+        assert!(parse_asset_timeout("3601").is_err());
+    }
+
+    #[test]
+    fn parse_timeout_accepts_valid_range() {
+        assert_eq!(parse_timeout("1").unwrap(), 1);
+        assert_eq!(parse_timeout("300").unwrap(), 300);
+    }
+
+    #[test]
+    fn parse_timeout_rejects_out_of_range() {
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("301").is_err());
+        assert!(parse_timeout("2m").is_err());
+    }
+
+    #[test]
+    fn parse_host_retries_accepts_host_and_count() {
+        assert_eq!(
+            parse_host_retries("CDN.example.com=1").unwrap(),
+            HostRetries {
+                host: "cdn.example.com".to_string(),
+                max_retries: 1,
+            }
+        );
+        assert!(parse_host_retries("cdn.example.com").is_err());
+        assert!(parse_host_retries("=1").is_err());
+        assert!(parse_host_retries("cdn.example.com=9").is_err());
+    }
+
+    #[test]
+    fn github_app_flags_must_be_given_together() {
+        let partial = Cli::try_parse_from(["voy", "fetch", "--github-app-id", "1"]);
+        assert!(partial.is_err());
+
+        let cli = Cli::try_parse_from([
+            "voy",
+            "fetch",
+            "--github-app-id",
+            "1",
+            "--github-installation-id",
+            "2",
+            "--github-app-key",
+            "app.pem",
+        ])
+        .unwrap();
+        let Commands::Fetch(args) = cli.command else {
+            panic!("expected fetch");
+        };
+        assert!(matches!(
+            args.github_auth.auth().unwrap(),
+            GitHubAuth::App(_)
+        ));
+    }
+
+    #[test]
+    fn github_token_file_is_read_trimmed_and_excludes_token_flag() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let token_path = dir.path().join("token");
+        std::fs::write(&token_path, "ghp_secret\n").unwrap();
+        let token_arg = token_path.to_str().unwrap();
+
+        let conflicting = Cli::try_parse_from([
+            "voy",
+            "fetch",
+            "--github-token",
+            "other",
+            "--github-token-file",
+            token_arg,
+        ]);
+        assert!(conflicting.is_err());
+
+        let cli = Cli::try_parse_from(["voy", "fetch", "--github-token-file", token_arg]).unwrap();
+        let Commands::Fetch(args) = cli.command else {
+            panic!("expected fetch");
+        };
+        assert_eq!(
+            args.github_auth.auth().unwrap(),
+            GitHubAuth::Token("ghp_secret".to_string())
+        );
+
+        let missing = GitHubAuthArgs {
+            github_token_file: Some(dir.path().join("missing")),
+            ..Default::default()
+        };
+        assert!(matches!(missing.auth(), Err(Error::FileRead { .. })));
+    }
+
+    #[test]
+    fn parse_lock_path_rejects_transaction_log_extension() {
+        assert_eq!(
+            parse_lock_path("indexes/a.lock").unwrap(),
+            PathBuf::from("indexes/a.lock")
+        );
+        assert!(parse_lock_path("a.txn").is_err());
+        assert!(parse_lock_path("..").is_err());
+    }
+
+    #[test]
+    fn parse_lock_ext_strips_leading_dot() {
+        assert_eq!(parse_lock_ext(".lock.toml").unwrap(), "lock.toml");
+    }
+
+    #[test]
+    fn parse_lock_ext_rejects_clashing_or_invalid_values() {
+        assert!(parse_lock_ext("").is_err());
+        assert!(parse_lock_ext("toml").is_err());
+        assert!(parse_lock_ext("txn").is_err());
+        assert!(parse_lock_ext("a/b").is_err());
+    }
 }
@@ -1,6 +1,9 @@
 mod args;
 
 pub use args::{
-    AddArgs, Cli, ColorChoice, Commands, CompletionsArgs, ConfigPaths, DEFAULT_CONFIG_FILE,
-    FetchArgs, GenerateArgs, InfoArgs, InitArgs, ListArgs, LockArgs, RemoveArgs, ValidateArgs,
+    AddArgs, AssetMatchArg, Cli, ColorChoice, Commands, CompletionsArgs, ConfigFormatArg,
+    ConfigPaths, DEFAULT_CONFIG_FILE, DiffArgs, ErrorFormat, FetchArgs, GenerateArgs,
+    GitHubAuthArgs, HashAlgorithmArg, HashFormat, InfoArgs, InitArgs, ListArgs, LockArgs,
+    MigrateArgs, OnFailure, OutdatedArgs, OutputFormat, PackageOrderArg, PruneArgs, RemoveArgs,
+    UrlCheckMethodArg, UrlsArgs, ValidateArgs, VerifyArgs, VersionOrderArg,
 };
@@ -1,22 +1,60 @@
-use crate::cli::AddArgs;
+use crate::cli::{AddArgs, OutputFormat};
 use crate::config::{Package, validation};
 use crate::context::AppContext;
 use crate::domain::Repository;
 use crate::error::{Error, Result};
 use crate::infra::GitHubApi;
-use crate::lock::compute_manifest_hash_from_manifest;
-use crate::services::{check_and_load, save_manifest_and_lock};
+use crate::lock::compute_manifest_hash_with;
+use crate::services::{VerifiedRepositories, check_and_load, verified_repositories_path};
 use crate::term;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Release asset read by `--infer-id`.
+const INFER_ID_ASSET: &str = "package.json";
+/// Retries for downloading that asset.
+const INFER_ID_RETRIES: u32 = 3;
+
+/// The package recorded by a successful `add`.
+#[derive(Debug, Serialize)]
+pub struct AddOutcome {
+    pub id: String,
+    pub repository: String,
+    /// Whether the repository was checked on GitHub before adding it.
+    pub verified: bool,
+}
 
 pub async fn execute<G: GitHubApi>(args: AddArgs, ctx: &AppContext<G>) -> Result<()> {
+    let format = args.format;
+    let outcome = add_package(args, ctx).await?;
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&outcome).map_err(Error::JsonSerialize)?;
+            println!("{}", json);
+        }
+        OutputFormat::Text => {
+            term::success(format!("Added {} ({})", outcome.id, outcome.repository));
+            term::blank();
+            term::hint("Next: voy fetch");
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds the package to the manifest and lockfile without printing anything.
+pub async fn add_package<G: GitHubApi>(args: AddArgs, ctx: &AppContext<G>) -> Result<AddOutcome> {
     let config_path = ctx.paths.config_path();
     let lock_path = ctx.paths.lock_path();
     let repo = Repository::parse(&args.repository)
         .map_err(|e| Error::InvalidRepository(e.input().to_string()))?;
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    let lock_writer = ctx.paths.lock_writer()?;
+    let check_result = check_and_load(config_path, lock_path, ctx.paths.config_format())?;
     let mut manifest = check_result.manifest;
     let mut lockfile = check_result.lockfile;
+    let github = ctx.github(&manifest.registry)?;
 
     let package_id = match args.id {
         Some(id) => {
@@ -24,6 +62,15 @@ pub async fn execute<G: GitHubApi>(args: AddArgs, ctx: &AppContext<G>) -> Result
             validation::validate_package_id_prefix(&id, &manifest.vpm.id)?;
             id
         }
+        None if args.infer_id => {
+            let spinner = term::spinner("Reading package.json of the latest release...");
+            let inferred = infer_package_id(github.as_ref(), &repo).await;
+            spinner.finish_and_clear();
+            let id = inferred?;
+            validation::validate_package_id_prefix(&id, &manifest.vpm.id)?;
+            validation::validate_reverse_domain(&id)?;
+            id
+        }
         None => {
             let repo_name = repo.repo.to_lowercase().replace('-', "_");
             format!("{}.{}", manifest.vpm.id, repo_name)
@@ -38,23 +85,88 @@ pub async fn execute<G: GitHubApi>(args: AddArgs, ctx: &AppContext<G>) -> Result
         )));
     }
 
-    let spinner = term::spinner("Verifying repository...");
-    let verify_result = ctx.github.verify_repository(&repo).await;
-    spinner.finish_and_clear();
-    verify_result?;
+    let verified = !args.skip_verify;
+    if verified {
+        verify_repository(github.as_ref(), config_path, &repo).await?;
+    } else {
+        term::warning(format!(
+            "Skipped verifying {}; 'voy fetch' will fail if it does not exist",
+            repo
+        ));
+    }
 
     manifest.packages.push(Package {
         id: package_id.clone(),
         repository: repo.clone(),
+        suffix: None,
+        tags: Vec::new(),
+        versions: Vec::new(),
+        version_range: None,
+        asset_name: None,
     });
 
-    let new_hash = compute_manifest_hash_from_manifest(&manifest, config_path)?;
+    let new_hash =
+        compute_manifest_hash_with(&manifest, config_path, lockfile.manifest_hash_algorithm())?;
     lockfile.manifest_hash = Some(new_hash);
-    save_manifest_and_lock(&manifest, &lockfile, config_path, lock_path)?;
+    lock_writer.save_manifest_and_lock(&manifest, &lockfile)?;
 
-    term::success(format!("Added {} ({})", package_id, repo));
-    term::blank();
-    term::hint("Next: voy fetch");
+    Ok(AddOutcome {
+        id: package_id,
+        repository: repo.to_string(),
+        verified,
+    })
+}
 
-    Ok(())
+/// Checks that `repo` exists unless it was verified recently.
+async fn verify_repository<G: GitHubApi>(
+    github: &G,
+    config_path: &Path,
+    repo: &Repository,
+) -> Result<()> {
+    let cache_path = verified_repositories_path(config_path);
+    let mut verified = VerifiedRepositories::load(&cache_path)?;
+    if verified.is_fresh(repo) {
+        return Ok(());
+    }
+
+    let spinner = term::spinner("Verifying repository...");
+    let verify_result = github.verify_repository(repo).await;
+    spinner.finish_and_clear();
+    verify_result?;
+
+    verified.record(repo);
+    verified.save(&cache_path)
+}
+
+/// Reads the package ID from the `name` of the newest release's package.json.
+async fn infer_package_id<G: GitHubApi>(github: &G, repo: &Repository) -> Result<String> {
+    let releases = github.get_releases(repo, INFER_ID_ASSET).await?;
+    let Some(latest) = releases.into_iter().find(|r| r.asset_url().is_some()) else {
+        return Err(Error::ConfigValidation(format!(
+            "No release of '{}' has a {} to infer the package ID from; pass --id",
+            repo, INFER_ID_ASSET
+        )));
+    };
+    let source = latest.asset_url().unwrap_or_default().to_string();
+
+    let content = github
+        .download_assets(vec![latest], 1, INFER_ID_RETRIES, None)
+        .await
+        .into_iter()
+        .next()
+        .map(|(_, result)| result)
+        .unwrap_or_else(|| {
+            Err(Error::ConfigValidation(format!(
+                "Downloading {} returned no result",
+                source
+            )))
+        })?;
+    let package: PackageName =
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse { source, error: e })?;
+    Ok(package.name)
+}
+
+#[derive(Deserialize)]
+struct PackageName {
+    name: String,
 }
@@ -0,0 +1,95 @@
+use crate::cli::{ConfigPaths, DiffArgs, OutputFormat};
+use crate::error::{Error, Result};
+use crate::infra::{HttpApi, read_json};
+use crate::output::VpmOutput;
+use crate::services::{
+    IndexDiff, PackageChange, check_and_load, diff_indexes, generate_from_lockfile,
+};
+use crate::term;
+use std::sync::Arc;
+use tracing::info;
+
+pub async fn execute<H: HttpApi>(args: DiffArgs, paths: &ConfigPaths, http: Arc<H>) -> Result<()> {
+    let (old_source, new_source) = match (args.against.as_deref(), args.indexes.as_slice()) {
+        (None, [old, new]) => (old.as_str(), Some(new.as_str())),
+        (Some(against), [new]) => (against, Some(new.as_str())),
+        (Some(against), []) => (against, None),
+        _ => {
+            return Err(Error::ConfigValidation(
+                "Pass two index paths, or --against with at most one index path".to_string(),
+            ));
+        }
+    };
+
+    let old = load_index(old_source, http.as_ref()).await?;
+    info!(old = %old_source, "Loaded old index");
+
+    let new = match new_source {
+        Some(source) => load_index(source, http.as_ref()).await?,
+        None => {
+            let check_result = check_and_load(
+                paths.config_path(),
+                paths.lock_path(),
+                paths.config_format(),
+            )?;
+            generate_from_lockfile(&check_result.manifest, &check_result.lockfile)?
+        }
+    };
+
+    let diff = diff_indexes(&old, &new);
+
+    match args.format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&diff).map_err(Error::JsonSerialize)?;
+            println!("{}", json);
+        }
+        OutputFormat::Text => print_diff(&diff, old_source),
+    }
+
+    // Only comparing two index files gates on differences; an --against
+    // preview reports them and succeeds.
+    if diff.is_empty() || args.against.is_some() {
+        return Ok(());
+    }
+    Err(Error::IndexesDiffer {
+        count: diff.packages.len(),
+    })
+}
+
+/// Loads an index from an `http(s)://` URL or a local path.
+async fn load_index<H: HttpApi>(source: &str, http: &H) -> Result<VpmOutput> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let content = http.fetch_text(source).await?;
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse {
+            source: source.to_string(),
+            error: e,
+        })
+    } else {
+        read_json(source)
+    }
+}
+
+fn print_diff(diff: &IndexDiff, against: &str) {
+    if diff.is_empty() {
+        term::success(format!("No differences from {}", against));
+        return;
+    }
+
+    for package in &diff.packages {
+        let label = match package.change {
+            PackageChange::Added => " (new package)",
+            PackageChange::Removed => " (removed package)",
+            PackageChange::Modified => "",
+        };
+        term::line(format!("{}{}", term::bold(&package.id), term::dim(label)));
+        for version in &package.added {
+            term::indent(1, term::green(format!("+ {}", version)));
+        }
+        for version in &package.removed {
+            term::indent(1, term::red(format!("- {}", version)));
+        }
+        for version in &package.changed {
+            term::indent(1, term::yellow(format!("~ {}", version)));
+        }
+    }
+}
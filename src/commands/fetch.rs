@@ -1,15 +1,31 @@
-use crate::cli::FetchArgs;
+use crate::cli::{FetchArgs, OnFailure, OutputFormat};
+use crate::commands::package_not_found_error;
 use crate::context::AppContext;
-use crate::error::Result;
-use crate::infra::GitHubApi;
-use crate::services::{FetchProgressReporter, FetcherConfig, PackageFetcher, check_and_load};
+use crate::error::{Error, Result};
+use crate::infra::{GitHubApi, UrlRewrite};
+use crate::lock::{Lockfile, format_utc};
+use crate::services::{
+    FetchCheckpoint, FetchProgressReporter, FetchSummary, FetchSummaryReporter,
+    FetchedRepositories, FetcherConfig, GitDiffSource, PackageFetcher, PackagePlan, check_and_load,
+    checkpoint_path, fetched_repositories_path, packages_changed_since,
+};
 use crate::term;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use tracing::info;
 
+/// Machine-readable result of `voy fetch --dry-run --format json`.
+#[derive(Serialize)]
+struct DryRunReport<'a> {
+    packages: &'a [PackagePlan],
+    summary: &'a FetchSummary,
+}
+
 struct TerminalFetchReporter {
     progress: term::FetchProgress,
     indices: HashMap<String, usize>,
+    summary: FetchSummaryReporter,
 }
 
 impl TerminalFetchReporter {
@@ -22,6 +38,7 @@ impl TerminalFetchReporter {
         Self {
             progress: term::FetchProgress::new(package_ids),
             indices,
+            summary: FetchSummaryReporter::default(),
         }
     }
 
@@ -48,27 +65,58 @@ impl FetchProgressReporter for TerminalFetchReporter {
     }
 
     fn on_done(&self, package_id: &str, existing: usize, new: usize) {
+        self.summary.on_done(package_id, existing, new);
         if let Some(&index) = self.indices.get(package_id) {
             self.progress.set_done(index, package_id, existing, new);
         }
     }
+
+    fn on_failed(&self, package_id: &str, failed: usize) {
+        self.summary.on_failed(package_id, failed);
+    }
+
+    fn on_retrying(&self, package_id: &str, attempt: u32) {
+        if let Some(&index) = self.indices.get(package_id) {
+            self.progress.set_retrying(index, package_id, attempt);
+        }
+    }
 }
 
 pub async fn execute<G: GitHubApi>(args: FetchArgs, ctx: &AppContext<G>) -> Result<()> {
     let config_path = ctx.paths.config_path();
     let lock_path = ctx.paths.lock_path();
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    // A dry run writes nothing, so it neither waits for nor blocks other writers.
+    let lock_writer = if args.dry_run {
+        None
+    } else {
+        Some(ctx.paths.lock_writer()?)
+    };
+    let check_result = check_and_load(config_path, lock_path, ctx.paths.config_format())?;
     let manifest = check_result.manifest;
     let mut lockfile = check_result.lockfile;
     let current_hash = check_result.current_hash;
+    let github = ctx.github(&manifest.registry)?;
+    // JSON output owns stdout, so human status lines are skipped.
+    let text_output = args.format == OutputFormat::Text;
+
+    if let Some(unknown) = args
+        .only
+        .iter()
+        .find(|id| !manifest.packages.iter().any(|pkg| &pkg.id == *id))
+    {
+        return Err(package_not_found_error(unknown, config_path));
+    }
 
     if args.wipe {
         info!("Wiping all cached versions");
-        for pkg in &mut lockfile.packages {
+        // Retained packages are no longer fetched, so wiping would lose them.
+        for pkg in lockfile.packages.iter_mut().filter(|pkg| !pkg.retained) {
             pkg.versions.clear();
         }
-        term::status("Cleared all cached versions");
+        if text_output {
+            term::status("Cleared all cached versions");
+        }
     }
 
     info!(
@@ -78,6 +126,11 @@ pub async fn execute<G: GitHubApi>(args: FetchArgs, ctx: &AppContext<G>) -> Resu
         max_concurrent = args.max_concurrent,
         max_retries = args.max_retries,
         asset_name = %args.asset_name,
+        only_missing = args.only_missing,
+        only_changed_since = args.only_changed_since.as_deref(),
+        only_new_repos = args.only_new_repos,
+        resume = args.resume,
+        strip_build_metadata = args.strip_build_metadata,
         "Starting fetch"
     );
 
@@ -86,28 +139,159 @@ pub async fn execute<G: GitHubApi>(args: FetchArgs, ctx: &AppContext<G>) -> Resu
         "Loaded existing lock file"
     );
 
-    let package_ids: Vec<String> = manifest.packages.iter().map(|p| p.id.clone()).collect();
-    let reporter = TerminalFetchReporter::new(&package_ids);
+    let only_packages = match &args.only_changed_since {
+        Some(git_ref) => match packages_changed_since(&GitDiffSource, config_path, git_ref) {
+            Ok(changed) => {
+                if text_output {
+                    term::status(format!(
+                        "{} package(s) changed since {}",
+                        changed.len(),
+                        git_ref
+                    ));
+                }
+                Some(changed)
+            }
+            Err(e) => {
+                term::warning(format!(
+                    "Could not determine changes since '{}' ({}); fetching all packages",
+                    git_ref, e
+                ));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let fetched_path = fetched_repositories_path(config_path);
+    let mut fetched_repositories = FetchedRepositories::load(&fetched_path)?;
+    let only_packages = if args.only_new_repos {
+        let new_packages = fetched_repositories.new_packages(&manifest);
+        if text_output {
+            term::status(format!(
+                "{} package(s) with repositories not fetched before",
+                new_packages.len()
+            ));
+        }
+        Some(match only_packages {
+            Some(changed) => changed.intersection(&new_packages).cloned().collect(),
+            None => new_packages,
+        })
+    } else {
+        only_packages
+    };
+    let only_packages = if args.only.is_empty() {
+        only_packages
+    } else {
+        let selected: HashSet<String> = args.only.iter().cloned().collect();
+        Some(match only_packages {
+            Some(filtered) => filtered.intersection(&selected).cloned().collect(),
+            None => selected,
+        })
+    };
 
+    let checkpoint_path = checkpoint_path(config_path);
     let fetcher = PackageFetcher::new(
-        ctx.github.clone(),
+        github.clone(),
         FetcherConfig {
             max_concurrent: args.max_concurrent,
+            package_concurrency: args.package_concurrency,
+            download_concurrency: args.download_concurrency,
             max_retries: args.max_retries,
             asset_name: args.asset_name,
+            only_missing: args.only_missing,
+            only_packages,
+            // A dry run must not leave state behind, so nothing is checkpointed.
+            checkpoint_path: (!args.dry_run).then(|| checkpoint_path.clone()),
+            resume: args.resume,
+            strip_build_metadata: args.strip_build_metadata,
+            allowed_dependency_prefixes: args.allow_dep_prefixes,
+            min_unity: args.min_unity,
+            require_unity: args.require_unity,
+            verify_zip: args.verify_zip,
+            store_raw: args.store_raw,
+            strict_keywords: args.strict_keywords,
+            hash_algorithm: args.hash_algo.into(),
+            strict_url: args.strict_url,
+            strict: args.strict,
+            no_prerelease: args.no_prerelease,
+            keep_existing: args.keep_existing,
         },
     );
 
+    let package_ids: Vec<String> = manifest.packages.iter().map(|p| p.id.clone()).collect();
+    let reporter = TerminalFetchReporter::new(&package_ids);
+    let locked_before = lock_writer.is_none().then(|| lockfile.clone());
+    let selected_packages = fetcher.selected_packages(&manifest, &lockfile);
+
     let fetch_result = fetcher
         .fetch(&manifest, &mut lockfile, Some(&reporter))
         .await;
     reporter.finish();
-    fetch_result?;
-
+    github.save_release_cache();
+    let Some(lock_writer) = lock_writer else {
+        if let Some(locked_before) = locked_before
+            && matches!(
+                fetch_result,
+                Ok(()) | Err(Error::FetchPartialFailure { .. })
+            )
+        {
+            let changes = lockfile_changes(&locked_before, &lockfile);
+            let summary = reporter.summary.summary();
+            if text_output {
+                print_dry_run(&changes, &summary, lock_path);
+            } else {
+                let report = DryRunReport {
+                    packages: &changes,
+                    summary: &summary,
+                };
+                let json = serde_json::to_string_pretty(&report).map_err(Error::JsonSerialize)?;
+                println!("{}", json);
+            }
+        }
+        return fetch_result;
+    };
+    if args.persist_rewrite {
+        persist_url_rewrite(
+            &mut lockfile,
+            &UrlRewrite::new(manifest.registry.url_rewrite.clone()),
+        );
+    }
+    if !text_output
+        && matches!(
+            fetch_result,
+            Ok(()) | Err(Error::FetchPartialFailure { .. })
+        )
+    {
+        let summary = reporter.summary.summary();
+        let json = serde_json::to_string_pretty(&summary).map_err(Error::JsonSerialize)?;
+        println!("{}", json);
+    }
     lockfile.manifest_hash = Some(current_hash);
-    lockfile.save(lock_path)?;
+
+    let kept_failure = match fetch_result {
+        Ok(()) => None,
+        Err(Error::FetchPartialFailure { failures, .. }) if args.on_failure == OnFailure::Keep => {
+            Some(Error::FetchPartialFailure {
+                failures,
+                lock_saved: true,
+            })
+        }
+        Err(e) => return Err(e),
+    };
+
+    lock_writer.save_lock(&lockfile)?;
     info!(path = %lock_path.display(), "Lock file saved");
+    // The saved lock file holds every package the checkpoint would resume.
+    FetchCheckpoint::remove(&checkpoint_path)?;
+    if let Some(error) = kept_failure {
+        return Err(error);
+    }
+    fetched_repositories.record(&manifest, &selected_packages);
+    fetched_repositories.save(&fetched_path)?;
 
+    if !text_output {
+        return Ok(());
+    }
     let total_versions: usize = lockfile.packages.iter().map(|p| p.versions.len()).sum();
     term::success(format!(
         "Fetched {} package(s), {} version(s)",
@@ -115,8 +299,100 @@ pub async fn execute<G: GitHubApi>(args: FetchArgs, ctx: &AppContext<G>) -> Resu
         total_versions
     ));
     term::info(format!("Saved {}", lock_path.display()));
+    if let Some(rate_limit) = github.rate_limit() {
+        term::info(format!(
+            "GitHub API: {} call(s) remaining, resets at {}",
+            rate_limit.remaining,
+            format_utc(rate_limit.reset)
+        ));
+    }
     term::blank();
     term::hint("Next: voy generate");
 
     Ok(())
 }
+
+/// Replaces locked asset URLs with their rewritten form.
+fn persist_url_rewrite(lockfile: &mut Lockfile, url_rewrite: &UrlRewrite) {
+    for version in lockfile
+        .packages
+        .iter_mut()
+        .flat_map(|pkg| pkg.versions.iter_mut())
+    {
+        version.url = url_rewrite.apply(&version.url).into_owned();
+    }
+}
+
+/// Compares the lock file before and after an in-memory fetch, package by
+/// package, including packages the fetch pruned entirely.
+fn lockfile_changes(before: &Lockfile, after: &Lockfile) -> Vec<PackagePlan> {
+    let pruned_packages = before
+        .packages
+        .iter()
+        .filter(|pkg| after.get_package(&pkg.id).is_none());
+    after
+        .packages
+        .iter()
+        .chain(pruned_packages)
+        .map(|pkg| {
+            let versions = |lockfile: &Lockfile| -> Vec<String> {
+                lockfile
+                    .get_package(&pkg.id)
+                    .map(|locked| locked.versions.iter().map(|v| v.version.clone()).collect())
+                    .unwrap_or_default()
+            };
+            let old = versions(before);
+            let new = versions(after);
+            let (kept, added): (Vec<_>, Vec<_>) = new.into_iter().partition(|v| old.contains(v));
+            PackagePlan {
+                package_id: pkg.id.clone(),
+                added,
+                pruned: old.into_iter().filter(|v| !kept.contains(v)).collect(),
+                kept,
+            }
+        })
+        .collect()
+}
+
+fn print_dry_run(changes: &[PackagePlan], summary: &FetchSummary, lock_path: &Path) {
+    let failed_count = |package_id: &str| {
+        summary
+            .packages
+            .iter()
+            .find(|pkg| pkg.package_id == package_id)
+            .map_or(0, |pkg| pkg.failed)
+    };
+
+    for change in changes {
+        term::line(format!(
+            "{} {}",
+            term::bold(&change.package_id),
+            term::dim(format!("({} kept)", change.kept.len()))
+        ));
+        for version in &change.added {
+            term::indent(1, term::green(format!("+ {}", version)));
+        }
+        for version in &change.pruned {
+            term::indent(1, term::red(format!("- {}", version)));
+        }
+        let failed = failed_count(&change.package_id);
+        if failed > 0 {
+            term::indent(1, term::yellow(format!("! {} version(s) failed", failed)));
+        }
+    }
+
+    let added: usize = changes.iter().map(|c| c.added.len()).sum();
+    let pruned: usize = changes.iter().map(|c| c.pruned.len()).sum();
+    term::blank();
+    if changes.iter().any(PackagePlan::has_changes) || summary.total.failed > 0 {
+        term::status(format!(
+            "Dry run: {} version(s) to add, {} to prune, {} failed; {} was not written",
+            term::green(added),
+            term::red(pruned),
+            term::yellow(summary.total.failed),
+            lock_path.display()
+        ));
+    } else {
+        term::success(format!("Dry run: {} is up to date", lock_path.display()));
+    }
+}
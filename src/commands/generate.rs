@@ -1,15 +1,39 @@
-use crate::cli::{ConfigPaths, GenerateArgs};
+use crate::cli::{ConfigPaths, GenerateArgs, HashFormat};
 use crate::error::{Error, Result};
-use crate::infra::write_json;
-use crate::services::{check_and_load, generate_from_lockfile};
+use crate::infra::{FileSink, OutputSink};
+use crate::lock::compute_hash;
+use crate::services::{
+    DependencyGraph, GenerateOptions, asset_list, check_and_load, duplicate_display_name_warning,
+    generate_from_lockfile_with, index_size_warning, serialize_index,
+};
 use crate::term;
-use tracing::info;
+use std::io::Write;
+use tracing::{info, warn};
 
 pub fn execute(args: GenerateArgs, paths: &ConfigPaths) -> Result<()> {
+    execute_with_sink(args, paths, &FileSink)
+}
+
+/// Runs `generate`, writing the index and any checksum sidecar through `sink`.
+///
+/// Generation reads only the manifest and lock file. `args.offline` is the
+/// gate for any network step added here: such a step must be skipped when it
+/// is set, so air-gapped publish steps can rely on `--offline`.
+pub fn execute_with_sink<S: OutputSink>(
+    args: GenerateArgs,
+    paths: &ConfigPaths,
+    sink: &S,
+) -> Result<()> {
     let config_path = paths.config_path();
     let lock_path = paths.lock_path();
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    if args.writes_to_stdout() && matches!(args.output_hash_file, Some(None)) {
+        return Err(Error::ConfigValidation(
+            "--output-hash-file needs a path when the index is written to stdout".to_string(),
+        ));
+    }
+
+    let check_result = check_and_load(config_path, lock_path, paths.config_format())?;
     let manifest = check_result.manifest;
     let lockfile = check_result.lockfile;
 
@@ -31,19 +55,93 @@ pub fn execute(args: GenerateArgs, paths: &ConfigPaths) -> Result<()> {
         lock = %lock_path.display(),
         output = %args.output.display(),
         packages = manifest.packages.len(),
+        offline = args.offline,
         "Starting index generation"
     );
 
     let spinner = term::spinner("Generating index...");
 
-    let output = generate_from_lockfile(&manifest, &lockfile)?;
+    if let Err(e) = DependencyGraph::from_lockfile(&lockfile).check_acyclic() {
+        if args.strict {
+            spinner.finish_and_clear();
+            return Err(e);
+        }
+        warn!(error = %e, "Locked packages have a dependency cycle");
+        spinner.suspend(|| term::warning(e.to_string()));
+    }
+
+    let options = GenerateOptions {
+        package_order: args.sort.into(),
+        version_order: args.sort_versions.into(),
+        min_versions: args.min_versions,
+        tag: args.tag.clone(),
+        omit_empty_packages: !args.emit_empty_packages,
+        reject_duplicate_display_names: args.no_duplicate_display_names,
+        drop_extra: args.drop_extra,
+        emit_metadata: args.emit_metadata,
+        redact_email: args.redact_email,
+    };
+    let output = generate_from_lockfile_with(&manifest, &lockfile, &options)?;
+    if let Some(message) = duplicate_display_name_warning(&output) {
+        warn!(%message, "Packages share a display name");
+        spinner.suspend(|| term::warning(&message));
+    }
 
-    write_json(&args.output, &output)?;
+    let json = serialize_index(&output, args.sort_keys, args.minify)?;
+    if let Some(message) = index_size_warning(&json, args.max_index_size) {
+        if args.strict {
+            spinner.finish_and_clear();
+            return Err(Error::ConfigValidation(message));
+        }
+        warn!(
+            size = json.len(),
+            max = args.max_index_size,
+            "Generated index is large"
+        );
+        spinner.suspend(|| term::warning(&message));
+    }
+    if args.writes_to_stdout() {
+        write_stdout(&json)?;
+    } else {
+        sink.write(&args.output, &json)?;
+    }
     info!(path = %args.output.display(), "Output written successfully");
 
+    if let Some(hash_path) = args.output_hash_path() {
+        let hash = compute_hash(&json);
+        let hash = match args.hash_format {
+            HashFormat::Prefixed => hash.as_str(),
+            HashFormat::Hex => hash.trim_start_matches("sha256:"),
+        };
+        sink.write(&hash_path, &format!("{hash}\n"))?;
+        info!(path = %hash_path.display(), "Checksum written successfully");
+    }
+
+    if let Some(assets_path) = &args.assets {
+        let assets = asset_list(&lockfile);
+        let json = serde_json::to_string_pretty(&assets).map_err(Error::JsonSerialize)?;
+        sink.write(assets_path, &json)?;
+        info!(path = %assets_path.display(), count = assets.len(), "Asset list written successfully");
+    }
+
     spinner.finish_and_clear();
 
-    term::success(format!("Generated {}", args.output.display()));
+    if args.writes_to_stdout() {
+        term::success_stderr("Generated index to stdout");
+    } else {
+        term::success(format!("Generated {}", args.output.display()));
+    }
 
     Ok(())
 }
+
+fn write_stdout(json: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(json.as_bytes())
+        .and_then(|()| stdout.flush())
+        .map_err(|e| Error::OutputWrite {
+            path: "<stdout>".to_string(),
+            source: e,
+        })
+}
@@ -1,14 +1,15 @@
-use crate::cli::{ConfigPaths, InfoArgs};
+use crate::cli::{ConfigPaths, InfoArgs, OutputFormat};
 use crate::commands::{package_not_found_error, print_no_versions_fetched_hint};
-use crate::error::Result;
-use crate::services::check_and_load;
+use crate::error::{Error, Result};
+use crate::lock::LockedPackage;
+use crate::services::{DependencyDiff, ManifestDiff, check_and_load, diff_manifests};
 use crate::term;
 
 pub fn execute(args: InfoArgs, paths: &ConfigPaths) -> Result<()> {
     let config_path = paths.config_path();
     let lock_path = paths.lock_path();
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    let check_result = check_and_load(config_path, lock_path, paths.config_format())?;
     let manifest = check_result.manifest;
     let lockfile = check_result.lockfile;
 
@@ -20,6 +21,27 @@ pub fn execute(args: InfoArgs, paths: &ConfigPaths) -> Result<()> {
 
     let locked_package = lockfile.get_package(&args.package_id);
 
+    if let Some(versions) = &args.compare {
+        let diff = compare_versions(locked_package, &args.package_id, &versions[0], &versions[1])?;
+        match args.format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&diff).map_err(Error::JsonSerialize)?;
+                println!("{}", json);
+            }
+            OutputFormat::Text => print_manifest_diff(&package.id, &diff),
+        }
+        return Ok(());
+    }
+
+    if args.raw {
+        let versions = locked_package
+            .map(|p| p.versions.as_slice())
+            .unwrap_or_default();
+        let json = serde_json::to_string_pretty(versions).map_err(Error::JsonSerialize)?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     term::blank();
     term::line(format!("  {}", term::bold(&package.id)));
     term::line(format!("  {}", term::dim(&package.repository)));
@@ -88,6 +110,79 @@ pub fn execute(args: InfoArgs, paths: &ConfigPaths) -> Result<()> {
     Ok(())
 }
 
+fn compare_versions(
+    locked_package: Option<&LockedPackage>,
+    package_id: &str,
+    old: &str,
+    new: &str,
+) -> Result<ManifestDiff> {
+    let manifest_of = |version: &str| {
+        locked_package
+            .and_then(|p| p.get_version(version))
+            .map(|v| &v.manifest)
+            .ok_or_else(|| {
+                Error::ConfigValidation(format!(
+                    "Version '{}' of '{}' is not in the lockfile",
+                    version, package_id
+                ))
+            })
+    };
+    Ok(diff_manifests(manifest_of(old)?, manifest_of(new)?))
+}
+
+fn print_manifest_diff(package_id: &str, diff: &ManifestDiff) {
+    term::blank();
+    term::line(format!(
+        "  {}  {}",
+        term::bold(package_id),
+        term::dim(format!("{} -> {}", diff.from, diff.to))
+    ));
+    term::blank();
+
+    if diff.is_empty() {
+        term::success("No metadata changes");
+        term::blank();
+        return;
+    }
+
+    for change in &diff.fields {
+        let show = |value: &Option<serde_json::Value>| match value {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+            None => "(none)".to_string(),
+        };
+        print_field(
+            &change.field,
+            &format!("{} -> {}", show(&change.old), show(&change.new)),
+        );
+    }
+
+    print_dependency_diff("VPM Dependencies", &diff.vpm_dependencies);
+    print_dependency_diff("Unity Dependencies", &diff.dependencies);
+    term::blank();
+}
+
+fn print_dependency_diff(label: &str, diff: &DependencyDiff) {
+    if diff.is_empty() {
+        return;
+    }
+
+    term::blank();
+    term::line(format!("  {}", term::bold(label)));
+    for (dep, version) in &diff.added {
+        term::indent(2, term::green(format!("+ {}  {}", dep, version)));
+    }
+    for (dep, version) in &diff.removed {
+        term::indent(2, term::red(format!("- {}  {}", dep, version)));
+    }
+    for change in &diff.changed {
+        term::indent(
+            2,
+            format!("~ {}  {} -> {}", change.name, change.old, change.new),
+        );
+    }
+}
+
 fn print_field(label: &str, value: &str) {
     term::line(format!("  {:14}  {}", term::dim(label), value));
 }
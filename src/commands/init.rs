@@ -2,13 +2,26 @@ use crate::cli::{ConfigPaths, InitArgs};
 use crate::config::{Manifest, Vpm, validation};
 use crate::error::{Error, Result};
 use crate::lock::{Lockfile, compute_manifest_hash_from_manifest};
-use crate::services::save_manifest_and_lock;
+use crate::services::transaction_path;
 use crate::term;
+use std::io::IsTerminal;
 
 pub fn execute(args: InitArgs, paths: &ConfigPaths) -> Result<()> {
     let output_path = paths.config_path();
     let lock_path = paths.lock_path();
 
+    let can_prompt = std::io::stdin().is_terminal();
+    if !can_prompt {
+        let missing = missing_flags(&args);
+        if !missing.is_empty() {
+            return Err(Error::ConfigValidation(format!(
+                "stdin is not a terminal; pass {}",
+                missing.join(", ")
+            )));
+        }
+    }
+    let prompt_all = args.interactive && can_prompt;
+
     cliclack::intro("voy init")?;
 
     if output_path.exists() && !args.force {
@@ -25,57 +38,45 @@ pub fn execute(args: InitArgs, paths: &ConfigPaths) -> Result<()> {
         }
     }
 
-    let name: String = match args.name {
-        Some(n) => n,
-        None => cliclack::input("VPM name")
-            .placeholder("My Awesome VPM")
-            .interact()?,
-    };
-
-    let id: String = match args.id {
-        Some(i) => {
-            validation::validate_reverse_domain(&i)?;
-            i
-        }
-        None => cliclack::input("VPM ID")
-            .placeholder("com.example.vpm")
-            .validate(|input: &String| {
-                validation::validate_reverse_domain(input)
-                    .map(|_| ())
-                    .map_err(|e| e.to_string())
-            })
-            .interact()?,
-    };
-
-    let author: String = match args.author {
-        Some(a) => a,
-        None => cliclack::input("Author name").interact()?,
-    };
-
-    let url: String = match args.url {
-        Some(u) => {
-            validation::validate_url(&u)?;
-            u
-        }
-        None => cliclack::input("VPM URL")
-            .placeholder("https://example.github.io/repo/index.json")
-            .validate(|input: &String| {
-                validation::validate_url(input)
-                    .map(|_| ())
-                    .map_err(|e| e.to_string())
-            })
-            .interact()?,
-    };
-
-    let manifest = Manifest::new(Vpm {
+    let name = resolve_field(
+        args.name,
+        prompt_all,
+        "VPM name",
+        "My Awesome VPM",
+        validate_not_empty,
+    )?;
+    let id = resolve_field(
+        args.id,
+        prompt_all,
+        "VPM ID",
+        "com.example.vpm",
+        validation::validate_reverse_domain,
+    )?;
+    let author = resolve_field(
+        args.author,
+        prompt_all,
+        "Author name",
+        "",
+        validate_not_empty,
+    )?;
+    let url = resolve_field(
+        args.url,
+        prompt_all,
+        "VPM URL",
+        "https://example.github.io/repo/index.json",
+        validation::validate_url,
+    )?;
+
+    let mut manifest = Manifest::new(Vpm {
         id,
         name,
         author,
         url,
     });
+    manifest.format = paths.config_format();
 
     if args.force {
-        let tx_path = output_path.with_extension("txn");
+        let tx_path = transaction_path(lock_path);
         if tx_path.exists() {
             std::fs::remove_file(&tx_path).map_err(|e| Error::FileWrite {
                 path: tx_path.display().to_string(),
@@ -91,7 +92,9 @@ pub fn execute(args: InitArgs, paths: &ConfigPaths) -> Result<()> {
     let hash = compute_manifest_hash_from_manifest(&manifest, output_path)?;
     let mut lockfile = Lockfile::new();
     lockfile.manifest_hash = Some(hash);
-    save_manifest_and_lock(&manifest, &lockfile, output_path, lock_path)?;
+    paths
+        .lock_writer()?
+        .save_manifest_and_lock(&manifest, &lockfile)?;
 
     cliclack::outro(format!("Created {}", output_path.display()))?;
 
@@ -100,3 +103,56 @@ pub fn execute(args: InitArgs, paths: &ConfigPaths) -> Result<()> {
 
     Ok(())
 }
+
+/// Lists the flags that would otherwise have to be prompted for.
+fn missing_flags(args: &InitArgs) -> Vec<&'static str> {
+    [
+        ("--name", args.name.is_none()),
+        ("--id", args.id.is_none()),
+        ("--author", args.author.is_none()),
+        ("--url", args.url.is_none()),
+    ]
+    .into_iter()
+    .filter_map(|(flag, missing)| missing.then_some(flag))
+    .collect()
+}
+
+/// Uses the flag value as given, or prompts for it (offering the flag value
+/// as the default) until `validate` accepts the answer.
+fn resolve_field(
+    value: Option<String>,
+    prompt_all: bool,
+    label: &str,
+    placeholder: &str,
+    validate: impl Fn(&str) -> Result<()>,
+) -> Result<String> {
+    if let Some(value) = &value
+        && !prompt_all
+    {
+        validate(value)?;
+        return Ok(value.clone());
+    }
+
+    term::prompt_until_valid(
+        |rejection| {
+            if let Some(reason) = rejection {
+                cliclack::log::error(reason)?;
+            }
+            let mut input = cliclack::input(label).placeholder(placeholder);
+            if let Some(default) = &value {
+                input = input.default_input(default);
+            }
+            Ok(input.interact()?)
+        },
+        validate,
+    )
+}
+
+fn validate_not_empty(value: &str) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(Error::ConfigValidation(
+            "value must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
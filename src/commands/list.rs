@@ -1,41 +1,152 @@
 use crate::cli::{ConfigPaths, ListArgs};
 use crate::commands::{package_not_found_error, print_no_versions_fetched_hint};
-use crate::config::Manifest;
-use crate::error::Result;
-use crate::lock::Lockfile;
+use crate::config::{Manifest, Package};
+use crate::error::{Error, Result};
+use crate::lock::{LockedVersion, Lockfile};
 use crate::services::check_and_load;
 use crate::term;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct PackageEntry<'a> {
+    id: &'a str,
+    repository: String,
+    versions: Vec<VersionEntry<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum VersionEntry<'a> {
+    Version(&'a str),
+    Detailed {
+        version: &'a str,
+        tag: &'a str,
+        url: &'a str,
+        hash: &'a str,
+    },
+}
+
+impl<'a> VersionEntry<'a> {
+    fn new(version: &'a LockedVersion, detailed: bool) -> Self {
+        if detailed {
+            Self::Detailed {
+                version: &version.version,
+                tag: &version.tag,
+                url: &version.url,
+                hash: &version.hash,
+            }
+        } else {
+            Self::Version(&version.version)
+        }
+    }
+}
+
+impl<'a> PackageEntry<'a> {
+    fn new(package: &'a Package, lockfile: &'a Lockfile, detailed: bool) -> Self {
+        let versions = lockfile
+            .get_package(&package.id)
+            .map(|locked| {
+                locked
+                    .versions
+                    .iter()
+                    .map(|version| VersionEntry::new(version, detailed))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            id: &package.id,
+            repository: package.repository.to_string(),
+            versions,
+        }
+    }
+}
 
 pub fn execute(args: ListArgs, paths: &ConfigPaths) -> Result<()> {
     let config_path = paths.config_path();
     let lock_path = paths.lock_path();
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    let check_result = check_and_load(config_path, lock_path, paths.config_format())?;
     let manifest = check_result.manifest;
     let lockfile = check_result.lockfile;
 
+    if args.json {
+        return print_json(
+            &manifest,
+            &lockfile,
+            args.package_id.as_deref(),
+            args.tag.as_deref(),
+            args.detailed,
+            paths,
+        );
+    }
+
     match args.package_id {
         Some(package_id) => list_versions(&manifest, &lockfile, &package_id, paths),
-        None => list_packages(&manifest, &lockfile),
+        None => list_packages(&manifest, &lockfile, args.tag.as_deref()),
     }
 }
 
-fn list_packages(manifest: &Manifest, lockfile: &Lockfile) -> Result<()> {
+fn print_json(
+    manifest: &Manifest,
+    lockfile: &Lockfile,
+    package_id: Option<&str>,
+    tag: Option<&str>,
+    detailed: bool,
+    paths: &ConfigPaths,
+) -> Result<()> {
+    let json = match package_id {
+        Some(package_id) => {
+            let package = manifest
+                .packages
+                .iter()
+                .find(|p| p.id == package_id)
+                .ok_or_else(|| package_not_found_error(package_id, paths.config_path()))?;
+            serde_json::to_string_pretty(&PackageEntry::new(package, lockfile, detailed))
+        }
+        None => {
+            let entries: Vec<_> = manifest
+                .packages
+                .iter()
+                .filter(|p| tag.is_none_or(|tag| p.has_tag(tag)))
+                .map(|p| PackageEntry::new(p, lockfile, detailed))
+                .collect();
+            serde_json::to_string_pretty(&entries)
+        }
+    }
+    .map_err(Error::JsonSerialize)?;
+
+    println!("{}", json);
+    Ok(())
+}
+
+fn list_packages(manifest: &Manifest, lockfile: &Lockfile, tag: Option<&str>) -> Result<()> {
     if manifest.packages.is_empty() {
         term::status("No packages configured.");
         term::hint("Run 'voy add <owner/repo>' to add a package.");
         return Ok(());
     }
 
-    let max_id_len = manifest
+    let packages: Vec<_> = manifest
         .packages
+        .iter()
+        .filter(|p| tag.is_none_or(|tag| p.has_tag(tag)))
+        .collect();
+
+    if let Some(tag) = tag
+        && packages.is_empty()
+    {
+        term::status(format!("No packages tagged '{}'.", tag));
+        return Ok(());
+    }
+
+    let max_id_len = packages
         .iter()
         .map(|p| p.id.len())
         .max()
         .unwrap_or(7)
         .max(7);
-    let max_repo_len = manifest
-        .packages
+    let max_repo_len = packages
         .iter()
         .map(|p| p.repository.to_string().len())
         .max()
@@ -47,7 +158,7 @@ fn list_packages(manifest: &Manifest, lockfile: &Lockfile) -> Result<()> {
         "Package", "Repository",
     ));
 
-    for package in &manifest.packages {
+    for package in packages {
         let version_count = lockfile
             .get_package(&package.id)
             .map(|p| p.versions.len())
@@ -1,64 +1,88 @@
-use crate::cli::LockArgs;
-use crate::config::Manifest;
+use crate::cli::{ConfigPaths, LockArgs};
+use crate::config::{ConfigFormat, Manifest};
 use crate::context::AppContext;
 use crate::error::{Error, Result};
 use crate::infra::GitHubApi;
-use crate::lock::{Lockfile, compute_manifest_hash};
-use crate::services::recover_manifest_lock_transaction;
+use crate::lock::{HashAlgorithm, Lockfile, ReconcileChanges, compute_manifest_hash_with};
 use crate::term;
+use std::path::Path;
 use tracing::info;
 
-pub async fn execute<G: GitHubApi>(args: LockArgs, ctx: &AppContext<G>) -> Result<()> {
-    let config_path = ctx.paths.config_path();
-    let lock_path = ctx.paths.lock_path();
-    recover_manifest_lock_transaction(config_path, lock_path)?;
+/// Names the manifest read by `--config -` in messages.
+const STDIN: &str = "<stdin>";
 
-    if !config_path.exists() {
-        return Err(Error::ConfigValidation(format!(
-            "Configuration file '{}' not found. Run 'voy init' first.",
-            config_path.display()
-        )));
+pub async fn execute<G: GitHubApi>(args: LockArgs, ctx: &AppContext<G>) -> Result<()> {
+    if ctx.paths.config_is_stdin() {
+        return check_stdin_manifest(&args, ctx.paths.config_format());
     }
 
-    if !lock_path.exists() {
-        return Err(Error::ConfigValidation(format!(
-            "Lock file '{}' not found. Run 'voy fetch' first.",
-            lock_path.display()
-        )));
+    // Printing is read-only, so it neither waits for other writers nor
+    // recovers a pending transaction.
+    if args.print {
+        let LockInputs {
+            manifest,
+            mut lockfile,
+            hash,
+            ..
+        } = load(&args, &ctx.paths)?;
+        lockfile.reconcile(&manifest);
+        lockfile.manifest_hash = Some(hash);
+        print!("{}", ctx.paths.lock_content(&lockfile)?);
+        return Ok(());
     }
 
-    let initial_hash = compute_manifest_hash(config_path)?;
-    let mut lockfile = Lockfile::load(lock_path)?;
-
-    let is_match = lockfile
-        .manifest_hash
-        .as_ref()
-        .is_some_and(|h| h == &initial_hash);
+    let config_path = ctx.paths.config_path();
+    let lock_path = ctx.paths.lock_path();
+    let lock_writer = ctx.paths.lock_writer()?;
+    let LockInputs {
+        manifest,
+        mut lockfile,
+        algorithm,
+        hash: initial_hash,
+    } = load(&args, &ctx.paths)?;
+    // Exact comparison so legacy unprefixed hashes get rewritten.
+    let hash_changed = lockfile.manifest_hash.as_deref() != Some(initial_hash.as_str());
 
     if args.check {
-        if is_match {
+        if lockfile.manifest_hash_matches(&initial_hash) {
             term::success("Manifest hash matches lock file");
             Ok(())
         } else {
-            term::error("Manifest hash does not match lock file");
             Err(Error::ManifestHashMismatch)
         }
+    } else if args.fix {
+        let changes = lockfile.reconcile(&manifest);
+        if changes.is_empty() && !hash_changed {
+            term::success("Lock file is already up to date");
+            return Ok(());
+        }
+
+        lockfile.manifest_hash = Some(initial_hash);
+        lock_writer.save_manifest_and_lock(&manifest, &lockfile)?;
+        info!(path = %lock_path.display(), "Lock file fixed");
+        print_fix_changes(&changes, hash_changed);
+
+        Ok(())
     } else {
-        if is_match {
+        if !hash_changed {
             term::success("Lock file is already up to date");
             return Ok(());
         }
 
-        let manifest = Manifest::load(config_path)?;
-        verify_repositories(&manifest, ctx.github.as_ref()).await?;
+        let github = ctx.github(&manifest.registry)?;
+        verify_repositories(&manifest, github.as_ref()).await?;
 
-        let final_hash = compute_manifest_hash(config_path)?;
+        let final_hash = compute_manifest_hash_with(
+            &Manifest::load_as(config_path, ctx.paths.config_format())?,
+            config_path,
+            algorithm,
+        )?;
         if final_hash != initial_hash {
             return Err(Error::ManifestHashMismatch);
         }
 
         lockfile.manifest_hash = Some(final_hash);
-        lockfile.save(lock_path)?;
+        lock_writer.save_lock(&lockfile)?;
         info!(path = %lock_path.display(), "Lock file updated");
         term::success("Updated manifest hash in lock file");
 
@@ -66,6 +90,71 @@ pub async fn execute<G: GitHubApi>(args: LockArgs, ctx: &AppContext<G>) -> Resul
     }
 }
 
+/// The manifest and lock file, with the manifest hash computed using
+/// `--manifest-hash-algo` or the lock file's algorithm.
+struct LockInputs {
+    manifest: Manifest,
+    lockfile: Lockfile,
+    algorithm: HashAlgorithm,
+    hash: String,
+}
+
+fn load(args: &LockArgs, paths: &ConfigPaths) -> Result<LockInputs> {
+    let config_path = paths.config_path();
+    let lock_path = paths.lock_path();
+    if !config_path.exists() {
+        return Err(Error::ConfigValidation(format!(
+            "Configuration file '{}' not found. Run 'voy init' first.",
+            config_path.display()
+        )));
+    }
+
+    if !lock_path.exists() {
+        return Err(Error::ConfigValidation(format!(
+            "Lock file '{}' not found. Run 'voy fetch' first.",
+            lock_path.display()
+        )));
+    }
+
+    let lockfile = Lockfile::load(lock_path)?;
+    let algorithm = args
+        .manifest_hash_algo
+        .map(HashAlgorithm::from)
+        .unwrap_or_else(|| lockfile.manifest_hash_algorithm());
+    let manifest = Manifest::load_as(config_path, paths.config_format())?;
+    let hash = compute_manifest_hash_with(&manifest, config_path, algorithm)?;
+    Ok(LockInputs {
+        manifest,
+        lockfile,
+        algorithm,
+        hash,
+    })
+}
+
+fn print_fix_changes(changes: &ReconcileChanges, hash_changed: bool) {
+    for id in &changes.removed {
+        term::success(format!("Removed stale package {}", id));
+    }
+    for id in &changes.added {
+        term::success(format!("Added package {}", id));
+    }
+    for id in &changes.repository_changed {
+        term::success(format!(
+            "Cleared locked versions of {} after its repository changed",
+            id
+        ));
+    }
+    if changes.reordered {
+        term::success("Reordered packages to match the manifest");
+    }
+    if hash_changed {
+        term::success("Updated manifest hash in lock file");
+    }
+    if !changes.added.is_empty() || !changes.repository_changed.is_empty() {
+        term::hint("Next: voy fetch");
+    }
+}
+
 async fn verify_repositories<G: GitHubApi>(manifest: &Manifest, github: &G) -> Result<()> {
     if manifest.packages.is_empty() {
         return Ok(());
@@ -82,3 +171,24 @@ async fn verify_repositories<G: GitHubApi>(manifest: &Manifest, github: &G) -> R
     spinner.finish_and_clear();
     verify_result
 }
+
+/// Validates a manifest read from stdin. There is no file to hash, so only
+/// its structure is checked.
+fn check_stdin_manifest(args: &LockArgs, format: ConfigFormat) -> Result<()> {
+    if !args.check {
+        return Err(Error::ConfigValidation(
+            "--config - is only supported with 'lock --check'".to_string(),
+        ));
+    }
+
+    let content = std::io::read_to_string(std::io::stdin()).map_err(|e| Error::FileRead {
+        path: STDIN.to_string(),
+        source: e,
+    })?;
+    let manifest = Manifest::parse_as(&content, Path::new(STDIN), format)?;
+    term::success(format!(
+        "Manifest is valid ({} package(s)); hash check skipped for stdin",
+        manifest.packages.len()
+    ));
+    Ok(())
+}
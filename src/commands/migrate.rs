@@ -0,0 +1,70 @@
+use crate::cli::{ConfigPaths, MigrateArgs};
+use crate::error::{Error, Result};
+use crate::infra::read_json;
+use crate::lock::compute_manifest_hash_from_manifest;
+use crate::output::VpmOutput;
+use crate::services::{migrate_index, transaction_path};
+use crate::term;
+
+pub fn execute(args: MigrateArgs, paths: &ConfigPaths) -> Result<()> {
+    let config_path = paths.config_path();
+    let lock_path = paths.lock_path();
+
+    if !args.force {
+        if let Some(existing) = [config_path, lock_path].into_iter().find(|p| p.exists()) {
+            return Err(Error::ConfigValidation(format!(
+                "{} already exists; pass --force to overwrite it",
+                existing.display()
+            )));
+        }
+    } else {
+        let tx_path = transaction_path(lock_path);
+        if tx_path.exists() {
+            std::fs::remove_file(&tx_path).map_err(|e| Error::FileWrite {
+                path: tx_path.display().to_string(),
+                source: e,
+            })?;
+            term::status(format!(
+                "Removed stale transaction log {}",
+                tx_path.display()
+            ));
+        }
+    }
+
+    let index: VpmOutput = read_json(&args.index)?;
+    let mut migrated = migrate_index(&index)?;
+    migrated.manifest.format = paths.config_format();
+
+    for (package_id, reason) in &migrated.skipped {
+        term::warning(format!("Skipped {}: {}", package_id, reason));
+    }
+
+    let hash = compute_manifest_hash_from_manifest(&migrated.manifest, config_path)?;
+    migrated.lockfile.manifest_hash = Some(hash);
+    paths
+        .lock_writer()?
+        .save_manifest_and_lock(&migrated.manifest, &migrated.lockfile)?;
+
+    let version_count: usize = migrated
+        .lockfile
+        .packages
+        .iter()
+        .map(|p| p.versions.len())
+        .sum();
+    term::success(format!(
+        "Migrated {} package(s) with {} version(s) into {}",
+        migrated.manifest.packages.len(),
+        version_count,
+        config_path.display()
+    ));
+    if !migrated.skipped.is_empty() {
+        term::hint("Add skipped packages with: voy add <owner/repo>");
+    }
+    term::hint(format!(
+        "Repositories and tags were guessed from release URLs; review {}",
+        config_path.display()
+    ));
+    term::hint("Versions stay unverified until fetched from their releases: voy fetch");
+
+    Ok(())
+}
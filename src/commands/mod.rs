@@ -1,12 +1,18 @@
 pub mod add;
+pub mod diff;
 pub mod fetch;
 pub mod generate;
 pub mod info;
 pub mod init;
 pub mod list;
 pub mod lock;
+pub mod migrate;
+pub mod outdated;
+pub mod prune;
 pub mod remove;
+pub mod urls;
 pub mod validate;
+pub mod verify;
 
 use crate::error::Error;
 use crate::term;
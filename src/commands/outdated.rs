@@ -0,0 +1,83 @@
+use crate::cli::{OutdatedArgs, OutputFormat};
+use crate::context::AppContext;
+use crate::error::{Error, Result};
+use crate::infra::GitHubApi;
+use crate::services::{
+    FetcherConfig, OutdatedPackage, PackageFetcher, check_and_load, outdated_packages,
+};
+use crate::term;
+use tracing::info;
+
+pub async fn execute<G: GitHubApi>(args: OutdatedArgs, ctx: &AppContext<G>) -> Result<()> {
+    let check_result = check_and_load(
+        ctx.paths.config_path(),
+        ctx.paths.lock_path(),
+        ctx.paths.config_format(),
+    )?;
+    let manifest = check_result.manifest;
+    let lockfile = check_result.lockfile;
+    let github = ctx.github(&manifest.registry)?;
+
+    info!(
+        packages = manifest.packages.len(),
+        max_concurrent = args.max_concurrent,
+        "Checking for unlocked releases"
+    );
+
+    let fetcher = PackageFetcher::new(
+        github,
+        FetcherConfig {
+            max_concurrent: args.max_concurrent,
+            asset_name: args.asset_name,
+            ..Default::default()
+        },
+    );
+    let spinner = term::spinner("Checking releases...");
+    let plans = fetcher.plan(&manifest, &lockfile).await;
+    spinner.finish_and_clear();
+    let outdated = outdated_packages(&plans?, &lockfile);
+
+    match args.format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&outdated).map_err(Error::JsonSerialize)?;
+            println!("{}", json);
+        }
+        OutputFormat::Text => print_outdated(&outdated),
+    }
+
+    if outdated.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Outdated {
+        count: outdated.len(),
+    })
+}
+
+fn print_outdated(outdated: &[OutdatedPackage]) {
+    if outdated.is_empty() {
+        term::success("All packages are up to date");
+        return;
+    }
+
+    let id_width = outdated
+        .iter()
+        .map(|p| p.package_id.len())
+        .max()
+        .unwrap_or(0)
+        .max("Package".len());
+    term::line(format!(
+        "{}",
+        term::bold(format!(
+            "{:id_width$}  {:12}  {}",
+            "Package", "Locked", "Latest"
+        ))
+    ));
+    for package in outdated {
+        term::line(format!(
+            "{:id_width$}  {:12}  {}",
+            package.package_id,
+            package.locked.as_deref().unwrap_or("-"),
+            term::green(&package.latest)
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::cli::{ConfigPaths, PruneArgs};
+use crate::commands::package_not_found_error;
+use crate::error::Result;
+use crate::lock::compute_manifest_hash_with;
+use crate::services::check_and_load;
+use crate::term;
+
+pub fn execute(args: PruneArgs, paths: &ConfigPaths) -> Result<()> {
+    let config_path = paths.config_path();
+    let lock_path = paths.lock_path();
+
+    let lock_writer = paths.lock_writer()?;
+    let check_result = check_and_load(config_path, lock_path, paths.config_format())?;
+    let manifest = check_result.manifest;
+    let mut lockfile = check_result.lockfile;
+
+    if let Some(package_id) = &args.package_id
+        && !manifest.packages.iter().any(|p| &p.id == package_id)
+    {
+        return Err(package_not_found_error(package_id, config_path));
+    }
+
+    let mut pruned = Vec::new();
+    for package in lockfile
+        .packages
+        .iter_mut()
+        .filter(|p| args.package_id.as_ref().is_none_or(|id| &p.id == id))
+    {
+        let removed = package.keep_latest(args.keep_latest);
+        if !removed.is_empty() {
+            pruned.push((package.id.clone(), removed));
+        }
+    }
+
+    if pruned.is_empty() {
+        term::info(format!(
+            "Nothing to prune: no package has more than {} locked version(s)",
+            args.keep_latest
+        ));
+        return Ok(());
+    }
+
+    lockfile.manifest_hash = Some(compute_manifest_hash_with(
+        &manifest,
+        config_path,
+        lockfile.manifest_hash_algorithm(),
+    )?);
+    lock_writer.save_manifest_and_lock(&manifest, &lockfile)?;
+
+    for (package_id, removed) in &pruned {
+        term::success(format!(
+            "Pruned {} version(s) from {}: {}",
+            removed.len(),
+            package_id,
+            term::dim(removed.join(", "))
+        ));
+    }
+
+    Ok(())
+}
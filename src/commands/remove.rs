@@ -1,18 +1,27 @@
 use crate::cli::{ConfigPaths, RemoveArgs};
 use crate::commands::package_not_found_error;
-use crate::error::Result;
-use crate::lock::compute_manifest_hash_from_manifest;
-use crate::services::{check_and_load, save_manifest_and_lock};
+use crate::error::{Error, Result};
+use crate::lock::compute_manifest_hash_with;
+use crate::services::check_and_load;
 use crate::term;
 
 pub fn execute(args: RemoveArgs, paths: &ConfigPaths) -> Result<()> {
     let config_path = paths.config_path();
     let lock_path = paths.lock_path();
 
-    let check_result = check_and_load(config_path, lock_path)?;
+    let lock_writer = paths.lock_writer()?;
+    let check_result = check_and_load(config_path, lock_path, paths.config_format())?;
     let mut manifest = check_result.manifest;
     let mut lockfile = check_result.lockfile;
 
+    if let Some(include_path) = manifest.included.get(&args.package_id) {
+        return Err(Error::ConfigValidation(format!(
+            "Package '{}' is defined in included file '{}'; remove it there",
+            args.package_id,
+            include_path.display()
+        )));
+    }
+
     let original_len = manifest.packages.len();
     manifest.packages.retain(|p| p.id != args.package_id);
 
@@ -20,12 +29,26 @@ pub fn execute(args: RemoveArgs, paths: &ConfigPaths) -> Result<()> {
         return Err(package_not_found_error(&args.package_id, config_path));
     }
 
-    let new_hash = compute_manifest_hash_from_manifest(&manifest, config_path)?;
-    lockfile.packages.retain(|p| p.id != args.package_id);
+    let new_hash =
+        compute_manifest_hash_with(&manifest, config_path, lockfile.manifest_hash_algorithm())?;
+    if args.keep_lock {
+        if let Some(locked) = lockfile.get_package_mut(&args.package_id) {
+            locked.retained = true;
+        }
+    } else {
+        lockfile.packages.retain(|p| p.id != args.package_id);
+    }
     lockfile.manifest_hash = Some(new_hash);
-    save_manifest_and_lock(&manifest, &lockfile, config_path, lock_path)?;
+    lock_writer.save_manifest_and_lock(&manifest, &lockfile)?;
 
-    term::success(format!("Removed {}", args.package_id));
+    if args.keep_lock {
+        term::success(format!(
+            "Removed {} (locked versions kept)",
+            args.package_id
+        ));
+    } else {
+        term::success(format!("Removed {}", args.package_id));
+    }
 
     Ok(())
 }
@@ -0,0 +1,55 @@
+use crate::cli::{ConfigPaths, UrlsArgs};
+use crate::error::{Error, Result};
+use crate::infra::{HttpApi, write_text};
+use crate::services::{check_and_load, generate_from_lockfile, url_list, warm_urls};
+use crate::term;
+use std::sync::Arc;
+use tracing::info;
+
+pub async fn execute<H: HttpApi>(args: UrlsArgs, paths: &ConfigPaths, http: Arc<H>) -> Result<()> {
+    let check_result = check_and_load(
+        paths.config_path(),
+        paths.lock_path(),
+        paths.config_format(),
+    )?;
+    let output = generate_from_lockfile(&check_result.manifest, &check_result.lockfile)?;
+    let urls = url_list(&output);
+
+    info!(urls = urls.len(), "Collected version URLs");
+
+    match &args.output {
+        Some(path) => {
+            let mut content = urls.join("\n");
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            write_text(path, &content)?;
+            term::success(format!("Wrote {} URL(s) to {}", urls.len(), path.display()));
+        }
+        None => {
+            for url in &urls {
+                println!("{}", url);
+            }
+        }
+    }
+
+    if !args.warm {
+        return Ok(());
+    }
+
+    let bar = term::progress_bar(urls.len() as u64, "Warming URLs");
+    let failures = warm_urls(http.as_ref(), &urls, args.max_concurrent, Some(&bar)).await;
+    bar.finish_and_clear();
+
+    if failures.is_empty() {
+        term::success(format!("Warmed {} URL(s)", urls.len()));
+        return Ok(());
+    }
+
+    for (url, error) in &failures {
+        term::status(format!("{}: {}", term::underlined(url), error));
+    }
+    Err(Error::UrlValidation {
+        count: failures.len(),
+    })
+}
@@ -1,10 +1,11 @@
 use crate::cli::ValidateArgs;
 use crate::error::{Error, Result};
-use crate::infra::{HttpApi, read_json};
+use crate::infra::{HttpApi, read_json, write_json};
 use crate::output::VpmOutput;
-use crate::services::UrlValidator;
+use crate::services::{UrlValidator, ValidationReport};
 use crate::term;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::info;
 
 pub async fn execute<H: HttpApi>(args: ValidateArgs, http: Arc<H>) -> Result<()> {
@@ -20,10 +21,21 @@ pub async fn execute<H: HttpApi>(args: ValidateArgs, http: Arc<H>) -> Result<()>
 
     let spinner = term::spinner("Validating URLs...");
 
-    let validator = UrlValidator::new(http, args.max_concurrent, args.max_retries);
-    let result = validator.validate(&output).await?;
+    let validator = UrlValidator::new(http, args.max_concurrent, args.max_retries)
+        .with_https_only(args.https_only);
+    let retry_observer = term::SpinnerRetryObserver::new(&spinner);
+    let started = Instant::now();
+    let result = validator
+        .validate_with_observer(&output, Some(&retry_observer))
+        .await?;
+    let elapsed = started.elapsed();
     spinner.finish_and_clear();
 
+    if let Some(report_path) = &args.report {
+        write_json(report_path, &ValidationReport::new(&output, &result))?;
+        info!(path = %report_path.display(), "Validation report written");
+    }
+
     if result.invalid.is_empty() {
         term::success(format!("Checked {} URL(s): all valid", result.total));
     } else {
@@ -35,14 +47,24 @@ pub async fn execute<H: HttpApi>(args: ValidateArgs, http: Arc<H>) -> Result<()>
         ));
     }
 
+    if args.concurrency_report {
+        info!(urls = result.total, ?elapsed, "URL check throughput");
+        term::info(concurrency_report(
+            result.total,
+            elapsed,
+            args.max_concurrent,
+        ));
+    }
+
     if !result.invalid.is_empty() {
         term::blank();
         for invalid in &result.invalid {
-            term::error(format!(
-                "{} {}: {}",
+            term::status(format!(
+                "{} {}: {} {}",
                 term::red(&invalid.package_id),
                 term::dim(format!("v{}", invalid.version)),
-                term::underlined(&invalid.url)
+                term::underlined(&invalid.url),
+                term::dim(format!("({})", invalid.outcome))
             ));
         }
         return Err(Error::UrlValidation {
@@ -54,3 +76,16 @@ pub async fn execute<H: HttpApi>(args: ValidateArgs, http: Arc<H>) -> Result<()>
 
     Ok(())
 }
+
+fn concurrency_report(url_count: usize, elapsed: Duration, max_concurrent: usize) -> String {
+    let secs = elapsed.as_secs_f64();
+    let per_sec = if secs > 0.0 {
+        url_count as f64 / secs
+    } else {
+        0.0
+    };
+    format!(
+        "Checked {} URL(s) in {:.2}s ({:.1} URLs/s at --max-concurrent {})",
+        url_count, secs, per_sec, max_concurrent
+    )
+}
@@ -0,0 +1,76 @@
+use crate::cli::VerifyArgs;
+use crate::commands::print_no_versions_fetched_hint;
+use crate::config::Registry;
+use crate::context::AppContext;
+use crate::error::{Error, Result};
+use crate::infra::GitHubApi;
+use crate::lock::Lockfile;
+use crate::services::{LockVerifier, recover_manifest_lock_transaction};
+use crate::term;
+use tracing::info;
+
+pub async fn execute<G: GitHubApi>(args: VerifyArgs, ctx: &AppContext<G>) -> Result<()> {
+    let config_path = ctx.paths.config_path();
+    let lock_path = ctx.paths.lock_path();
+    recover_manifest_lock_transaction(config_path, lock_path)?;
+    let lockfile = Lockfile::load_or_default(lock_path)?;
+    let total: usize = lockfile.packages.iter().map(|p| p.versions.len()).sum();
+
+    if total == 0 {
+        print_no_versions_fetched_hint();
+        return Ok(());
+    }
+
+    info!(
+        versions = total,
+        max_concurrent = args.max_concurrent,
+        "Starting lock verification"
+    );
+
+    // Downloads only need the `[registry]` settings, so the rest of the
+    // manifest is not validated.
+    let registry = if config_path.exists() {
+        Registry::load_as(config_path, ctx.paths.config_format())?
+    } else {
+        Registry::default()
+    };
+    let verifier = LockVerifier::new(
+        ctx.github(&registry)?,
+        args.max_concurrent,
+        args.max_retries,
+    );
+    let bar = term::progress_bar(total as u64, "Verifying locked versions");
+    let retry_observer = term::SpinnerRetryObserver::new(&bar);
+    let result = verifier
+        .verify(&lockfile, Some(&bar), Some(&retry_observer))
+        .await?;
+    bar.finish_and_clear();
+
+    if result.failures.is_empty() {
+        term::success(format!(
+            "Verified {} locked version(s): all match",
+            result.total
+        ));
+        return Ok(());
+    }
+
+    term::status(format!(
+        "Verified {} locked version(s): {} match, {} failed",
+        result.total,
+        term::green(result.verified),
+        term::red(result.failures.len())
+    ));
+    term::blank();
+    for failure in &result.failures {
+        term::status(format!(
+            "{} {}: {}",
+            term::red(&failure.package_id),
+            term::dim(format!("v{}", failure.version)),
+            failure.reason
+        ));
+    }
+
+    Err(Error::LockVerification {
+        count: result.failures.len(),
+    })
+}
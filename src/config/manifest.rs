@@ -1,49 +1,214 @@
 use super::validation;
-use crate::domain::Repository;
+use crate::domain::{Provider, Repository};
 use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Manifest {
+    /// Other TOML files whose `[[packages]]` are merged in at load time,
+    /// relative to this manifest.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Defaults::is_empty")]
+    pub defaults: Defaults,
+    #[serde(default, skip_serializing_if = "Registry::is_empty")]
+    pub registry: Registry,
     pub vpm: Vpm,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub packages: Vec<Package>,
+    /// Package IDs merged from included files, mapped to the file defining them.
+    #[serde(skip)]
+    pub included: HashMap<String, PathBuf>,
+    /// Format the manifest was read from and is saved back in.
+    #[serde(skip)]
+    pub format: ConfigFormat,
+}
+
+/// File format of a manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// JSON for `.json` files, TOML otherwise.
+    #[default]
+    Auto,
+    Toml,
+    /// JSON with the same structure as the TOML manifest.
+    Json,
+}
+
+impl ConfigFormat {
+    /// Resolves `Auto` from the extension of `path`.
+    pub fn resolve(self, path: &Path) -> Self {
+        match self {
+            Self::Auto if path.extension().is_some_and(|ext| ext == "json") => Self::Json,
+            Self::Auto => Self::Toml,
+            format => format,
+        }
+    }
+}
+
+/// Contents of a file listed in `include`.
+#[derive(Deserialize)]
+struct IncludedPackages {
+    #[serde(default)]
+    packages: Vec<Package>,
+}
+
+/// The parts of a manifest written back to its own file.
+#[derive(Serialize)]
+struct ManifestFile<'a> {
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    include: &'a [PathBuf],
+    #[serde(skip_serializing_if = "Defaults::is_empty")]
+    defaults: &'a Defaults,
+    #[serde(skip_serializing_if = "Registry::is_empty")]
+    registry: &'a Registry,
+    vpm: &'a Vpm,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<toml::Table>,
 }
 
 impl Manifest {
     pub fn new(vpm: Vpm) -> Self {
         Self {
+            include: Vec::new(),
+            defaults: Defaults::default(),
+            registry: Registry::default(),
             vpm,
             packages: Vec::new(),
+            included: HashMap::new(),
+            format: ConfigFormat::Auto,
         }
     }
 
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_as(path, ConfigFormat::Auto)
+    }
+
+    /// Loads the manifest in the given format (`Auto` picks by extension).
+    pub fn load_as<P: AsRef<Path>>(path: P, format: ConfigFormat) -> Result<Self> {
         let path = path.as_ref();
-        let path_str = path.display().to_string();
+        let manifest = Self::load_unvalidated(path, format)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
 
-        let content = std::fs::read_to_string(path).map_err(|e| Error::FileRead {
-            path: path_str.clone(),
-            source: e,
-        })?;
+    /// Parses and validates manifest `content` without reading it from disk.
+    /// `path` only names the source in errors and anchors `include` paths.
+    pub fn parse_as(content: &str, path: &Path, format: ConfigFormat) -> Result<Self> {
+        let manifest = Self::parse_unvalidated(content, path, format.resolve(path))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
 
-        let manifest: Manifest = toml::from_str(&content).map_err(|e| Error::TomlParse {
+    /// Parses the manifest and merges included packages without validating them.
+    pub(crate) fn load_unvalidated(path: &Path, format: ConfigFormat) -> Result<Self> {
+        let format = format.resolve(path);
+        let content = read_file(path)?;
+        Self::parse_unvalidated(&content, path, format)
+    }
+
+    fn parse_unvalidated(content: &str, path: &Path, format: ConfigFormat) -> Result<Self> {
+        let path_str = path.display().to_string();
+        let mut table = parse_table(content, path, format)?;
+        let template = repository_template(&table);
+        apply_repository_template(&mut table, template.as_deref())?;
+
+        let mut manifest: Manifest = table.try_into().map_err(|e| Error::TomlParse {
             path: path_str,
             source: e,
         })?;
 
-        manifest.validate()?;
+        manifest.format = format;
+        manifest.resolve_includes(path)?;
         Ok(manifest)
     }
 
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let path = path.as_ref();
-        let content = toml::to_string_pretty(self).map_err(|e| Error::TomlSerialize {
+    fn resolve_includes(&mut self, path: &Path) -> Result<()> {
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+
+        for include in self.include.clone() {
+            let include_path = base_dir.join(&include);
+            let include_str = include_path.display().to_string();
+
+            let mut table = read_table(&include_path, ConfigFormat::Auto.resolve(&include_path))?;
+            apply_repository_template(&mut table, self.defaults.repository_template.as_deref())?;
+            let included: IncludedPackages = table.try_into().map_err(|e| Error::TomlParse {
+                path: include_str.clone(),
+                source: e,
+            })?;
+
+            for package in included.packages {
+                if self.packages.iter().any(|p| p.id == package.id) {
+                    let defined_in = self
+                        .included
+                        .get(&package.id)
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|| path.display().to_string());
+                    return Err(Error::ConfigValidation(format!(
+                        "Duplicate package ID: {} (in '{}', already defined in '{}')",
+                        package.id, include_str, defined_in
+                    )));
+                }
+                self.included
+                    .insert(package.id.clone(), include_path.clone());
+                self.packages.push(package);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the manifest for its own file, leaving out included packages
+    /// and repositories that come from `repository_template`.
+    pub fn to_toml_string(&self) -> std::result::Result<String, toml::ser::Error> {
+        toml::to_string_pretty(&self.to_file()?)
+    }
+
+    /// Serializes the manifest in its own format, resolving `Auto` from `path`.
+    pub fn to_file_string(&self, path: &Path) -> Result<String> {
+        let toml_error = |e| Error::TomlSerialize {
             path: path.display().to_string(),
             source: e,
-        })?;
+        };
+        match self.format.resolve(path) {
+            ConfigFormat::Json => {
+                let file = self.to_file().map_err(toml_error)?;
+                serde_json::to_string_pretty(&file).map_err(Error::JsonSerialize)
+            }
+            _ => self.to_toml_string().map_err(toml_error),
+        }
+    }
+
+    fn to_file(&self) -> std::result::Result<ManifestFile<'_>, toml::ser::Error> {
+        let mut packages = Vec::new();
+        for package in self
+            .packages
+            .iter()
+            .filter(|p| !self.included.contains_key(&p.id))
+        {
+            let mut table = toml::Table::try_from(package)?;
+            if package.suffix.is_some() {
+                table.remove("repository");
+            }
+            packages.push(table);
+        }
+
+        Ok(ManifestFile {
+            include: &self.include,
+            defaults: &self.defaults,
+            registry: &self.registry,
+            vpm: &self.vpm,
+            packages,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content = self.to_file_string(path)?;
 
         std::fs::write(path, content).map_err(|e| Error::FileWrite {
             path: path.display().to_string(),
@@ -55,11 +220,21 @@ impl Manifest {
 
     fn validate(&self) -> Result<()> {
         self.vpm.validate()?;
+        self.registry.validate()?;
 
+        let gitea_host = self.registry.gitea_host();
         let mut seen_ids = HashSet::new();
         for package in &self.packages {
             package.validate()?;
             validation::validate_package_id_prefix(&package.id, &self.vpm.id)?;
+            if package.repository.provider == Provider::Gitea && gitea_host.is_none() {
+                return Err(Error::ConfigValidation(format!(
+                    "Package '{}' uses a gitea: repository, but [registry] gitea_url is not set",
+                    package.id
+                )));
+            }
+            self.defaults
+                .validate_host(package, gitea_host.as_deref())?;
 
             if !seen_ids.insert(&package.id) {
                 return Err(Error::ConfigValidation(format!(
@@ -73,6 +248,167 @@ impl Manifest {
     }
 }
 
+/// Reads a manifest or included file into a TOML table, whatever its format.
+fn read_table(path: &Path, format: ConfigFormat) -> Result<toml::Table> {
+    parse_table(&read_file(path)?, path, format)
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| Error::FileRead {
+        path: path.display().to_string(),
+        source: e,
+    })
+}
+
+/// Parses manifest `content` read from `path` into a TOML table.
+fn parse_table(content: &str, path: &Path, format: ConfigFormat) -> Result<toml::Table> {
+    let path_str = path.display().to_string();
+    match format {
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| Error::JsonParse {
+            source: path_str,
+            error: e,
+        }),
+        _ => toml::from_str(content).map_err(|e| Error::TomlParse {
+            path: path_str,
+            source: e,
+        }),
+    }
+}
+
+/// Reads `[defaults] repository_template` from a parsed manifest.
+fn repository_template(table: &toml::Table) -> Option<String> {
+    table
+        .get("defaults")?
+        .get("repository_template")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Fills in `repository` for every package that only sets `suffix`, so the
+/// rest of voyager only ever sees full repositories.
+fn apply_repository_template(table: &mut toml::Table, template: Option<&str>) -> Result<()> {
+    let Some(toml::Value::Array(packages)) = table.get_mut("packages") else {
+        return Ok(());
+    };
+
+    for package in packages.iter_mut().filter_map(toml::Value::as_table_mut) {
+        let Some(suffix) = package.get("suffix").and_then(toml::Value::as_str) else {
+            continue;
+        };
+        let id = package
+            .get("id")
+            .and_then(toml::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if package.contains_key("repository") {
+            return Err(Error::ConfigValidation(format!(
+                "Package '{}' sets both repository and suffix",
+                id
+            )));
+        }
+        let Some(template) = template else {
+            return Err(Error::ConfigValidation(format!(
+                "Package '{}' sets suffix but [defaults] repository_template is not set",
+                id
+            )));
+        };
+
+        let repository = template.replace("{suffix}", suffix);
+        if let Err(e) = Repository::parse(&repository) {
+            return Err(Error::ConfigValidation(format!(
+                "Package '{}' has invalid templated repository '{}': {}",
+                id, repository, e
+            )));
+        }
+        package.insert("repository".to_string(), toml::Value::String(repository));
+    }
+
+    Ok(())
+}
+
+/// Settings shared by all packages in the manifest.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Defaults {
+    /// Repository for packages that set `suffix`, with `{suffix}` replaced
+    /// (e.g. `myorg/{suffix}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository_template: Option<String>,
+    /// Hosts package repositories may live on; empty allows any supported host.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Defaults {
+    fn is_empty(&self) -> bool {
+        self.repository_template.is_none() && self.allowed_hosts.is_empty()
+    }
+
+    fn validate_host(&self, package: &Package, gitea_host: Option<&str>) -> Result<()> {
+        let host = package.repository.host().or(gitea_host).unwrap_or_default();
+        if self.allowed_hosts.is_empty() || self.allowed_hosts.iter().any(|h| h == host) {
+            return Ok(());
+        }
+        Err(Error::ConfigValidation(format!(
+            "Package '{}' repository host '{}' is not in allowed_hosts ({})",
+            package.id,
+            host,
+            self.allowed_hosts.join(", ")
+        )))
+    }
+}
+
+/// Where release assets and package URLs are downloaded from.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    /// URL prefixes replaced before downloading (e.g. a mirror of
+    /// `https://github.com/`). Lockfile URLs keep the original prefix.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub url_rewrite: IndexMap<String, String>,
+    /// Base URL of the Gitea or Forgejo instance `gitea:` repositories live on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitea_url: Option<String>,
+}
+
+impl Registry {
+    /// Reads only the `[registry]` table of the manifest at `path`, for
+    /// commands that need nothing else from it. The rest of the manifest is
+    /// neither deserialized nor validated.
+    pub fn load_as(path: &Path, format: ConfigFormat) -> Result<Self> {
+        let mut table = read_table(path, format.resolve(path))?;
+        let Some(registry) = table.remove("registry") else {
+            return Ok(Self::default());
+        };
+        let registry: Self = registry.try_into().map_err(|e| Error::TomlParse {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.url_rewrite.is_empty() && self.gitea_url.is_none()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (from, to) in &self.url_rewrite {
+            validation::validate_url(from)?;
+            validation::validate_url(to)?;
+        }
+        if let Some(gitea_url) = &self.gitea_url {
+            validation::validate_url(gitea_url)?;
+        }
+        Ok(())
+    }
+
+    /// Host of `gitea_url`, if one is configured.
+    fn gitea_host(&self) -> Option<String> {
+        let url = reqwest::Url::parse(self.gitea_url.as_deref()?).ok()?;
+        url.host_str().map(str::to_string)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Vpm {
     pub id: String,
@@ -107,6 +443,20 @@ impl Vpm {
 pub struct Package {
     pub id: String,
     pub repository: Repository,
+    /// Fills `repository` from `[defaults] repository_template` at load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Exact versions to lock; empty locks every release.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<String>,
+    /// VPM-style semver range (e.g. `>=2.0.0 <3.0.0`) versions must satisfy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<String>,
+    /// Release asset holding `package.json`; overrides the fetch default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_name: Option<String>,
 }
 
 impl Package {
@@ -117,8 +467,65 @@ impl Package {
 
         validation::validate_reverse_domain(&self.id)?;
 
+        for tag in &self.tags {
+            validation::validate_tag(tag)?;
+        }
+
+        for version in &self.versions {
+            if let Err(e) = Version::parse(version) {
+                return Err(Error::ConfigValidation(format!(
+                    "Package '{}' has invalid version '{}' in versions: {}",
+                    self.id, version, e
+                )));
+            }
+        }
+
+        if let Some(range) = &self.version_range
+            && validation::validate_vpm_dependency_range(range).is_err()
+        {
+            return Err(Error::ConfigValidation(format!(
+                "Package '{}' has invalid version_range '{}'",
+                self.id, range
+            )));
+        }
+
+        if self
+            .asset_name
+            .as_deref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(Error::ConfigValidation(format!(
+                "Package '{}' has an empty asset_name",
+                self.id
+            )));
+        }
+
         Ok(())
     }
+
+    /// Returns true if the package carries the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Returns true if the version may be locked under the `versions`
+    /// allowlist and `version_range`.
+    pub fn allows_version(&self, version: &str) -> bool {
+        let listed = self.versions.is_empty() || self.versions.iter().any(|v| v == version);
+        listed && self.in_version_range(version)
+    }
+
+    /// Returns the release asset to read, falling back to `default`.
+    pub fn asset_name<'a>(&'a self, default: &'a str) -> &'a str {
+        self.asset_name.as_deref().unwrap_or(default)
+    }
+
+    fn in_version_range(&self, version: &str) -> bool {
+        let Some(range) = &self.version_range else {
+            return true;
+        };
+        Version::parse(version).is_ok_and(|version| validation::vpm_range_matches(range, &version))
+    }
 }
 
 #[cfg(test)]
@@ -335,6 +742,507 @@ repository = "owner/repo2"
             );
         }
 
+        #[test]
+        fn loads_package_tags() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+tags = ["core", "samples"]
+"#;
+            let file = create_temp_manifest(content);
+            let manifest = Manifest::load(file.path()).unwrap();
+
+            assert!(manifest.packages[0].has_tag("core"));
+            assert!(!manifest.packages[0].has_tag("experimental"));
+        }
+
+        #[test]
+        fn fails_on_invalid_tag() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+tags = ["not a tag"]
+"#;
+            let file = create_temp_manifest(content);
+            let result = Manifest::load(file.path());
+
+            assert!(
+                matches!(result, Err(Error::ConfigValidation(msg)) if msg.contains("Invalid tag"))
+            );
+        }
+
+        #[test]
+        fn fails_on_non_semver_allowlisted_version() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+versions = ["1.0.0", "1.0"]
+"#;
+            let file = create_temp_manifest(content);
+            let result = Manifest::load(file.path());
+
+            assert!(
+                matches!(result, Err(Error::ConfigValidation(msg)) if msg.contains("invalid version '1.0'"))
+            );
+        }
+
+        #[test]
+        fn fails_on_invalid_version_range() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+version_range = ">=2.0.0, <three"
+"#;
+            let file = create_temp_manifest(content);
+            let result = Manifest::load(file.path());
+
+            assert!(
+                matches!(result, Err(Error::ConfigValidation(msg)) if msg.contains("invalid version_range"))
+            );
+        }
+
+        #[test]
+        fn version_range_accepts_vpm_syntax() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+version_range = ">=2.0.0 <3.0.0 || 4.x"
+"#;
+            let file = create_temp_manifest(content);
+            let manifest = Manifest::load(file.path()).unwrap();
+            let package = &manifest.packages[0];
+
+            assert!(package.allows_version("2.1.0"));
+            assert!(package.allows_version("4.2.0"));
+            assert!(!package.allows_version("3.0.0"));
+            assert!(!package.allows_version("1.9.0"));
+            assert!(!package.allows_version("not-a-version"));
+        }
+
+        #[test]
+        fn fails_on_empty_asset_name() {
+            let content = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+asset_name = ""
+"#;
+            let file = create_temp_manifest(content);
+            let result = Manifest::load(file.path());
+
+            assert!(
+                matches!(result, Err(Error::ConfigValidation(msg)) if msg.contains("empty asset_name"))
+            );
+        }
+
+        #[test]
+        fn loads_registry_url_rewrite() {
+            let content = |to: &str| {
+                format!(
+                    r#"
+[registry.url_rewrite]
+"https://github.com/" = "{to}"
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+"#
+                )
+            };
+            let file = create_temp_manifest(&content("https://mirror.example/gh/"));
+            let manifest = Manifest::load(file.path()).unwrap();
+            assert_eq!(
+                manifest.registry.url_rewrite["https://github.com/"],
+                "https://mirror.example/gh/"
+            );
+            assert!(
+                manifest
+                    .to_toml_string()
+                    .unwrap()
+                    .contains("[registry.url_rewrite]")
+            );
+
+            let invalid = create_temp_manifest(&content("mirror"));
+            assert!(matches!(
+                Manifest::load(invalid.path()),
+                Err(Error::InvalidUrl(url, _)) if url == "mirror"
+            ));
+        }
+
+        #[test]
+        fn merges_packages_from_included_file() {
+            let dir = tempfile::TempDir::new().unwrap();
+            std::fs::write(
+                dir.path().join("packages.toml"),
+                r#"
+[[packages]]
+id = "com.example.vpm.included"
+repository = "owner/included"
+"#,
+            )
+            .unwrap();
+            let config_path = dir.path().join("voyager.toml");
+            std::fs::write(
+                &config_path,
+                r#"
+include = ["packages.toml"]
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.own"
+repository = "owner/own"
+"#,
+            )
+            .unwrap();
+
+            let manifest = Manifest::load(&config_path).unwrap();
+            let ids: Vec<_> = manifest.packages.iter().map(|p| p.id.as_str()).collect();
+            assert_eq!(ids, vec!["com.example.vpm.own", "com.example.vpm.included"]);
+
+            let saved = manifest.to_toml_string().unwrap();
+            assert!(saved.contains("com.example.vpm.own"));
+            assert!(!saved.contains("com.example.vpm.included"));
+        }
+
+        #[test]
+        fn resolves_suffix_from_repository_template() {
+            let content = r#"
+[defaults]
+repository_template = "myorg/{suffix}"
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.tools"
+suffix = "tools"
+"#;
+            let file = create_temp_manifest(content);
+            let manifest = Manifest::load(file.path()).unwrap();
+
+            assert_eq!(manifest.packages[0].repository.to_string(), "myorg/tools");
+
+            let saved = manifest.to_toml_string().unwrap();
+            assert!(saved.contains("suffix = \"tools\""));
+            assert!(!saved.contains("repository = "));
+
+            let hash = crate::lock::compute_manifest_hash(file.path()).unwrap();
+            let retargeted = create_temp_manifest(&content.replace("myorg/", "otherorg/"));
+            let retargeted_hash = crate::lock::compute_manifest_hash(retargeted.path()).unwrap();
+            assert_ne!(hash, retargeted_hash);
+        }
+
+        #[test]
+        fn enforces_allowed_hosts() {
+            let content = |hosts: &str| {
+                format!(
+                    r#"
+[defaults]
+allowed_hosts = [{hosts}]
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+"#
+                )
+            };
+
+            let rejected = create_temp_manifest(&content(r#""gitlab.com""#));
+            assert!(matches!(
+                Manifest::load(rejected.path()),
+                Err(Error::ConfigValidation(msg))
+                    if msg.contains("host 'github.com' is not in allowed_hosts (gitlab.com)")
+            ));
+
+            let accepted = create_temp_manifest(&content(r#""gitlab.com", "github.com""#));
+            let manifest = Manifest::load(accepted.path()).unwrap();
+            assert_eq!(
+                manifest.defaults.allowed_hosts,
+                ["gitlab.com", "github.com"]
+            );
+        }
+
+        #[test]
+        fn registry_loads_without_the_rest_of_the_manifest() {
+            let file = create_temp_manifest(
+                r#"
+[registry.url_rewrite]
+"https://github.com/" = "https://mirror.example/gh/"
+
+[vpm]
+id = "not a reverse domain"
+"#,
+            );
+            let registry = Registry::load_as(file.path(), ConfigFormat::Auto).unwrap();
+            assert_eq!(
+                registry.url_rewrite["https://github.com/"],
+                "https://mirror.example/gh/"
+            );
+
+            let invalid = create_temp_manifest(
+                "[registry.url_rewrite]\n\"https://github.com/\" = \"mirror\"\n",
+            );
+            assert!(matches!(
+                Registry::load_as(invalid.path(), ConfigFormat::Auto),
+                Err(Error::InvalidUrl(url, _)) if url == "mirror"
+            ));
+        }
+
+        #[test]
+        fn gitea_repositories_need_gitea_url() {
+            let content = |registry: &str| {
+                format!(
+                    r#"
+{registry}
+[defaults]
+allowed_hosts = ["git.example.com"]
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "gitea:owner/repo"
+"#
+                )
+            };
+
+            let missing = create_temp_manifest(&content(""));
+            assert!(matches!(
+                Manifest::load(missing.path()),
+                Err(Error::ConfigValidation(msg)) if msg.contains("gitea_url is not set")
+            ));
+
+            let other_host = create_temp_manifest(&content(
+                "[registry]\ngitea_url = \"https://gitea.other.org\"\n",
+            ));
+            assert!(matches!(
+                Manifest::load(other_host.path()),
+                Err(Error::ConfigValidation(msg))
+                    if msg.contains("host 'gitea.other.org' is not in allowed_hosts")
+            ));
+
+            let configured = create_temp_manifest(&content(
+                "[registry]\ngitea_url = \"https://git.example.com/\"\n",
+            ));
+            let manifest = Manifest::load(configured.path()).unwrap();
+            assert_eq!(
+                manifest.registry.gitea_url.as_deref(),
+                Some("https://git.example.com/")
+            );
+        }
+
+        #[test]
+        fn fails_on_invalid_templated_repository() {
+            let content = r#"
+[defaults]
+repository_template = "{suffix}"
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.tools"
+suffix = "tools"
+"#;
+            let file = create_temp_manifest(content);
+            let result = Manifest::load(file.path());
+
+            assert!(
+                matches!(result, Err(Error::ConfigValidation(msg)) if msg.contains("invalid templated repository 'tools'"))
+            );
+        }
+
+        #[test]
+        fn fails_on_duplicate_package_id_across_included_files() {
+            let dir = tempfile::TempDir::new().unwrap();
+            std::fs::write(
+                dir.path().join("packages.toml"),
+                r#"
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/other"
+"#,
+            )
+            .unwrap();
+            let config_path = dir.path().join("voyager.toml");
+            std::fs::write(
+                &config_path,
+                r#"
+include = ["packages.toml"]
+
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+"#,
+            )
+            .unwrap();
+
+            let result = Manifest::load(&config_path);
+
+            assert!(matches!(
+                result,
+                Err(Error::ConfigValidation(msg))
+                    if msg.contains("Duplicate package ID") && msg.contains("packages.toml")
+            ));
+        }
+
+        const EQUIVALENT_TOML: &str = r#"
+[vpm]
+id = "com.example.vpm"
+name = "Example VPM"
+author = "Test Author"
+url = "https://example.com/vpm.json"
+
+[[packages]]
+id = "com.example.vpm.package"
+repository = "owner/repo"
+tags = ["core"]
+version_range = ">=1.0.0, <2.0.0"
+"#;
+
+        const EQUIVALENT_JSON: &str = r#"{
+  "vpm": {
+    "id": "com.example.vpm",
+    "name": "Example VPM",
+    "author": "Test Author",
+    "url": "https://example.com/vpm.json"
+  },
+  "packages": [
+    {
+      "id": "com.example.vpm.package",
+      "repository": "owner/repo",
+      "tags": ["core"],
+      "version_range": ">=1.0.0, <2.0.0"
+    }
+  ]
+}"#;
+
+        #[test]
+        fn loads_json_manifest_identically_to_toml() {
+            let dir = tempfile::TempDir::new().unwrap();
+            let toml_path = dir.path().join("voyager.toml");
+            let json_path = dir.path().join("voyager.json");
+            std::fs::write(&toml_path, EQUIVALENT_TOML).unwrap();
+            std::fs::write(&json_path, EQUIVALENT_JSON).unwrap();
+
+            let from_toml = Manifest::load(&toml_path).unwrap();
+            let from_json = Manifest::load(&json_path).unwrap();
+
+            assert_eq!(from_toml.format, ConfigFormat::Toml);
+            assert_eq!(from_json.format, ConfigFormat::Json);
+            assert_eq!(
+                from_toml.to_toml_string().unwrap(),
+                from_json.to_toml_string().unwrap()
+            );
+            assert_eq!(
+                crate::lock::compute_manifest_hash(&toml_path).unwrap(),
+                crate::lock::compute_manifest_hash(&json_path).unwrap()
+            );
+        }
+
+        #[test]
+        fn round_trips_json_manifest_in_json() {
+            let dir = tempfile::TempDir::new().unwrap();
+            let json_path = dir.path().join("voyager.json");
+            std::fs::write(&json_path, EQUIVALENT_JSON).unwrap();
+            let hash = crate::lock::compute_manifest_hash(&json_path).unwrap();
+
+            Manifest::load(&json_path)
+                .unwrap()
+                .save(&json_path)
+                .unwrap();
+
+            let saved = std::fs::read_to_string(&json_path).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&saved).unwrap();
+            assert_eq!(value["packages"][0]["version_range"], ">=1.0.0, <2.0.0");
+            assert_eq!(
+                crate::lock::compute_manifest_hash(&json_path).unwrap(),
+                hash
+            );
+        }
+
+        #[test]
+        fn loads_json_regardless_of_extension_when_format_is_given() {
+            let file = create_temp_manifest(EQUIVALENT_JSON);
+
+            assert!(matches!(
+                Manifest::load(file.path()),
+                Err(Error::TomlParse { .. })
+            ));
+            let manifest = Manifest::load_as(file.path(), ConfigFormat::Json).unwrap();
+            assert_eq!(manifest.packages[0].id, "com.example.vpm.package");
+        }
+
         #[test]
         fn fails_on_missing_file() {
             let result = Manifest::load("/nonexistent/path.toml");
@@ -1,4 +1,4 @@
 mod manifest;
 pub mod validation;
 
-pub use manifest::{Manifest, Package, Vpm};
+pub use manifest::{ConfigFormat, Manifest, Package, Registry, Vpm};
@@ -51,6 +51,24 @@ pub fn validate_package_id_prefix(package_id: &str, vpm_id: &str) -> Result<()>
     Ok(())
 }
 
+/// Validates that a package tag is a simple identifier.
+///
+/// Valid examples: "core", "samples", "experimental_2", "beta-tools"
+/// Invalid examples: "", "has space", "core!"
+pub fn validate_tag(tag: &str) -> Result<()> {
+    if tag.is_empty()
+        || !tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::ConfigValidation(format!(
+            "Invalid tag '{}': tags may only contain letters, digits, '-' or '_'",
+            tag
+        )));
+    }
+    Ok(())
+}
+
 /// Validates that a URL has a valid format.
 ///
 /// Valid examples: "http://example.com", "https://example.com/path"
@@ -87,12 +105,30 @@ pub fn validate_url(url: &str) -> Result<()> {
     Ok(())
 }
 
+/// Returns the lowercase host of `url`, or `None` if it has none or does not parse.
+pub fn url_host(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_ascii_lowercase())
+}
+
 /// Validates that a URL points to a ZIP archive.
 pub fn validate_zip_url(url: &str) -> Result<()> {
+    validate_zip_url_with(url, false)
+}
+
+/// Validates that a URL points to a ZIP archive, additionally rejecting
+/// plain `http://` URLs when `https_only` is set.
+pub fn validate_zip_url_with(url: &str, https_only: bool) -> Result<()> {
     validate_url(url)?;
     let parsed = Url::parse(url)
         .map_err(|e| Error::InvalidUrl(url.to_string(), format!("Invalid URL format: {e}")))?;
 
+    if https_only {
+        validate_https_url(url)?;
+    }
+
     let path = parsed.path();
     let file_name = path.rsplit('/').next().unwrap_or_default();
     let has_extension = file_name.contains('.');
@@ -109,25 +145,43 @@ pub fn validate_zip_url(url: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validates that a URL uses the `https` scheme.
+pub fn validate_https_url(url: &str) -> Result<()> {
+    let parsed = Url::parse(url)
+        .map_err(|e| Error::InvalidUrl(url.to_string(), format!("Invalid URL format: {e}")))?;
+    if parsed.scheme() != "https" {
+        return Err(Error::InvalidUrl(
+            url.to_string(),
+            "URL must use https://".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Validates that a Unity version string is in `MAJOR.MINOR` format.
 ///
 /// Valid examples: "2022.3", "2019.1", "6000.0"
 /// Invalid examples: "2022", "2022.3.1", "hello", "2022.x"
 pub fn validate_unity_version(version: &str) -> Result<()> {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() != 2 {
-        return Err(Error::ConfigValidation(format!(
+    parse_unity_version(version).map(|_| ())
+}
+
+/// Parses a Unity version in `MAJOR.MINOR` format into comparable parts.
+pub fn parse_unity_version(version: &str) -> Result<(u32, u32)> {
+    let err = || {
+        Error::ConfigValidation(format!(
             "Unity version '{version}' must be in MAJOR.MINOR format (e.g. \"2022.3\")"
-        )));
-    }
-    for part in parts {
+        ))
+    };
+
+    let (major, minor) = version.split_once('.').ok_or_else(err)?;
+    let parse = |part: &str| {
         if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
-            return Err(Error::ConfigValidation(format!(
-                "Unity version '{version}' must be in MAJOR.MINOR format (e.g. \"2022.3\")"
-            )));
+            return Err(err());
         }
-    }
-    Ok(())
+        part.parse::<u32>().map_err(|_| err())
+    };
+    Ok((parse(major)?, parse(minor)?))
 }
 
 /// Validates a Unity release suffix in `<UPDATE><RELEASE>` format.
@@ -193,42 +247,77 @@ pub fn validate_vpm_dependency_range(range: &str) -> Result<()> {
             )));
         }
 
-        if is_valid_hyphen_range(clause) {
-            continue;
-        }
-
-        let normalized = normalize_vpm_clause(clause);
-        if VersionReq::parse(&normalized).is_ok() {
-            continue;
+        if parse_vpm_clause(clause).is_none() {
+            return Err(Error::ConfigValidation(format!(
+                "VPM dependency range '{range}' is invalid"
+            )));
         }
+    }
 
-        // `semver::VersionReq` does not accept space-separated AND clauses.
-        // Convert `>=1.0.0 <2.0.0` to `>=1.0.0, <2.0.0`.
-        let comma_joined = normalized.split_whitespace().collect::<Vec<_>>().join(", ");
-        if !comma_joined.is_empty() && VersionReq::parse(&comma_joined).is_ok() {
-            continue;
-        }
+    Ok(())
+}
 
+/// `legacyFolders`/`legacyFiles` entries are Unity asset paths, which VCC
+/// migrates only when they use forward slashes and carry no stray whitespace
+/// or trailing slash.
+pub fn validate_legacy_path(path: &str) -> Result<()> {
+    if path.contains('\\') {
         return Err(Error::ConfigValidation(format!(
-            "VPM dependency range '{range}' is invalid"
+            "legacy path '{path}' must use '/' instead of '\\'"
+        )));
+    }
+    if path.trim() != path {
+        return Err(Error::ConfigValidation(format!(
+            "legacy path '{path}' has leading or trailing whitespace"
+        )));
+    }
+    if path.ends_with('/') {
+        return Err(Error::ConfigValidation(format!(
+            "legacy path '{path}' must not end with '/'"
         )));
     }
-
     Ok(())
 }
 
-fn is_valid_hyphen_range(clause: &str) -> bool {
-    let Some((left, right)) = clause.split_once(" - ") else {
-        return false;
-    };
+/// Returns whether `version` satisfies a VPM range accepted by
+/// [`validate_vpm_dependency_range`]; invalid ranges match nothing.
+pub fn vpm_range_matches(range: &str, version: &Version) -> bool {
+    range
+        .trim()
+        .split("||")
+        .filter_map(|clause| parse_vpm_clause(clause.trim()))
+        .any(|req| req.matches(version))
+}
+
+fn parse_vpm_clause(clause: &str) -> Option<VersionReq> {
+    if let Some(req) = parse_hyphen_range(clause) {
+        return Some(req);
+    }
+
+    let normalized = normalize_vpm_clause(clause);
+    if let Ok(req) = VersionReq::parse(&normalized) {
+        return Some(req);
+    }
+
+    // `semver::VersionReq` does not accept space-separated AND clauses.
+    // Convert `>=1.0.0 <2.0.0` to `>=1.0.0, <2.0.0`.
+    let comma_joined = normalized.split_whitespace().collect::<Vec<_>>().join(", ");
+    if comma_joined.is_empty() {
+        return None;
+    }
+    VersionReq::parse(&comma_joined).ok()
+}
+
+fn parse_hyphen_range(clause: &str) -> Option<VersionReq> {
+    let (left, right) = clause.split_once(" - ")?;
 
     let left = normalize_vpm_version_token(left.trim());
     let right = normalize_vpm_version_token(right.trim());
     if left.is_empty() || right.is_empty() {
-        return false;
+        return None;
     }
 
-    VersionReq::parse(&format!(">={left}, <={right}")).is_ok()
+    VersionReq::parse(&format!(">={left}, <={right}")).ok()
 }
 
 fn normalize_vpm_clause(clause: &str) -> String {
@@ -441,6 +530,43 @@ mod tests {
         fn valid_extensionless_download_url() {
             assert!(validate_zip_url("https://example.com/download/12345").is_ok());
         }
+
+        #[test]
+        fn accepts_http_by_default() {
+            assert!(validate_zip_url("http://example.com/pkg.zip").is_ok());
+            assert!(validate_zip_url_with("http://example.com/pkg.zip", false).is_ok());
+        }
+
+        #[test]
+        fn rejects_http_when_https_only() {
+            assert!(matches!(
+                validate_zip_url_with("http://example.com/pkg.zip", true),
+                Err(Error::InvalidUrl(_, reason)) if reason.contains("https")
+            ));
+            assert!(validate_zip_url_with("https://example.com/pkg.zip", true).is_ok());
+        }
+    }
+
+    mod tag {
+        use super::*;
+
+        #[test]
+        fn valid_simple_identifiers() {
+            assert!(validate_tag("core").is_ok());
+            assert!(validate_tag("beta-tools").is_ok());
+            assert!(validate_tag("experimental_2").is_ok());
+        }
+
+        #[test]
+        fn invalid_empty() {
+            assert!(validate_tag("").is_err());
+        }
+
+        #[test]
+        fn invalid_special_chars() {
+            assert!(validate_tag("has space").is_err());
+            assert!(validate_tag("core!").is_err());
+        }
     }
 
     mod unity_dependency_version {
@@ -577,5 +703,48 @@ mod tests {
         fn rejects_invalid() {
             assert!(validate_vpm_dependency_range("definitely-not-a-range").is_err());
         }
+
+        #[test]
+        fn matches_versions_with_vpm_syntax() {
+            let matches =
+                |range, version| vpm_range_matches(range, &Version::parse(version).unwrap());
+
+            assert!(matches(">=2.0.0", "2.0.0"));
+            assert!(!matches(">=2.0.0", "1.9.9"));
+            assert!(matches(">=2.0.0 <3.0.0", "2.5.0"));
+            assert!(!matches(">=2.0.0 <3.0.0", "3.0.0"));
+            assert!(matches("1.2.3 - 2.0.0", "2.0.0"));
+            assert!(matches("1.x || 3.x", "3.1.0"));
+            assert!(!matches("1.x || 3.x", "2.0.0"));
+            assert!(!matches("definitely-not-a-range", "1.0.0"));
+        }
+    }
+
+    mod legacy_path {
+        use super::*;
+
+        #[test]
+        fn accepts_unity_asset_paths() {
+            assert!(validate_legacy_path("Assets/Example/Editor").is_ok());
+            assert!(validate_legacy_path("Assets/Example/Runtime.cs").is_ok());
+        }
+
+        #[test]
+        fn rejects_windows_backslash_paths() {
+            let err = validate_legacy_path("Assets\\Example\\Editor").unwrap_err();
+            assert!(err.to_string().contains("Assets\\Example\\Editor"));
+            assert!(validate_legacy_path("Assets/Example\\").is_err());
+        }
+
+        #[test]
+        fn rejects_surrounding_whitespace() {
+            assert!(validate_legacy_path(" Assets/Example").is_err());
+            assert!(validate_legacy_path("Assets/Example\t").is_err());
+        }
+
+        #[test]
+        fn rejects_trailing_slash() {
+            assert!(validate_legacy_path("Assets/Example/").is_err());
+        }
     }
 }
@@ -1,31 +1,83 @@
 use crate::cli::ConfigPaths;
+use crate::config::Registry;
 use crate::error::Result;
-use crate::infra::{GitHubApi, GitHubClient};
+use crate::infra::{
+    GitHubApi, GitHubAuth, GitHubClient, GitLabClient, GiteaClient, ProviderClient,
+};
 use std::sync::Arc;
 
+/// Builds the release client from the `[registry]` settings of the manifest
+/// a command has loaded.
+type ClientFactory<G> = Box<dyn Fn(&Registry) -> Result<Arc<G>> + Send + Sync>;
+
 /// Application context holding shared dependencies.
 ///
 /// This struct serves as the dependency injection container for the application.
 /// It's constructed once at startup and passed to command handlers.
-pub struct AppContext<G: GitHubApi = GitHubClient> {
+pub struct AppContext<G: GitHubApi = ProviderClient> {
     /// Configuration file paths.
     pub paths: ConfigPaths,
-    /// GitHub client for API interactions.
-    pub github: Arc<G>,
+    /// Release client factory for API interactions, GitHub or otherwise.
+    client: ClientFactory<G>,
 }
 
-impl AppContext<GitHubClient> {
-    /// Create a new AppContext with GitHub dependency initialized.
-    pub fn new(paths: ConfigPaths, github_token: Option<&str>) -> Result<Self> {
-        let github = Arc::new(GitHubClient::new(github_token)?);
+impl AppContext<ProviderClient> {
+    /// Create a new AppContext with GitHub and GitLab tokens. `gitea:`
+    /// repositories use the instance in `[registry] gitea_url` anonymously.
+    pub fn new(paths: ConfigPaths, github_token: Option<&str>, gitlab_token: Option<&str>) -> Self {
+        Self::with_auth(
+            paths,
+            GitHubAuth::resolve(github_token, None),
+            gitlab_token,
+            None,
+        )
+    }
 
-        Ok(Self { paths, github })
+    /// Create a new AppContext whose GitHub client authenticates with `auth`.
+    /// The Gitea client talks to the manifest's `[registry] gitea_url`.
+    pub fn with_auth(
+        paths: ConfigPaths,
+        auth: GitHubAuth,
+        gitlab_token: Option<&str>,
+        gitea_token: Option<&str>,
+    ) -> Self {
+        let gitlab_token = gitlab_token.map(str::to_string);
+        let gitea_token = gitea_token.map(str::to_string);
+        Self::with_client_factory(paths, move |registry| {
+            Ok(ProviderClient::new(
+                GitHubClient::with_auth(auth.clone())?,
+                GitLabClient::new(gitlab_token.as_deref())?,
+                GiteaClient::new(registry.gitea_url.as_deref(), gitea_token.as_deref())?,
+            ))
+        })
     }
 }
 
-impl<G: GitHubApi> AppContext<G> {
+impl<G: GitHubApi + 'static> AppContext<G> {
     /// Create an AppContext with only a custom GitHub dependency.
     pub fn with_github(paths: ConfigPaths, github: Arc<G>) -> Self {
-        Self { paths, github }
+        Self {
+            paths,
+            client: Box::new(move |_| Ok(Arc::clone(&github))),
+        }
+    }
+
+    /// Create an AppContext whose release client is built by `build` once a
+    /// command has loaded the manifest's `[registry]` settings.
+    pub fn with_client_factory(
+        paths: ConfigPaths,
+        build: impl Fn(&Registry) -> Result<G> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            paths,
+            client: Box::new(move |registry| build(registry).map(Arc::new)),
+        }
+    }
+}
+
+impl<G: GitHubApi> AppContext<G> {
+    /// Returns the release client for the manifest's `[registry]` settings.
+    pub fn github(&self, registry: &Registry) -> Result<Arc<G>> {
+        (self.client)(registry)
     }
 }
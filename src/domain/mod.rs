@@ -1,5 +1,5 @@
 mod release;
 mod repository;
 
-pub use release::Release;
-pub use repository::{Repository, RepositoryParseError};
+pub use release::{Release, strip_build_metadata};
+pub use repository::{GITHUB_HOST, GITLAB_HOST, Provider, Repository, RepositoryParseError};
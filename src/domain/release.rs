@@ -19,6 +19,11 @@ impl Release {
         self.tag.strip_prefix('v').unwrap_or(&self.tag)
     }
 
+    /// Returns the version with any `+build` metadata removed.
+    pub fn version_without_build_metadata(&self) -> &str {
+        strip_build_metadata(self.version())
+    }
+
     pub fn asset_url(&self) -> Option<&str> {
         self.asset_url.as_deref()
     }
@@ -34,6 +39,11 @@ impl Release {
     }
 }
 
+/// Removes SemVer build metadata (`+...`) from a version string.
+pub fn strip_build_metadata(version: &str) -> &str {
+    version.split_once('+').map_or(version, |(base, _)| base)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,6 +82,22 @@ mod tests {
         }
     }
 
+    mod version_without_build_metadata {
+        use super::*;
+
+        #[test]
+        fn strips_build_metadata() {
+            let release = Release::new("v1.2.3-beta.1+build.123".to_string(), None);
+            assert_eq!(release.version_without_build_metadata(), "1.2.3-beta.1");
+        }
+
+        #[test]
+        fn returns_version_without_build_metadata_unchanged() {
+            let release = Release::new("v1.2.3".to_string(), None);
+            assert_eq!(release.version_without_build_metadata(), "1.2.3");
+        }
+    }
+
     mod tag {
         use super::*;
 
@@ -1,10 +1,33 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use std::fmt;
 
+/// Git hosting service a repository's releases are read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Provider {
+    #[default]
+    GitHub,
+    GitLab,
+    /// A self-hosted Gitea or Forgejo instance configured in the manifest.
+    Gitea,
+}
+
+impl Provider {
+    /// Prefix selecting the provider in a repository string, e.g.
+    /// `gitlab:owner/repo`. GitHub repositories are written without one.
+    fn prefix(self) -> Option<&'static str> {
+        match self {
+            Self::GitHub => None,
+            Self::GitLab => Some("gitlab:"),
+            Self::Gitea => Some("gitea:"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Repository {
     pub owner: String,
     pub repo: String,
+    pub provider: Provider,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,7 +51,7 @@ impl fmt::Display for RepositoryParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Invalid repository format '{}', expected 'owner/repo'",
+            "Invalid repository format '{}', expected 'owner/repo', a GitHub URL, 'gitlab:owner/repo' or 'gitea:owner/repo'",
             self.input
         )
     }
@@ -36,9 +59,37 @@ impl fmt::Display for RepositoryParseError {
 
 impl std::error::Error for RepositoryParseError {}
 
+/// Host of repositories written as `owner/repo`.
+pub const GITHUB_HOST: &str = "github.com";
+/// Host of repositories written as `gitlab:owner/repo`.
+pub const GITLAB_HOST: &str = "gitlab.com";
+
 impl Repository {
+    /// Host the repository lives on, or `None` for Gitea, whose host comes
+    /// from the manifest's `[registry] gitea_url`.
+    pub fn host(&self) -> Option<&str> {
+        match self.provider {
+            Provider::GitHub => Some(GITHUB_HOST),
+            Provider::GitLab => Some(GITLAB_HOST),
+            Provider::Gitea => None,
+        }
+    }
+
+    /// Parses `owner/repo`, `gitlab:owner/repo` or `gitea:owner/repo`.
+    ///
+    /// GitHub repositories may also be given as a clone or web URL, such as
+    /// `https://github.com/owner/repo.git` or `git@github.com:owner/repo.git`.
     pub fn parse(s: &str) -> Result<Self, RepositoryParseError> {
-        let parts: Vec<&str> = s.split('/').collect();
+        let (provider, path) = [Provider::GitLab, Provider::Gitea]
+            .into_iter()
+            .find_map(|provider| {
+                let path = s.strip_prefix(provider.prefix()?)?;
+                Some((provider, path))
+            })
+            .or_else(|| Some((Provider::GitHub, strip_github_url(s)?)))
+            .unwrap_or((Provider::GitHub, s));
+
+        let parts: Vec<&str> = path.split('/').collect();
         if parts.len() != 2 {
             return Err(RepositoryParseError::new(s));
         }
@@ -50,17 +101,37 @@ impl Repository {
             return Err(RepositoryParseError::new(s));
         }
 
-        if !is_valid_owner(owner) || !is_valid_repo(repo) {
+        // GitLab namespaces and Gitea owners allow the same characters as
+        // repository names.
+        let valid_owner = match provider {
+            Provider::GitHub => is_valid_owner(owner),
+            Provider::GitLab | Provider::Gitea => is_valid_repo(owner),
+        };
+        if !valid_owner || !is_valid_repo(repo) {
             return Err(RepositoryParseError::new(s));
         }
 
         Ok(Self {
             owner: owner.to_string(),
             repo: repo.to_string(),
+            provider,
         })
     }
 }
 
+/// Returns the `owner/repo` part of a GitHub web or clone URL.
+fn strip_github_url(s: &str) -> Option<&str> {
+    let path = [
+        "https://github.com/",
+        "http://github.com/",
+        "git@github.com:",
+    ]
+    .into_iter()
+    .find_map(|prefix| s.strip_prefix(prefix))?;
+    let path = path.strip_suffix('/').unwrap_or(path);
+    Some(path.strip_suffix(".git").unwrap_or(path))
+}
+
 fn is_valid_owner(owner: &str) -> bool {
     if owner.len() > 39 {
         return false;
@@ -80,6 +151,9 @@ fn is_valid_repo(repo: &str) -> bool {
 
 impl fmt::Display for Repository {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = self.provider.prefix() {
+            f.write_str(prefix)?;
+        }
         write!(f, "{}/{}", self.owner, self.repo)
     }
 }
@@ -190,6 +264,68 @@ mod tests {
             let result = Repository::parse("owner/my repo");
             assert!(result.is_err());
         }
+
+        #[test]
+        fn parses_gitlab_prefix() {
+            let repo = Repository::parse("gitlab:my.group/repo").unwrap();
+            assert_eq!(repo.provider, Provider::GitLab);
+            assert_eq!(repo.owner, "my.group");
+            assert_eq!(repo.repo, "repo");
+            assert_eq!(repo.host(), Some(GITLAB_HOST));
+            assert_eq!(repo.to_string(), "gitlab:my.group/repo");
+
+            let github = Repository::parse("owner/repo").unwrap();
+            assert_eq!(github.provider, Provider::GitHub);
+            assert_eq!(github.host(), Some(GITHUB_HOST));
+        }
+
+        #[test]
+        fn parses_gitea_prefix() {
+            let repo = Repository::parse("gitea:my_org/repo").unwrap();
+            assert_eq!(repo.provider, Provider::Gitea);
+            assert_eq!(repo.owner, "my_org");
+            assert_eq!(repo.repo, "repo");
+            assert_eq!(repo.host(), None);
+            assert_eq!(repo.to_string(), "gitea:my_org/repo");
+        }
+
+        #[test]
+        fn parses_github_web_url() {
+            for input in [
+                "https://github.com/owner/repo",
+                "https://github.com/owner/repo/",
+                "https://github.com/owner/repo.git",
+                "http://github.com/owner/repo",
+            ] {
+                let repo = Repository::parse(input).unwrap();
+                assert_eq!(repo.provider, Provider::GitHub, "{input}");
+                assert_eq!(repo.to_string(), "owner/repo", "{input}");
+            }
+        }
+
+        #[test]
+        fn parses_github_ssh_url() {
+            let repo = Repository::parse("git@github.com:my-owner/my.repo.git").unwrap();
+            assert_eq!(repo.provider, Provider::GitHub);
+            assert_eq!(repo.owner, "my-owner");
+            assert_eq!(repo.repo, "my.repo");
+        }
+
+        #[test]
+        fn fails_on_github_url_without_repo() {
+            assert!(Repository::parse("owner").is_err());
+            assert!(Repository::parse("https://github.com/owner").is_err());
+            assert!(Repository::parse("git@github.com:owner.git").is_err());
+            assert!(Repository::parse("https://github.com/owner/repo/tree/main").is_err());
+            assert!(Repository::parse("https://example.com/owner/repo").is_err());
+        }
+
+        #[test]
+        fn fails_on_gitlab_prefix_without_path() {
+            assert!(Repository::parse("gitlab:").is_err());
+            assert!(Repository::parse("gitlab:owner").is_err());
+            assert!(Repository::parse("gitea:").is_err());
+        }
     }
 
     mod display {
@@ -1,5 +1,35 @@
+use std::fmt;
 use thiserror::Error;
 
+/// A release that `fetch` could not lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchFailure {
+    pub package_id: String,
+    pub tag: String,
+    pub reason: String,
+}
+
+impl fmt::Display for FetchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.package_id, self.tag, self.reason)
+    }
+}
+
+fn lock_outcome(lock_saved: bool) -> &'static str {
+    if lock_saved {
+        "successfully fetched versions were saved to the lockfile"
+    } else {
+        "lockfile was not updated"
+    }
+}
+
+fn list_fetch_failures(failures: &[FetchFailure]) -> String {
+    failures
+        .iter()
+        .map(|failure| format!("\n  - {}", failure))
+        .collect()
+}
+
 /// Exit codes following sysexits.h conventions where applicable.
 /// See: https://man.freebsd.org/cgi/man.cgi?query=sysexits
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,6 +46,8 @@ impl ExitCode {
     pub const UNAVAILABLE: Self = Self(69); // EX_UNAVAILABLE
     /// Data format error (JSON/TOML parse error)
     pub const DATA: Self = Self(65); // EX_DATAERR
+    /// Packages depend on each other in a cycle
+    pub const DEPENDENCY_CYCLE: Self = Self(3);
 }
 
 impl From<ExitCode> for std::process::ExitCode {
@@ -60,7 +92,9 @@ pub enum Error {
     #[error("Config validation failed: {0}")]
     ConfigValidation(String),
 
-    #[error("Invalid repository format '{0}', expected 'owner/repo'")]
+    #[error(
+        "Invalid repository format '{0}', expected 'owner/repo', a GitHub URL, 'gitlab:owner/repo' or 'gitea:owner/repo'"
+    )]
     InvalidRepository(String),
 
     #[error(
@@ -105,14 +139,38 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[error("Asset '{url}' is larger than the {limit}-byte limit")]
+    AssetTooLarge { url: String, limit: u64 },
+
     #[error("URL validation failed: {count} URL(s) are not accessible")]
     UrlValidation { count: usize },
 
-    #[error("Repository '{0}' not found on GitHub")]
+    #[error("Repository '{0}' not found")]
     RepositoryNotFound(String),
 
-    #[error("Fetch completed with {count} failed release(s); lockfile was not updated")]
-    FetchPartialFailure { count: usize },
+    #[error(
+        "Fetch completed with {} failed release(s); {}{}",
+        failures.len(),
+        lock_outcome(*lock_saved),
+        list_fetch_failures(failures)
+    )]
+    FetchPartialFailure {
+        failures: Vec<FetchFailure>,
+        /// Whether the successful results were saved anyway (`--on-failure keep`).
+        lock_saved: bool,
+    },
+
+    #[error("Lock verification failed: {count} locked version(s) do not match")]
+    LockVerification { count: usize },
+
+    #[error("{count} package(s) have releases that are not locked")]
+    Outdated { count: usize },
+
+    #[error("{count} package(s) differ between the indexes")]
+    IndexesDiffer { count: usize },
+
+    #[error("Dependency cycle detected: {}", path.join(" -> "))]
+    DependencyCycle { path: Vec<String> },
 
     #[error("Manifest has been modified outside of voyager")]
     ManifestHashMismatch,
@@ -134,7 +192,10 @@ impl Error {
             Error::TomlParse { .. }
             | Error::TomlSerialize { .. }
             | Error::JsonParse { .. }
-            | Error::JsonSerialize(_) => ExitCode::DATA,
+            | Error::JsonSerialize(_)
+            | Error::LockVerification { .. }
+            | Error::AssetTooLarge { .. }
+            | Error::Outdated { .. } => ExitCode::DATA,
             // Configuration/validation errors
             Error::ConfigValidation(_)
             | Error::InvalidRepository(_)
@@ -147,8 +208,43 @@ impl Error {
             | Error::RepositoryNotFound(_)
             | Error::UrlValidation { .. }
             | Error::FetchPartialFailure { .. } => ExitCode::UNAVAILABLE,
+            Error::DependencyCycle { .. } => ExitCode::DEPENDENCY_CYCLE,
             // Other errors
-            Error::PackageJsonNotFound { .. } | Error::RuntimeInit(_) => ExitCode::FAILURE,
+            Error::PackageJsonNotFound { .. }
+            | Error::IndexesDiffer { .. }
+            | Error::RuntimeInit(_) => ExitCode::FAILURE,
+        }
+    }
+
+    /// Returns a stable identifier for this error, the name of its variant,
+    /// for machine-readable output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(..) => "Io",
+            Error::FileRead { .. } => "FileRead",
+            Error::FileWrite { .. } => "FileWrite",
+            Error::TomlParse { .. } => "TomlParse",
+            Error::TomlSerialize { .. } => "TomlSerialize",
+            Error::ConfigValidation(..) => "ConfigValidation",
+            Error::InvalidRepository(..) => "InvalidRepository",
+            Error::InvalidPackageId(..) => "InvalidPackageId",
+            Error::InvalidUrl(..) => "InvalidUrl",
+            Error::GitHub { .. } => "GitHub",
+            Error::Http { .. } => "Http",
+            Error::JsonParse { .. } => "JsonParse",
+            Error::JsonSerialize(..) => "JsonSerialize",
+            Error::PackageJsonNotFound { .. } => "PackageJsonNotFound",
+            Error::OutputWrite { .. } => "OutputWrite",
+            Error::AssetTooLarge { .. } => "AssetTooLarge",
+            Error::UrlValidation { .. } => "UrlValidation",
+            Error::RepositoryNotFound(..) => "RepositoryNotFound",
+            Error::FetchPartialFailure { .. } => "FetchPartialFailure",
+            Error::LockVerification { .. } => "LockVerification",
+            Error::Outdated { .. } => "Outdated",
+            Error::IndexesDiffer { .. } => "IndexesDiffer",
+            Error::DependencyCycle { .. } => "DependencyCycle",
+            Error::ManifestHashMismatch => "ManifestHashMismatch",
+            Error::RuntimeInit(..) => "RuntimeInit",
         }
     }
 }
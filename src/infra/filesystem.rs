@@ -119,6 +119,19 @@ where
     Ok(())
 }
 
+#[instrument(skip(content), fields(path = %path.as_ref().display()))]
+pub fn write_text<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
+    let path = path.as_ref();
+
+    write_atomic_file(path, content).map_err(|e| Error::OutputWrite {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+
+    debug!("Successfully wrote file");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
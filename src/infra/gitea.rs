@@ -0,0 +1,293 @@
+use super::github::GitHubApi;
+use super::http::{HttpTimeouts, build_http_client, same_origin};
+use super::retry::{RetryObserver, retry_download};
+use crate::domain::{Release, Repository};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+/// Releases requested per page; Gitea caps `limit` at 50 by default.
+const RELEASES_PER_PAGE: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct GiteaRelease {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<GiteaAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Reads releases from the API of a self-hosted Gitea or Forgejo instance.
+///
+/// The instance is the manifest's `[registry] gitea_url`; without one, every
+/// `gitea:` repository is rejected.
+pub struct GiteaClient {
+    http: Client,
+    api_base: Option<String>,
+    token: Option<String>,
+}
+
+impl GiteaClient {
+    pub fn new(base_url: Option<&str>, token: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            http: build_http_client(HttpTimeouts::default(), "gitea client initialization")?,
+            api_base: base_url.map(|url| format!("{}/api/v1", url.trim_end_matches('/'))),
+            token: token.map(str::to_string),
+        })
+    }
+
+    /// Rebuilds the client with other timeouts for API requests and asset
+    /// downloads.
+    pub fn with_timeouts(mut self, timeouts: HttpTimeouts) -> Result<Self> {
+        self.http = build_http_client(timeouts, "gitea client initialization")?;
+        Ok(self)
+    }
+
+    fn repo_url(&self, repo: &Repository) -> Result<String> {
+        let api_base = self.api_base.as_deref().ok_or_else(|| {
+            Error::ConfigValidation(format!(
+                "Repository '{repo}' needs [registry] gitea_url in the manifest"
+            ))
+        })?;
+        Ok(format!("{api_base}/repos/{}/{}", repo.owner, repo.repo))
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.header("Authorization", format!("token {token}")),
+            None => request,
+        }
+    }
+
+    async fn get_api(&self, repo: &Repository, url: &str) -> Result<reqwest::Response> {
+        let http_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        let response = self
+            .authorized(self.http.get(url))
+            .send()
+            .await
+            .map_err(http_error)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::RepositoryNotFound(repo.to_string()));
+        }
+        response.error_for_status().map_err(http_error)
+    }
+
+    #[instrument(skip(self), fields(%repo, %asset_name))]
+    pub async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+        let repo_url = self.repo_url(repo)?;
+        let mut result = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            debug!(page, "Fetching releases page");
+
+            let url = format!("{repo_url}/releases?limit={RELEASES_PER_PAGE}&page={page}");
+            let body = self
+                .get_api(repo, &url)
+                .await?
+                .text()
+                .await
+                .map_err(|e| Error::Http {
+                    url: url.clone(),
+                    source: e,
+                })?;
+            let releases: Vec<GiteaRelease> =
+                serde_json::from_str(&body).map_err(|e| Error::JsonParse {
+                    source: url.clone(),
+                    error: e,
+                })?;
+
+            for release in &releases {
+                let asset_url = release
+                    .assets
+                    .iter()
+                    .find(|asset| asset.name == asset_name)
+                    .map(|asset| asset.browser_download_url.clone());
+
+                result.push(Release::new(release.tag_name.clone(), asset_url));
+            }
+
+            if releases.len() < RELEASES_PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+
+        debug!(count = result.len(), "Found releases");
+        Ok(result)
+    }
+
+    #[instrument(skip(self), fields(%repo))]
+    pub async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        self.get_api(repo, &self.repo_url(repo)?).await?;
+
+        debug!("Repository verified");
+        Ok(())
+    }
+
+    async fn fetch_response(&self, url: &str) -> Result<reqwest::Response> {
+        let http_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        self.authorized(self.http.get(url))
+            .send()
+            .await
+            .map_err(http_error)?
+            .error_for_status()
+            .map_err(http_error)
+    }
+
+    async fn fetch_text(&self, url: &str) -> Result<String> {
+        self.fetch_response(url)
+            .await?
+            .text()
+            .await
+            .map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            })
+    }
+
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let bytes = self
+            .fetch_response(url)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            })?;
+        Ok(bytes.to_vec())
+    }
+}
+
+#[async_trait]
+impl GitHubApi for GiteaClient {
+    async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+        GiteaClient::get_releases(self, repo, asset_name).await
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        max_concurrent: usize,
+        max_retries: u32,
+        retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        stream::iter(releases)
+            .map(|release| async move {
+                let result = match release.asset_url() {
+                    Some(url) => {
+                        retry_download(url, max_retries, retry_observer, || self.fetch_text(url))
+                            .await
+                    }
+                    None => Err(Error::PackageJsonNotFound {
+                        tag: release.tag().to_string(),
+                    }),
+                };
+                (release, result)
+            })
+            .buffer_unordered(max_concurrent)
+            .collect()
+            .await
+    }
+
+    async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        GiteaClient::verify_repository(self, repo).await
+    }
+
+    async fn download_file(&self, url: &str, max_retries: u32) -> Result<Vec<u8>> {
+        retry_download(url, max_retries, None, || self.fetch_bytes(url)).await
+    }
+
+    fn serves_url(&self, url: &str) -> bool {
+        self.api_base
+            .as_deref()
+            .is_some_and(|api_base| same_origin(url, api_base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn can_bind_localhost() -> bool {
+        std::net::TcpListener::bind("127.0.0.1:0").is_ok()
+    }
+
+    fn gitea_client(server: &MockServer) -> GiteaClient {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        GiteaClient::new(Some(&format!("{}/", server.uri())), Some("secret")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn lists_releases_and_downloads_package_json_asset() {
+        if !can_bind_localhost() {
+            return;
+        }
+        let server = MockServer::start().await;
+        let asset_url = format!("{}/attachments/package.json", server.uri());
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/org/repo/releases"))
+            .and(query_param("page", "1"))
+            .and(header("Authorization", "token secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "tag_name": "v1.1.0",
+                    "assets": [
+                        { "name": "notes.txt", "browser_download_url": "https://example.com/notes.txt" },
+                        { "name": "package.json", "browser_download_url": asset_url }
+                    ]
+                },
+                { "tag_name": "v1.0.0", "assets": [] }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/attachments/package.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"version\":\"1.1.0\"}"))
+            .mount(&server)
+            .await;
+
+        let client = gitea_client(&server);
+        let repo = Repository::parse("gitea:org/repo").unwrap();
+        let releases = client.get_releases(&repo, "package.json").await.unwrap();
+
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].version(), "1.1.0");
+        assert_eq!(releases[0].asset_url(), Some(asset_url.as_str()));
+        assert_eq!(releases[1].asset_url(), None);
+
+        let downloaded = GitHubApi::download_assets(&client, releases, 2, 0, None).await;
+        let body = downloaded
+            .iter()
+            .find(|(release, _)| release.version() == "1.1.0")
+            .map(|(_, result)| result.as_ref().unwrap().clone());
+        assert_eq!(body.as_deref(), Some("{\"version\":\"1.1.0\"}"));
+    }
+
+    #[tokio::test]
+    async fn requires_a_configured_instance() {
+        let client = GiteaClient::new(None, None).unwrap();
+        let repo = Repository::parse("gitea:org/repo").unwrap();
+
+        let err = client.verify_repository(&repo).await.unwrap_err();
+
+        assert!(matches!(err, Error::ConfigValidation(msg) if msg.contains("gitea_url")));
+    }
+}
@@ -1,11 +1,22 @@
-use super::http::build_http_client;
-use super::retry::retry_backoff_delay;
+use super::http::{HttpTimeouts, build_http_client};
+use super::release_cache::{CachedPage, ListedAsset, ListedRelease, ReleaseCache};
+use super::retry::{HostRetries, RetryObserver, retries_for_url, retry_download};
+use super::url_rewrite::UrlRewrite;
 use crate::domain::{Release, Repository};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
+use jsonwebtoken::EncodingKey;
 use octocrab::Octocrab;
+use octocrab::models::{AppId, InstallationId};
+use regex::Regex;
 use reqwest::Client;
+use reqwest::StatusCode;
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tracing::{debug, info, instrument, warn};
@@ -15,17 +26,137 @@ use mockall::automock;
 
 /// Minimum remaining API calls before waiting for rate limit reset.
 const RATE_LIMIT_BUFFER: u64 = 10;
-const DOWNLOAD_TIMEOUT_SECS: u64 = 30;
+/// Releases requested per page; GitHub caps `per_page` at 100.
+const RELEASES_PER_PAGE: usize = 100;
+/// Default timeout for downloading a release asset.
+pub const DEFAULT_ASSET_TIMEOUT_SECS: u64 = 30;
 
-fn should_retry_download_error(error: &Error) -> bool {
-    match error {
-        Error::Http { source, .. } => {
-            if let Some(status) = source.status() {
-                return status.is_server_error() || status.as_u16() == 429;
+/// How the release asset to download is picked among a release's assets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssetMatchStrategy {
+    /// The asset whose name equals the configured asset name.
+    #[default]
+    Exact,
+    /// The first asset of the release, regardless of name.
+    First,
+    /// The largest asset of the release, regardless of name.
+    Largest,
+}
+
+/// Picks the asset to download from a release's assets. A `regex` takes
+/// precedence over `asset_name` and `strategy`; when several assets match it,
+/// the first one wins.
+fn select_asset<'a>(
+    assets: &'a [ListedAsset],
+    asset_name: &str,
+    strategy: AssetMatchStrategy,
+    regex: Option<&Regex>,
+) -> Option<&'a ListedAsset> {
+    if let Some(regex) = regex {
+        let mut matches = assets.iter().filter(|a| regex.is_match(&a.name));
+        let first = matches.next()?;
+        let others: Vec<&str> = matches.map(|a| a.name.as_str()).collect();
+        if !others.is_empty() {
+            warn!(
+                asset = %first.name,
+                ignored = %others.join(", "),
+                "Multiple assets match the asset regex; using the first"
+            );
+        }
+        return Some(first);
+    }
+    match strategy {
+        AssetMatchStrategy::Exact => assets.iter().find(|a| a.name == asset_name),
+        AssetMatchStrategy::First => assets.first(),
+        // Reversed so the earliest asset wins a size tie.
+        AssetMatchStrategy::Largest => assets.iter().rev().max_by_key(|a| a.size),
+    }
+}
+
+/// GitHub's core API rate limit as of the last response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Calls left in the current window.
+    pub remaining: u64,
+    /// When the window resets, in Unix seconds.
+    pub reset: u64,
+}
+
+/// Credentials for authenticating as a GitHub App installation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubAppAuth {
+    pub app_id: u64,
+    pub installation_id: u64,
+    /// PEM-encoded RSA private key of the app.
+    pub private_key_path: PathBuf,
+}
+
+/// How the GitHub API client authenticates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum GitHubAuth {
+    #[default]
+    Anonymous,
+    Token(String),
+    App(GitHubAppAuth),
+}
+
+impl GitHubAuth {
+    /// Prefers app credentials, then a personal access token, then anonymous
+    /// access.
+    pub fn resolve(token: Option<&str>, app: Option<GitHubAppAuth>) -> Self {
+        match (app, token) {
+            (Some(app), _) => Self::App(app),
+            (None, Some(token)) => Self::Token(token.to_string()),
+            (None, None) => Self::Anonymous,
+        }
+    }
+
+    fn build_octocrab(
+        &self,
+        api_timeouts: HttpTimeouts,
+        api_base: Option<&str>,
+    ) -> Result<Octocrab> {
+        let client_error = |e| Error::GitHub {
+            message: "Failed to initialize GitHub client".to_string(),
+            source: e,
+        };
+        let mut builder = Octocrab::builder()
+            .set_read_timeout(Some(api_timeouts.request))
+            .set_connect_timeout(Some(api_timeouts.connect));
+        if let Some(api_base) = api_base {
+            builder = builder.base_uri(api_base).map_err(client_error)?;
+        }
+
+        match self {
+            Self::Anonymous => builder.build().map_err(client_error),
+            Self::Token(token) => builder
+                .personal_token(token.clone())
+                .build()
+                .map_err(client_error),
+            Self::App(app) => {
+                let path = app.private_key_path.display().to_string();
+                let pem = std::fs::read(&app.private_key_path).map_err(|e| Error::FileRead {
+                    path: path.clone(),
+                    source: e,
+                })?;
+                let key = EncodingKey::from_rsa_pem(&pem).map_err(|e| {
+                    Error::ConfigValidation(format!(
+                        "Invalid GitHub App private key '{}': {}",
+                        path, e
+                    ))
+                })?;
+
+                // Installation tokens are minted from the app JWT on first use.
+                builder
+                    .app(AppId(app.app_id), key)
+                    .build()
+                    .and_then(|client| client.installation(InstallationId(app.installation_id)))
+                    .map_err(|e| Error::GitHub {
+                        message: "Failed to initialize GitHub App client".to_string(),
+                        source: e,
+                    })
             }
-            source.is_timeout() || source.is_connect() || source.is_request()
         }
-        _ => false,
     }
 }
 
@@ -33,7 +164,7 @@ fn should_retry_download_error(error: &Error) -> bool {
 ///
 /// This trait abstracts the GitHub client operations, allowing for:
 /// - Easier unit testing with mock implementations
-/// - Potential support for other git hosting providers (GitLab, etc.)
+/// - Other git hosting providers (see `GitLabClient` and `ProviderClient`)
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait GitHubApi: Send + Sync {
@@ -45,50 +176,189 @@ pub trait GitHubApi: Send + Sync {
     /// Returns a vector of tuples containing:
     /// - The release information
     /// - Result containing raw content string
-    async fn download_assets(
+    ///
+    /// `retry_observer` is notified before each retry backoff wait.
+    async fn download_assets<'a>(
         &self,
         releases: Vec<Release>,
         max_concurrent: usize,
         max_retries: u32,
+        retry_observer: Option<&'a dyn RetryObserver>,
     ) -> Vec<(Release, Result<String>)>;
 
     /// Verifies that a repository exists and is accessible on GitHub.
     async fn verify_repository(&self, repo: &Repository) -> Result<()>;
+
+    /// Downloads a file such as a package zip and returns its bytes.
+    async fn download_file(&self, url: &str, max_retries: u32) -> Result<Vec<u8>>;
+
+    /// Returns the API rate limit seen so far, if the client tracks one and
+    /// has made a request.
+    fn rate_limit(&self) -> Option<RateLimitStatus> {
+        None
+    }
+
+    /// Writes release listings recorded during this run to the release cache
+    /// file, if the client keeps one.
+    fn save_release_cache(&self) {}
+
+    /// Whether downloads from `url` should go through this client, so its
+    /// token is sent only to its own host.
+    fn serves_url(&self, _url: &str) -> bool {
+        false
+    }
+}
+
+/// A release cache and the file it is persisted to.
+struct ReleaseCacheFile {
+    path: PathBuf,
+    cache: Mutex<ReleaseCache>,
 }
 
 pub struct GitHubClient {
     octocrab: Octocrab,
+    auth: GitHubAuth,
     http: Client,
+    api_timeout: Duration,
+    asset_timeout: Duration,
+    connect_timeout: Duration,
     rate_limit_remaining: AtomicU64,
     rate_limit_reset: AtomicU64,
+    asset_match: AssetMatchStrategy,
+    asset_regex: Option<Regex>,
+    host_retries: Vec<HostRetries>,
+    max_asset_bytes: Option<u64>,
+    url_rewrite: UrlRewrite,
+    release_cache: Option<ReleaseCacheFile>,
 }
 
 impl GitHubClient {
     pub fn new(token: Option<&str>) -> Result<Self> {
-        let builder = Octocrab::builder();
-        let octocrab = match token {
-            Some(t) => builder.personal_token(t.to_string()).build(),
-            None => builder.build(),
-        }
-        .map_err(|e| Error::GitHub {
-            message: "Failed to initialize GitHub client".to_string(),
-            source: e,
-        })?;
+        Self::with_auth(GitHubAuth::resolve(token, None))
+    }
+
+    /// Creates a client that authenticates API requests with `auth`.
+    pub fn with_auth(auth: GitHubAuth) -> Result<Self> {
+        Self::with_auth_and_timeouts(auth, HttpTimeouts::default())
+    }
 
+    /// Creates a client whose API requests use `api_timeouts`. Asset
+    /// downloads share the connect timeout but keep their own request
+    /// timeout (see [`GitHubClient::with_asset_timeout`]).
+    pub fn with_auth_and_timeouts(auth: GitHubAuth, api_timeouts: HttpTimeouts) -> Result<Self> {
+        let octocrab = auth.build_octocrab(api_timeouts, None)?;
+
+        let asset_timeout = Duration::from_secs(DEFAULT_ASSET_TIMEOUT_SECS);
         let http = build_http_client(
-            DOWNLOAD_TIMEOUT_SECS,
+            HttpTimeouts {
+                request: asset_timeout,
+                connect: api_timeouts.connect,
+            },
             "github download client initialization",
         )?;
 
         Ok(Self {
             octocrab,
+            auth,
             http,
+            api_timeout: api_timeouts.request,
+            asset_timeout,
+            connect_timeout: api_timeouts.connect,
             // u64::MAX signals "not yet fetched" so the first API call triggers a rate limit check
             rate_limit_remaining: AtomicU64::new(u64::MAX),
             rate_limit_reset: AtomicU64::new(0),
+            asset_match: AssetMatchStrategy::default(),
+            asset_regex: None,
+            host_retries: Vec::new(),
+            max_asset_bytes: None,
+            url_rewrite: UrlRewrite::default(),
+            release_cache: None,
         })
     }
 
+    /// Sends API requests to another base URL such as a mock server.
+    pub fn with_api_base(mut self, api_base: &str) -> Result<Self> {
+        let api_timeouts = HttpTimeouts {
+            request: self.api_timeout,
+            connect: self.connect_timeout,
+        };
+        self.octocrab = self.auth.build_octocrab(api_timeouts, Some(api_base))?;
+        Ok(self)
+    }
+
+    /// Revalidates release listings cached in `path` by earlier runs with
+    /// conditional requests, and records new listings in memory until
+    /// [`GitHubApi::save_release_cache`]. An unreadable cache is ignored and
+    /// replaced.
+    pub fn with_release_cache(mut self, path: &Path) -> Self {
+        let cache = ReleaseCache::load(path).unwrap_or_else(|e| {
+            warn!(path = %path.display(), error = %e, "Ignoring unreadable release cache");
+            ReleaseCache::default()
+        });
+        self.release_cache = Some(ReleaseCacheFile {
+            path: path.to_path_buf(),
+            cache: Mutex::new(cache),
+        });
+        self
+    }
+
+    /// Rebuilds the asset download client with its own timeout, leaving the
+    /// API client's timeout unchanged.
+    pub fn with_asset_timeout(mut self, secs: u64) -> Result<Self> {
+        self.asset_timeout = Duration::from_secs(secs);
+        self.http = build_http_client(
+            HttpTimeouts {
+                request: self.asset_timeout,
+                connect: self.connect_timeout,
+            },
+            "github download client initialization",
+        )?;
+        Ok(self)
+    }
+
+    pub fn api_timeout(&self) -> Duration {
+        self.api_timeout
+    }
+
+    pub fn asset_timeout(&self) -> Duration {
+        self.asset_timeout
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Sets how the asset to download is picked from each release.
+    pub fn with_asset_match(mut self, strategy: AssetMatchStrategy) -> Self {
+        self.asset_match = strategy;
+        self
+    }
+
+    /// Picks the release asset whose name matches `regex` instead of using
+    /// the asset name and match strategy.
+    pub fn with_asset_regex(mut self, regex: Option<Regex>) -> Self {
+        self.asset_regex = regex;
+        self
+    }
+
+    /// Overrides the download retry budget for matching hosts.
+    pub fn with_host_retries(mut self, host_retries: Vec<HostRetries>) -> Self {
+        self.host_retries = host_retries;
+        self
+    }
+
+    /// Rejects release assets whose body grows past `limit` bytes.
+    pub fn with_max_asset_bytes(mut self, limit: Option<u64>) -> Self {
+        self.max_asset_bytes = limit;
+        self
+    }
+
+    /// Downloads assets and files from rewritten URLs, e.g. through a mirror.
+    pub fn with_url_rewrite(mut self, url_rewrite: UrlRewrite) -> Self {
+        self.url_rewrite = url_rewrite;
+        self
+    }
+
     async fn wait_for_rate_limit(&self) {
         let remaining = self.rate_limit_remaining.load(Ordering::Relaxed);
         let reset = self.rate_limit_reset.load(Ordering::Relaxed);
@@ -111,6 +381,28 @@ impl GitHubClient {
         }
     }
 
+    /// Returns the core rate limit as of the last API response, or `None`
+    /// before the first request.
+    /// Writes the release cache file, if there is one. Failing to write it
+    /// only costs API calls on the next run.
+    pub fn save_release_cache(&self) {
+        let Some(file) = &self.release_cache else {
+            return;
+        };
+        let cache = file.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = cache.save(&file.path) {
+            warn!(path = %file.path.display(), error = %e, "Failed to write release cache");
+        }
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimitStatus> {
+        let remaining = self.rate_limit_remaining.load(Ordering::Relaxed);
+        (remaining != u64::MAX).then(|| RateLimitStatus {
+            remaining,
+            reset: self.rate_limit_reset.load(Ordering::Relaxed),
+        })
+    }
+
     fn update_rate_limit(&self, remaining: Option<u64>, reset: Option<u64>) {
         if let Some(r) = remaining {
             self.rate_limit_remaining.store(r, Ordering::Relaxed);
@@ -120,54 +412,145 @@ impl GitHubClient {
         }
     }
 
-    #[instrument(skip(self), fields(%repo, %asset_name))]
+    #[instrument(skip(self), fields(%repo, %asset_name, asset_match = ?self.asset_match))]
     pub async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
-        let mut result = Vec::new();
-        let mut page = 1u32;
         let repo_str = repo.to_string();
+        let mut pages = Vec::new();
 
         loop {
             self.check_and_update_rate_limit().await?;
             self.wait_for_rate_limit().await;
 
-            debug!(page, "Fetching releases page");
+            debug!(page = pages.len() + 1, "Fetching releases page");
 
-            let releases = self
-                .octocrab
-                .repos(&repo.owner, &repo.repo)
-                .releases()
-                .list()
-                .per_page(100)
-                .page(page)
-                .send()
-                .await
-                .map_err(|e| Error::GitHub {
-                    message: format!("Failed to fetch releases for '{}'", repo_str),
-                    source: e,
-                })?;
-
-            if releases.items.is_empty() {
+            let page = self
+                .fetch_releases_page(repo, &repo_str, pages.len())
+                .await?;
+            let len = page.releases.len();
+            pages.push(page);
+            if len < RELEASES_PER_PAGE {
                 break;
             }
+        }
+
+        let result: Vec<Release> = pages
+            .iter()
+            .flat_map(|page| &page.releases)
+            .map(|release| {
+                let asset_url = select_asset(
+                    &release.assets,
+                    asset_name,
+                    self.asset_match,
+                    self.asset_regex.as_ref(),
+                )
+                .map(|a| a.browser_download_url.clone());
+                Release::new(release.tag_name.clone(), asset_url)
+            })
+            .collect();
+        self.store_release_pages(&repo_str, pages);
 
-            for release in &releases.items {
-                let asset_url = release
-                    .assets
-                    .iter()
-                    .find(|a| a.name == asset_name)
-                    .map(|a| a.browser_download_url.to_string());
+        debug!(count = result.len(), "Found releases");
+        Ok(result)
+    }
 
-                result.push(Release::new(release.tag_name.clone(), asset_url));
-            }
+    /// Lists page `index` (starting at 0) of the repository's releases. A
+    /// cached page is revalidated and reused when GitHub answers 304.
+    async fn fetch_releases_page(
+        &self,
+        repo: &Repository,
+        repo_str: &str,
+        index: usize,
+    ) -> Result<CachedPage> {
+        let github_error = |e| Error::GitHub {
+            message: format!("Failed to fetch releases for '{}'", repo_str),
+            source: e,
+        };
+        let cached = self.release_cache.as_ref().and_then(|file| {
+            let cache = file.cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache.page(repo_str, index).cloned()
+        });
 
-            if releases.items.len() < 100 {
-                break;
+        let mut headers = HeaderMap::new();
+        if let Some(cached) = &cached {
+            let validator = match (&cached.etag, &cached.last_modified) {
+                (Some(etag), _) => Some((IF_NONE_MATCH, etag)),
+                (None, Some(last_modified)) => Some((IF_MODIFIED_SINCE, last_modified)),
+                (None, None) => None,
+            };
+            if let Some((name, value)) = validator
+                && let Ok(value) = HeaderValue::from_str(value)
+            {
+                headers.insert(name, value);
             }
-            page += 1;
         }
 
-        debug!(count = result.len(), "Found releases");
-        Ok(result)
+        let route = format!(
+            "/repos/{}/{}/releases?per_page={RELEASES_PER_PAGE}&page={}",
+            repo.owner,
+            repo.repo,
+            index + 1
+        );
+        let response = self
+            .octocrab
+            ._get_with_headers(route, Some(headers))
+            .await
+            .map_err(github_error)?;
+        let rate_header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+        };
+        self.update_rate_limit(
+            rate_header("x-ratelimit-remaining"),
+            rate_header("x-ratelimit-reset"),
+        );
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(cached) = cached
+        {
+            debug!(page = index + 1, "Release page not modified; using cache");
+            return Ok(cached);
+        }
+
+        let response = octocrab::map_github_error(response)
+            .await
+            .map_err(github_error)?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = self
+            .octocrab
+            .body_to_string(response)
+            .await
+            .map_err(github_error)?;
+        let releases: Vec<ListedRelease> =
+            serde_json::from_str(&body).map_err(|e| Error::JsonParse {
+                source: format!("releases of '{}'", repo_str),
+                error: e,
+            })?;
+
+        Ok(CachedPage {
+            etag,
+            last_modified,
+            releases,
+        })
+    }
+
+    /// Records a complete release listing in the cache, if there is one.
+    fn store_release_pages(&self, repo_str: &str, pages: Vec<CachedPage>) {
+        let Some(file) = &self.release_cache else {
+            return;
+        };
+        let mut cache = file.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.insert(repo_str, pages);
     }
 
     async fn check_and_update_rate_limit(&self) -> Result<()> {
@@ -198,42 +581,10 @@ impl GitHubClient {
         Ok(())
     }
 
-    async fn download_with_retry<T, F, Fut>(&self, url: &str, max_retries: u32, f: F) -> Result<T>
-    where
-        F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T>>,
-    {
-        let total_attempts = max_retries + 1;
-        let mut last_error: Option<Error> = None;
-
-        for attempt in 0..total_attempts {
-            if attempt > 0 {
-                let delay = retry_backoff_delay(attempt);
-                warn!(attempt, max_retries, ?delay, "Retrying download");
-                tokio::time::sleep(delay).await;
-            }
-
-            match f().await {
-                Ok(output) => return Ok(output),
-                Err(e) => {
-                    debug!(url, attempt, error = %e, "Download attempt failed");
-                    if !should_retry_download_error(&e) || attempt + 1 >= total_attempts {
-                        return Err(e);
-                    }
-                    last_error = Some(e);
-                }
-            }
-        }
-
-        Err(last_error.unwrap_or_else(|| {
-            Error::ConfigValidation("Retry loop finished without attempts".to_string())
-        }))
-    }
-
     async fn fetch_raw(&self, url: &str) -> Result<String> {
-        let response = self
+        let mut response = self
             .http
-            .get(url)
+            .get(self.url_rewrite.apply(url).as_ref())
             .send()
             .await
             .map_err(|e| Error::Http {
@@ -246,25 +597,65 @@ impl GitHubClient {
                 source: e,
             })?;
 
-        let content = response.text().await.map_err(|e| Error::Http {
+        let Some(limit) = self.max_asset_bytes else {
+            return response.text().await.map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            });
+        };
+
+        // Count bytes as they arrive so an oversized body is never held in full.
+        let too_large = || Error::AssetTooLarge {
+            url: url.to_string(),
+            limit,
+        };
+        if response.content_length().is_some_and(|len| len > limit) {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| Error::Http {
             url: url.to_string(),
             source: e,
-        })?;
+        })? {
+            if (body.len() + chunk.len()) as u64 > limit {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
 
-        Ok(content)
+        Ok(String::from_utf8_lossy(&body).into_owned())
     }
 
-    #[instrument(skip(self, releases), fields(release_count = releases.len(), max_concurrent, max_retries))]
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let http_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        let response = self
+            .http
+            .get(self.url_rewrite.apply(url).as_ref())
+            .send()
+            .await
+            .map_err(http_error)?
+            .error_for_status()
+            .map_err(http_error)?;
+
+        let bytes = response.bytes().await.map_err(http_error)?;
+        Ok(bytes.to_vec())
+    }
+
+    #[instrument(skip(self, releases, retry_observer), fields(release_count = releases.len(), max_concurrent, max_retries))]
     async fn download_assets_impl(
         &self,
         releases: Vec<Release>,
         max_concurrent: usize,
         max_retries: u32,
+        retry_observer: Option<&dyn RetryObserver>,
     ) -> Vec<(Release, Result<String>)> {
         stream::iter(releases.into_iter())
             .map(|release| async move {
                 let result = match release.asset_url() {
-                    Some(url) => self.download_asset(url, max_retries).await,
+                    Some(url) => self.download_asset(url, max_retries, retry_observer).await,
                     None => Err(Error::PackageJsonNotFound {
                         tag: release.tag().to_string(),
                     }),
@@ -276,10 +667,15 @@ impl GitHubClient {
             .await
     }
 
-    #[instrument(skip(self), fields(%url))]
-    async fn download_asset(&self, url: &str, max_retries: u32) -> Result<String> {
-        self.download_with_retry(url, max_retries, || self.fetch_raw(url))
-            .await
+    #[instrument(skip(self, retry_observer), fields(%url))]
+    async fn download_asset(
+        &self,
+        url: &str,
+        max_retries: u32,
+        retry_observer: Option<&dyn RetryObserver>,
+    ) -> Result<String> {
+        let max_retries = retries_for_url(&self.host_retries, url, max_retries);
+        retry_download(url, max_retries, retry_observer, || self.fetch_raw(url)).await
     }
 
     #[instrument(skip(self), fields(%repo))]
@@ -312,17 +708,405 @@ impl GitHubApi for GitHubClient {
         GitHubClient::get_releases(self, repo, asset_name).await
     }
 
-    async fn download_assets(
+    async fn download_assets<'a>(
         &self,
         releases: Vec<Release>,
         max_concurrent: usize,
         max_retries: u32,
+        retry_observer: Option<&'a dyn RetryObserver>,
     ) -> Vec<(Release, Result<String>)> {
-        self.download_assets_impl(releases, max_concurrent, max_retries)
+        self.download_assets_impl(releases, max_concurrent, max_retries, retry_observer)
             .await
     }
 
     async fn verify_repository(&self, repo: &Repository) -> Result<()> {
         GitHubClient::verify_repository(self, repo).await
     }
+
+    #[instrument(skip(self), fields(%url))]
+    async fn download_file(&self, url: &str, max_retries: u32) -> Result<Vec<u8>> {
+        let max_retries = retries_for_url(&self.host_retries, url, max_retries);
+        retry_download(url, max_retries, None, || self.fetch_bytes(url)).await
+    }
+
+    fn rate_limit(&self) -> Option<RateLimitStatus> {
+        GitHubClient::rate_limit(self)
+    }
+
+    fn save_release_cache(&self) {
+        GitHubClient::save_release_cache(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str, size: i64) -> ListedAsset {
+        serde_json::from_value(serde_json::json!({
+            "url": format!("https://api.github.com/repos/o/r/releases/assets/{name}"),
+            "browser_download_url": format!("https://github.com/o/r/releases/download/v1.0.0/{name}"),
+            "id": 1,
+            "node_id": "RA_1",
+            "name": name,
+            "label": null,
+            "state": "uploaded",
+            "content_type": "application/octet-stream",
+            "size": size,
+            "digest": null,
+            "download_count": 0,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "uploader": null
+        }))
+        .unwrap()
+    }
+
+    fn release_assets() -> Vec<ListedAsset> {
+        vec![
+            asset("notes.txt", 10),
+            asset("package.json", 200),
+            asset("package.zip", 5000),
+        ]
+    }
+
+    fn selected(strategy: AssetMatchStrategy) -> Option<String> {
+        let assets = release_assets();
+        select_asset(&assets, "package.json", strategy, None).map(|a| a.name.clone())
+    }
+
+    #[test]
+    fn exact_strategy_matches_asset_name() {
+        assert_eq!(
+            selected(AssetMatchStrategy::Exact).as_deref(),
+            Some("package.json")
+        );
+        assert!(
+            select_asset(
+                &release_assets(),
+                "missing.json",
+                AssetMatchStrategy::Exact,
+                None
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn first_strategy_picks_first_asset() {
+        assert_eq!(
+            selected(AssetMatchStrategy::First).as_deref(),
+            Some("notes.txt")
+        );
+    }
+
+    #[test]
+    fn largest_strategy_picks_largest_asset() {
+        assert_eq!(
+            selected(AssetMatchStrategy::Largest).as_deref(),
+            Some("package.zip")
+        );
+
+        let tied = vec![asset("a.json", 100), asset("b.json", 100)];
+        assert_eq!(
+            select_asset(&tied, "package.json", AssetMatchStrategy::Largest, None)
+                .map(|a| a.name.as_str()),
+            Some("a.json")
+        );
+    }
+
+    #[test]
+    fn strategies_return_none_without_assets() {
+        for strategy in [
+            AssetMatchStrategy::Exact,
+            AssetMatchStrategy::First,
+            AssetMatchStrategy::Largest,
+        ] {
+            assert!(select_asset(&[], "package.json", strategy, None).is_none());
+        }
+    }
+
+    #[test]
+    fn regex_picks_first_matching_asset_over_strategy() {
+        let assets = vec![
+            asset("notes.txt", 10),
+            asset("package-1.2.3.json", 200),
+            asset("package-1.2.3-extra.json", 300),
+        ];
+        let regex = Regex::new(r"^package-[0-9.]+(-\w+)?\.json$").unwrap();
+        assert_eq!(
+            select_asset(
+                &assets,
+                "package.json",
+                AssetMatchStrategy::Largest,
+                Some(&regex)
+            )
+            .map(|a| a.name.as_str()),
+            Some("package-1.2.3.json")
+        );
+
+        let none = Regex::new(r"\.zip$").unwrap();
+        assert!(
+            select_asset(&assets, "notes.txt", AssetMatchStrategy::Exact, Some(&none)).is_none()
+        );
+    }
+
+    fn can_bind_localhost() -> bool {
+        std::net::TcpListener::bind("127.0.0.1:0").is_ok()
+    }
+
+    fn github_client() -> GitHubClient {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        GitHubClient::new(None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn asset_timeout_is_independent_of_api_timeout() {
+        let default = github_client();
+        assert_eq!(
+            default.asset_timeout(),
+            Duration::from_secs(DEFAULT_ASSET_TIMEOUT_SECS)
+        );
+
+        let client = github_client().with_asset_timeout(120).unwrap();
+        assert_eq!(client.asset_timeout(), Duration::from_secs(120));
+        assert_eq!(client.api_timeout(), default.api_timeout());
+    }
+
+    #[tokio::test]
+    async fn api_timeouts_leave_asset_timeout_unchanged() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let client = GitHubClient::with_auth_and_timeouts(
+            GitHubAuth::Anonymous,
+            HttpTimeouts::from_secs(120, 5),
+        )
+        .unwrap()
+        .with_asset_timeout(60)
+        .unwrap();
+
+        assert_eq!(client.api_timeout(), Duration::from_secs(120));
+        assert_eq!(client.connect_timeout(), Duration::from_secs(5));
+        assert_eq!(client.asset_timeout(), Duration::from_secs(60));
+        assert_eq!(
+            github_client().api_timeout(),
+            HttpTimeouts::default().request
+        );
+    }
+
+    #[tokio::test]
+    async fn asset_download_honors_asset_timeout() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        if !can_bind_localhost() {
+            return;
+        }
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/package.json"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(3)))
+            .mount(&mock_server)
+            .await;
+
+        let client = github_client().with_asset_timeout(1).unwrap();
+        let url = format!("{}/package.json", mock_server.uri());
+
+        let err = client.download_asset(&url, 0, None).await.unwrap_err();
+        assert!(matches!(err, Error::Http { source, .. } if source.is_timeout()));
+    }
+
+    #[tokio::test]
+    async fn asset_download_rejects_body_over_max_asset_bytes() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        if !can_bind_localhost() {
+            return;
+        }
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/package.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(2048)))
+            .mount(&mock_server)
+            .await;
+        let url = format!("{}/package.json", mock_server.uri());
+
+        let capped = github_client().with_max_asset_bytes(Some(1024));
+        let err = capped.download_asset(&url, 0, None).await.unwrap_err();
+        assert!(matches!(err, Error::AssetTooLarge { limit: 1024, .. }));
+
+        let roomy = github_client().with_max_asset_bytes(Some(2048));
+        let body = roomy.download_asset(&url, 0, None).await.unwrap();
+        assert_eq!(body.len(), 2048);
+    }
+
+    #[tokio::test]
+    async fn asset_download_uses_rewritten_url() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        if !can_bind_localhost() {
+            return;
+        }
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/mirror/o/r/package.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&mock_server)
+            .await;
+
+        let client = github_client().with_url_rewrite(UrlRewrite::new([(
+            "https://github.com/".to_string(),
+            format!("{}/mirror/", mock_server.uri()),
+        )]));
+
+        let body = client
+            .download_asset("https://github.com/o/r/package.json", 0, None)
+            .await
+            .unwrap();
+        assert_eq!(body, "{}");
+    }
+
+    #[tokio::test]
+    async fn host_retries_override_global_retry_budget() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        if !can_bind_localhost() {
+            return;
+        }
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/package.json"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = github_client().with_host_retries(vec![HostRetries {
+            host: "127.0.0.1".to_string(),
+            max_retries: 1,
+        }]);
+        let url = format!("{}/package.json", mock_server.uri());
+
+        assert!(client.download_asset(&url, 8, None).await.is_err());
+    }
+
+    #[test]
+    fn app_credentials_take_precedence_over_token() {
+        let app = GitHubAppAuth {
+            app_id: 1,
+            installation_id: 2,
+            private_key_path: PathBuf::from("app.pem"),
+        };
+
+        assert_eq!(
+            GitHubAuth::resolve(Some("token"), Some(app.clone())),
+            GitHubAuth::App(app)
+        );
+        assert_eq!(
+            GitHubAuth::resolve(Some("token"), None),
+            GitHubAuth::Token("token".to_string())
+        );
+        assert_eq!(GitHubAuth::resolve(None, None), GitHubAuth::Anonymous);
+    }
+
+    #[tokio::test]
+    async fn app_auth_builds_client_from_private_key() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = tempfile::TempDir::new().unwrap();
+        let key_path = dir.path().join("app.pem");
+        std::fs::write(&key_path, "not a pem key").unwrap();
+
+        let auth = GitHubAuth::App(GitHubAppAuth {
+            app_id: 1,
+            installation_id: 2,
+            private_key_path: key_path,
+        });
+
+        let err = GitHubClient::with_auth(auth).err().unwrap();
+        assert!(err.to_string().contains("Invalid GitHub App private key"));
+    }
+
+    #[tokio::test]
+    async fn release_listing_is_revalidated_from_cache() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        if !can_bind_localhost() {
+            return;
+        }
+        let mock_server = MockServer::start().await;
+        let rate = serde_json::json!({ "limit": 60, "used": 0, "remaining": 60, "reset": 0 });
+        Mock::given(method("GET"))
+            .and(path("/rate_limit"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "resources": { "core": rate, "search": rate },
+                "rate": rate
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/releases"))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/o/r/releases"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .insert_header("x-ratelimit-remaining", "42")
+                    .insert_header("x-ratelimit-reset", "1700000000")
+                    .set_body_json(serde_json::json!([{
+                        "tag_name": "v1.0.0",
+                        "assets": [{
+                            "name": "package.json",
+                            "size": 10,
+                            "browser_download_url": "https://example.com/package.json"
+                        }]
+                    }])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache_path = dir.path().join("voyager.cache");
+        let repo = Repository::parse("o/r").unwrap();
+        let client = || {
+            github_client()
+                .with_api_base(&mock_server.uri())
+                .unwrap()
+                .with_release_cache(&cache_path)
+        };
+
+        let first = client();
+        assert_eq!(first.rate_limit(), None);
+        let listed = first.get_releases(&repo, "package.json").await.unwrap();
+        assert!(!cache_path.exists());
+        first.save_release_cache();
+        assert_eq!(
+            first.rate_limit(),
+            Some(RateLimitStatus {
+                remaining: 42,
+                reset: 1_700_000_000
+            })
+        );
+        let revalidated = client().get_releases(&repo, "package.json").await.unwrap();
+
+        let summary = |releases: &[Release]| {
+            releases
+                .iter()
+                .map(|r| (r.tag().to_string(), r.asset_url().map(str::to_string)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&listed), summary(&revalidated));
+        assert_eq!(
+            revalidated[0].asset_url(),
+            Some("https://example.com/package.json")
+        );
+    }
 }
@@ -0,0 +1,327 @@
+use super::github::GitHubApi;
+use super::http::{HttpTimeouts, build_http_client, same_origin};
+use super::retry::{RetryObserver, retry_download};
+use crate::domain::{GITLAB_HOST, Release, Repository};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+/// Releases requested per page; GitLab caps `per_page` at 100.
+const RELEASES_PER_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    #[serde(default)]
+    assets: GitLabAssets,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GitLabAssets {
+    #[serde(default)]
+    links: Vec<GitLabAssetLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabAssetLink {
+    name: String,
+    url: String,
+    direct_asset_url: Option<String>,
+}
+
+impl GitLabAssetLink {
+    /// Prefers the permanent `direct_asset_url` over the raw link target.
+    fn download_url(&self) -> &str {
+        self.direct_asset_url.as_deref().unwrap_or(&self.url)
+    }
+}
+
+/// Reads releases from the GitLab REST API.
+///
+/// Release assets are GitLab release links; the link whose name equals the
+/// asset name is downloaded.
+pub struct GitLabClient {
+    http: Client,
+    api_base: String,
+    token: Option<String>,
+}
+
+impl GitLabClient {
+    pub fn new(token: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            http: build_http_client(HttpTimeouts::default(), "gitlab client initialization")?,
+            api_base: format!("https://{GITLAB_HOST}/api/v4"),
+            token: token.map(str::to_string),
+        })
+    }
+
+    /// Rebuilds the client with other timeouts for API requests and asset
+    /// downloads.
+    pub fn with_timeouts(mut self, timeouts: HttpTimeouts) -> Result<Self> {
+        self.http = build_http_client(timeouts, "gitlab client initialization")?;
+        Ok(self)
+    }
+
+    /// Points API requests at another base URL such as a mock server.
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn project_url(&self, repo: &Repository) -> String {
+        // Owner and repo are restricted to URL-safe characters, so only the
+        // separator needs encoding.
+        format!("{}/projects/{}%2F{}", self.api_base, repo.owner, repo.repo)
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+
+    async fn get_api(&self, repo: &Repository, url: &str) -> Result<reqwest::Response> {
+        let http_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        let response = self
+            .authorized(self.http.get(url))
+            .send()
+            .await
+            .map_err(http_error)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::RepositoryNotFound(repo.to_string()));
+        }
+        response.error_for_status().map_err(http_error)
+    }
+
+    #[instrument(skip(self), fields(%repo, %asset_name))]
+    pub async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+        let mut result = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            debug!(page, "Fetching releases page");
+
+            let url = format!(
+                "{}/releases?per_page={RELEASES_PER_PAGE}&page={page}",
+                self.project_url(repo)
+            );
+            let body = self
+                .get_api(repo, &url)
+                .await?
+                .text()
+                .await
+                .map_err(|e| Error::Http {
+                    url: url.clone(),
+                    source: e,
+                })?;
+            let releases: Vec<GitLabRelease> =
+                serde_json::from_str(&body).map_err(|e| Error::JsonParse {
+                    source: url.clone(),
+                    error: e,
+                })?;
+
+            for release in &releases {
+                let asset_url = release
+                    .assets
+                    .links
+                    .iter()
+                    .find(|link| link.name == asset_name)
+                    .map(|link| link.download_url().to_string());
+
+                result.push(Release::new(release.tag_name.clone(), asset_url));
+            }
+
+            if releases.len() < RELEASES_PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+
+        debug!(count = result.len(), "Found releases");
+        Ok(result)
+    }
+
+    #[instrument(skip(self), fields(%repo))]
+    pub async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        self.get_api(repo, &self.project_url(repo)).await?;
+
+        debug!("Repository verified");
+        Ok(())
+    }
+
+    async fn fetch_response(&self, url: &str) -> Result<reqwest::Response> {
+        let http_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        self.http
+            .get(url)
+            .send()
+            .await
+            .map_err(http_error)?
+            .error_for_status()
+            .map_err(http_error)
+    }
+
+    async fn fetch_text(&self, url: &str) -> Result<String> {
+        self.fetch_response(url)
+            .await?
+            .text()
+            .await
+            .map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            })
+    }
+
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let bytes = self
+            .fetch_response(url)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            })?;
+        Ok(bytes.to_vec())
+    }
+}
+
+#[async_trait]
+impl GitHubApi for GitLabClient {
+    async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+        GitLabClient::get_releases(self, repo, asset_name).await
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        max_concurrent: usize,
+        max_retries: u32,
+        retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        stream::iter(releases)
+            .map(|release| async move {
+                let result = match release.asset_url() {
+                    Some(url) => {
+                        retry_download(url, max_retries, retry_observer, || self.fetch_text(url))
+                            .await
+                    }
+                    None => Err(Error::PackageJsonNotFound {
+                        tag: release.tag().to_string(),
+                    }),
+                };
+                (release, result)
+            })
+            .buffer_unordered(max_concurrent)
+            .collect()
+            .await
+    }
+
+    async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        GitLabClient::verify_repository(self, repo).await
+    }
+
+    async fn download_file(&self, url: &str, max_retries: u32) -> Result<Vec<u8>> {
+        retry_download(url, max_retries, None, || self.fetch_bytes(url)).await
+    }
+
+    fn serves_url(&self, url: &str) -> bool {
+        same_origin(url, &self.api_base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn can_bind_localhost() -> bool {
+        std::net::TcpListener::bind("127.0.0.1:0").is_ok()
+    }
+
+    fn gitlab_client(server: &MockServer) -> GitLabClient {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        GitLabClient::new(Some("secret"))
+            .unwrap()
+            .with_api_base(&server.uri())
+    }
+
+    #[tokio::test]
+    async fn lists_releases_and_downloads_package_json_link() {
+        if !can_bind_localhost() {
+            return;
+        }
+        let server = MockServer::start().await;
+        let asset_url = format!("{}/uploads/package.json", server.uri());
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Frepo/releases"))
+            .and(query_param("page", "1"))
+            .and(header("PRIVATE-TOKEN", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "tag_name": "v1.1.0",
+                    "assets": { "links": [
+                        { "name": "notes.txt", "url": "https://example.com/notes.txt" },
+                        {
+                            "name": "package.json",
+                            "url": "https://example.com/raw",
+                            "direct_asset_url": asset_url
+                        }
+                    ] }
+                },
+                { "tag_name": "v1.0.0", "assets": { "links": [] } }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/uploads/package.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"version\":\"1.1.0\"}"))
+            .mount(&server)
+            .await;
+
+        let client = gitlab_client(&server);
+        let repo = Repository::parse("gitlab:group/repo").unwrap();
+        let releases = client.get_releases(&repo, "package.json").await.unwrap();
+
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].version(), "1.1.0");
+        assert_eq!(releases[0].asset_url(), Some(asset_url.as_str()));
+        assert_eq!(releases[1].asset_url(), None);
+
+        let downloaded = GitHubApi::download_assets(&client, releases, 2, 0, None).await;
+        let body = downloaded
+            .iter()
+            .find(|(release, _)| release.version() == "1.1.0")
+            .map(|(_, result)| result.as_ref().unwrap().clone());
+        assert_eq!(body.as_deref(), Some("{\"version\":\"1.1.0\"}"));
+    }
+
+    #[tokio::test]
+    async fn missing_project_is_reported_as_not_found() {
+        if !can_bind_localhost() {
+            return;
+        }
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Fmissing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = gitlab_client(&server);
+        let repo = Repository::parse("gitlab:group/missing").unwrap();
+        let err = client.verify_repository(&repo).await.unwrap_err();
+
+        assert!(matches!(err, Error::RepositoryNotFound(name) if name == "gitlab:group/missing"));
+    }
+}
@@ -2,24 +2,58 @@ use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
 use indicatif::ProgressBar;
 use reqwest::{Client, StatusCode};
+use serde::Serialize;
 use std::time::Duration;
 use tracing::{debug, instrument};
 
 #[cfg(test)]
 use mockall::automock;
 
-use super::retry::retry_backoff_delay;
+use super::retry::{RetryObserver, retry_backoff_delay};
+use super::url_rewrite::UrlRewrite;
 use crate::error::{Error, Result};
 
-const DEFAULT_TIMEOUT_SECS: u64 = 30;
-const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Default timeout for a whole HTTP request, including reading the body.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Default timeout for establishing a connection.
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
 
-pub(crate) fn build_http_client(timeout_secs: u64, context: &str) -> Result<Client> {
+/// Request and connect timeouts of an HTTP client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    pub request: Duration,
+    pub connect: Duration,
+}
+
+impl HttpTimeouts {
+    pub fn from_secs(request_secs: u64, connect_secs: u64) -> Self {
+        Self {
+            request: Duration::from_secs(request_secs),
+            connect: Duration::from_secs(connect_secs),
+        }
+    }
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self::from_secs(DEFAULT_TIMEOUT_SECS, DEFAULT_CONNECT_TIMEOUT_SECS)
+    }
+}
+
+/// Whether `url` has the same scheme, host and port as `base`.
+pub(crate) fn same_origin(url: &str, base: &str) -> bool {
+    match (reqwest::Url::parse(url), reqwest::Url::parse(base)) {
+        (Ok(url), Ok(base)) => url.origin() == base.origin(),
+        _ => false,
+    }
+}
+
+pub(crate) fn build_http_client(timeouts: HttpTimeouts, context: &str) -> Result<Client> {
     Client::builder()
         .user_agent("voyager")
         .redirect(reqwest::redirect::Policy::limited(10))
-        .timeout(Duration::from_secs(timeout_secs))
-        .connect_timeout(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS))
+        .timeout(timeouts.request)
+        .connect_timeout(timeouts.connect)
         .build()
         .map_err(|e| Error::Http {
             url: context.to_string(),
@@ -27,6 +61,56 @@ pub(crate) fn build_http_client(timeout_secs: u64, context: &str) -> Result<Clie
         })
 }
 
+/// HTTP method used to check that a URL is reachable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UrlCheckMethod {
+    /// HEAD, falling back to a range-limited GET when HEAD is blocked.
+    #[default]
+    Auto,
+    /// HEAD only, never falling back.
+    Head,
+    /// Range-limited GET only.
+    Get,
+}
+
+/// Result of checking whether a URL is reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UrlCheckOutcome {
+    Ok,
+    /// The host answered with a client error such as 404
+    NotFound,
+    /// The request failed or the host kept answering with server errors
+    Unreachable,
+    /// The host kept answering 429 until retries ran out
+    RateLimited,
+    /// Not an https:// URL under `validate --https-only`; never requested
+    Insecure,
+}
+
+impl UrlCheckOutcome {
+    pub fn is_ok(self) -> bool {
+        self == Self::Ok
+    }
+
+    /// Whether checking again may give a different answer.
+    fn is_retryable(self) -> bool {
+        matches!(self, Self::Unreachable | Self::RateLimited)
+    }
+}
+
+impl std::fmt::Display for UrlCheckOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ok => "ok",
+            Self::NotFound => "not found",
+            Self::Unreachable => "unreachable",
+            Self::RateLimited => "rate limited",
+            Self::Insecure => "not https",
+        })
+    }
+}
+
 /// Trait for HTTP operations, enabling dependency injection and testing.
 #[cfg_attr(test, automock)]
 #[async_trait]
@@ -34,25 +118,59 @@ pub trait HttpApi: Send + Sync {
     /// Check if a URL exists using HEAD request with retry logic.
     async fn check_url_exists(&self, url: &str, max_retries: u32) -> bool;
 
-    /// Validate multiple URLs concurrently, returning invalid ones.
+    /// Fetches the body of a URL as text.
+    async fn fetch_text(&self, url: &str) -> Result<String>;
+
+    /// Requests the first byte of a URL so caches in front of it fetch the file.
+    async fn warm_url(&self, url: &str) -> Result<()>;
+
+    /// Validate multiple URLs concurrently, returning invalid ones with the
+    /// reason they failed.
     /// Note: This version does not support progress tracking.
-    async fn validate_urls(
+    ///
+    /// `retry_observer` is notified before each retry backoff wait.
+    async fn validate_urls<'a>(
         &self,
         urls: Vec<(String, String, String)>,
         max_concurrent: usize,
         max_retries: u32,
-    ) -> Vec<(String, String, String)>;
+        retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(String, String, String, UrlCheckOutcome)>;
 }
 
 pub struct HttpClient {
     client: Client,
+    method: UrlCheckMethod,
+    url_rewrite: UrlRewrite,
 }
 
 impl HttpClient {
     pub fn new() -> Result<Self> {
-        let client = build_http_client(DEFAULT_TIMEOUT_SECS, "client initialization")?;
+        let client = build_http_client(HttpTimeouts::default(), "client initialization")?;
+
+        Ok(Self {
+            client,
+            method: UrlCheckMethod::default(),
+            url_rewrite: UrlRewrite::default(),
+        })
+    }
+
+    /// Rebuilds the client with other request and connect timeouts.
+    pub fn with_timeouts(mut self, timeouts: HttpTimeouts) -> Result<Self> {
+        self.client = build_http_client(timeouts, "client initialization")?;
+        Ok(self)
+    }
 
-        Ok(Self { client })
+    /// Sets the HTTP method used for URL checks.
+    pub fn with_method(mut self, method: UrlCheckMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Checks URLs at their rewritten location, e.g. through a mirror.
+    pub fn with_url_rewrite(mut self, url_rewrite: UrlRewrite) -> Self {
+        self.url_rewrite = url_rewrite;
+        self
     }
 
     pub fn client(&self) -> &Client {
@@ -66,7 +184,7 @@ impl HttpClient {
         )
     }
 
-    async fn check_url_exists_with_get(&self, url: &str) -> Option<bool> {
+    async fn check_url_with_get(&self, url: &str) -> UrlCheckOutcome {
         match self
             .client
             .get(url)
@@ -77,80 +195,137 @@ impl HttpClient {
             Ok(response) => {
                 let status = response.status();
                 if status.is_success() {
-                    Some(true)
+                    UrlCheckOutcome::Ok
                 } else if status == StatusCode::TOO_MANY_REQUESTS {
                     debug!(url = %url, status = %status, "GET fallback hit rate limit; retrying");
-                    None
+                    UrlCheckOutcome::RateLimited
                 } else if status.is_client_error() {
-                    Some(false)
+                    UrlCheckOutcome::NotFound
                 } else {
                     debug!(url = %url, status = %status, "GET fallback returned retryable status");
-                    None
+                    UrlCheckOutcome::Unreachable
                 }
             }
             Err(e) => {
                 debug!(url = %url, error = %e, "GET fallback URL check failed with error");
-                None
+                UrlCheckOutcome::Unreachable
             }
         }
     }
 
+    pub async fn fetch_text(&self, url: &str) -> Result<String> {
+        let to_error = |e| Error::Http {
+            url: url.to_string(),
+            source: e,
+        };
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(to_error)?
+            .text()
+            .await
+            .map_err(to_error)
+    }
+
+    pub async fn warm_url(&self, url: &str) -> Result<()> {
+        self.client
+            .get(url)
+            .header(reqwest::header::RANGE, "bytes=0-0")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| Error::Http {
+                url: url.to_string(),
+                source: e,
+            })
+    }
+
     pub async fn check_url_exists(&self, url: &str, max_retries: u32) -> bool {
-        // Use HEAD to avoid incrementing GitHub release download counts.
-        // Retries handle transient failures. Some hosts block HEAD, so we
-        // selectively fallback to a range-limited GET check.
+        self.check_url(url, max_retries, None).await.is_ok()
+    }
+
+    async fn check_url(
+        &self,
+        url: &str,
+        max_retries: u32,
+        retry_observer: Option<&dyn RetryObserver>,
+    ) -> UrlCheckOutcome {
+        // Retries handle transient failures; the last retryable outcome is
+        // reported once they run out.
+        let mut outcome = UrlCheckOutcome::Unreachable;
         for attempt in 0..=max_retries {
             if attempt > 0 {
                 let delay = retry_backoff_delay(attempt);
                 debug!(url = %url, attempt, ?delay, "Retrying URL check");
+                if let Some(observer) = retry_observer {
+                    observer.on_retry(url, attempt);
+                }
                 tokio::time::sleep(delay).await;
             }
 
-            match self.client.head(url).send().await {
-                Ok(response) => {
-                    let status = response.status();
-                    if status.is_success() {
-                        return true;
-                    }
-                    debug!(url = %url, status = %status, "URL check failed with status");
-                    if Self::should_fallback_to_get(status) {
-                        debug!(url = %url, status = %status, "Retrying URL check with GET fallback");
-                        match self.check_url_exists_with_get(url).await {
-                            Some(true) => return true,
-                            Some(false) => return false,
-                            None => continue,
-                        }
-                    }
-                    if status == StatusCode::TOO_MANY_REQUESTS {
-                        debug!(url = %url, status = %status, "URL check hit rate limit; retrying");
-                        continue;
-                    }
-                    // Don't retry on 4xx errors (client errors like 404)
-                    if status.is_client_error() {
-                        return false;
-                    }
+            outcome = self
+                .check_url_once(&self.url_rewrite.apply(url), attempt)
+                .await;
+            if !outcome.is_retryable() {
+                return outcome;
+            }
+        }
+        outcome
+    }
+
+    async fn check_url_once(&self, url: &str, attempt: u32) -> UrlCheckOutcome {
+        if self.method == UrlCheckMethod::Get {
+            return self.check_url_with_get(url).await;
+        }
+
+        // Use HEAD to avoid incrementing GitHub release download counts.
+        // Some hosts block HEAD, so we selectively fallback to a
+        // range-limited GET check.
+        match self.client.head(url).send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    return UrlCheckOutcome::Ok;
+                }
+                debug!(url = %url, status = %status, "URL check failed with status");
+                if self.method == UrlCheckMethod::Auto && Self::should_fallback_to_get(status) {
+                    debug!(url = %url, status = %status, "Retrying URL check with GET fallback");
+                    return self.check_url_with_get(url).await;
                 }
-                Err(e) => {
-                    debug!(url = %url, attempt, error = %e, "URL check failed with error");
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    debug!(url = %url, status = %status, "URL check hit rate limit; retrying");
+                    return UrlCheckOutcome::RateLimited;
                 }
+                // Don't retry on 4xx errors (client errors like 404)
+                if status.is_client_error() {
+                    return UrlCheckOutcome::NotFound;
+                }
+                UrlCheckOutcome::Unreachable
+            }
+            Err(e) => {
+                debug!(url = %url, attempt, error = %e, "URL check failed with error");
+                UrlCheckOutcome::Unreachable
             }
         }
-        false
     }
 
-    #[instrument(skip(self, urls, progress), fields(url_count = urls.len(), max_concurrent, max_retries))]
+    #[instrument(skip(self, urls, progress, retry_observer), fields(url_count = urls.len(), max_concurrent, max_retries))]
     pub async fn validate_urls_with_progress(
         &self,
         urls: Vec<(String, String, String)>,
         max_concurrent: usize,
         max_retries: u32,
         progress: Option<&ProgressBar>,
-    ) -> Vec<(String, String, String)> {
+        retry_observer: Option<&dyn RetryObserver>,
+    ) -> Vec<(String, String, String, UrlCheckOutcome)> {
         let results: Vec<_> = stream::iter(urls)
             .map(|(package_id, version, url)| async move {
-                let exists = self.check_url_exists(&url, max_retries).await;
-                debug!(url = %url, exists, "URL check completed");
-                (package_id, version, url, exists)
+                let outcome = self.check_url(&url, max_retries, retry_observer).await;
+                debug!(url = %url, ?outcome, "URL check completed");
+                (package_id, version, url, outcome)
             })
             .buffer_unordered(max_concurrent)
             .inspect(|_| {
@@ -161,16 +336,13 @@ impl HttpClient {
             .collect()
             .await;
 
-        results
+        // Checks finish in any order; sort so reports are stable across runs.
+        let mut invalid: Vec<_> = results
             .into_iter()
-            .filter_map(|(package_id, version, url, exists)| {
-                if exists {
-                    None
-                } else {
-                    Some((package_id, version, url))
-                }
-            })
-            .collect()
+            .filter(|(_, _, _, outcome)| !outcome.is_ok())
+            .collect();
+        invalid.sort_by(|a, b| (&a.0, &a.1, &a.2).cmp(&(&b.0, &b.1, &b.2)));
+        invalid
     }
 }
 
@@ -180,13 +352,22 @@ impl HttpApi for HttpClient {
         HttpClient::check_url_exists(self, url, max_retries).await
     }
 
-    async fn validate_urls(
+    async fn fetch_text(&self, url: &str) -> Result<String> {
+        HttpClient::fetch_text(self, url).await
+    }
+
+    async fn warm_url(&self, url: &str) -> Result<()> {
+        HttpClient::warm_url(self, url).await
+    }
+
+    async fn validate_urls<'a>(
         &self,
         urls: Vec<(String, String, String)>,
         max_concurrent: usize,
         max_retries: u32,
-    ) -> Vec<(String, String, String)> {
-        self.validate_urls_with_progress(urls, max_concurrent, max_retries, None)
+        retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(String, String, String, UrlCheckOutcome)> {
+        self.validate_urls_with_progress(urls, max_concurrent, max_retries, None, retry_observer)
             .await
     }
 }
@@ -201,6 +382,22 @@ mod tests {
         std::net::TcpListener::bind("127.0.0.1:0").is_ok()
     }
 
+    #[test]
+    fn same_origin_compares_scheme_host_and_port() {
+        let base = "https://gitlab.example.com/api/v4";
+        assert!(same_origin(
+            "https://gitlab.example.com/g/r/-/package.json",
+            base
+        ));
+        assert!(!same_origin(
+            "https://gitlab.example.com:8443/package.json",
+            base
+        ));
+        assert!(!same_origin("http://gitlab.example.com/package.json", base));
+        assert!(!same_origin("https://cdn.example.com/package.json", base));
+        assert!(!same_origin("not a url", base));
+    }
+
     mod check_url_exists {
         use super::*;
 
@@ -307,6 +504,60 @@ mod tests {
             assert!(client.check_url_exists(&url, 0).await);
         }
 
+        #[tokio::test]
+        async fn get_method_skips_head() {
+            if !can_bind_localhost() {
+                return;
+            }
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("HEAD"))
+                .and(path("/head-blocked"))
+                .respond_with(ResponseTemplate::new(405))
+                .expect(0)
+                .mount(&mock_server)
+                .await;
+
+            Mock::given(method("GET"))
+                .and(path("/head-blocked"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            let client = HttpClient::new().unwrap().with_method(UrlCheckMethod::Get);
+            let url = format!("{}/head-blocked", mock_server.uri());
+
+            assert!(client.check_url_exists(&url, 0).await);
+        }
+
+        #[tokio::test]
+        async fn head_method_does_not_fall_back_to_get() {
+            if !can_bind_localhost() {
+                return;
+            }
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("HEAD"))
+                .and(path("/head-blocked"))
+                .respond_with(ResponseTemplate::new(405))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            Mock::given(method("GET"))
+                .and(path("/head-blocked"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(0)
+                .mount(&mock_server)
+                .await;
+
+            let client = HttpClient::new().unwrap().with_method(UrlCheckMethod::Head);
+            let url = format!("{}/head-blocked", mock_server.uri());
+
+            assert!(!client.check_url_exists(&url, 0).await);
+        }
+
         #[tokio::test]
         async fn get_fallback_still_returns_false_for_missing_resource() {
             if !can_bind_localhost() {
@@ -489,10 +740,40 @@ mod tests {
                 ),
             ];
 
-            let invalid = client.validate_urls_with_progress(urls, 4, 0, None).await;
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
 
             assert_eq!(invalid.len(), 1);
             assert_eq!(invalid[0].0, "pkg2");
+            assert_eq!(invalid[0].3, UrlCheckOutcome::NotFound);
+        }
+
+        #[tokio::test]
+        async fn reports_rate_limited_urls() {
+            if !can_bind_localhost() {
+                return;
+            }
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("HEAD"))
+                .respond_with(ResponseTemplate::new(429))
+                .mount(&mock_server)
+                .await;
+
+            let client = HttpClient::new().unwrap();
+            let urls = vec![(
+                "pkg1".to_string(),
+                "1.0.0".to_string(),
+                format!("{}/limited", mock_server.uri()),
+            )];
+
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
+
+            assert_eq!(invalid.len(), 1);
+            assert_eq!(invalid[0].3, UrlCheckOutcome::RateLimited);
         }
 
         #[tokio::test]
@@ -521,7 +802,9 @@ mod tests {
                 ),
             ];
 
-            let invalid = client.validate_urls_with_progress(urls, 4, 0, None).await;
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
 
             assert!(invalid.is_empty());
         }
@@ -552,7 +835,9 @@ mod tests {
                 ),
             ];
 
-            let invalid = client.validate_urls_with_progress(urls, 4, 0, None).await;
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
 
             assert_eq!(invalid.len(), 2);
         }
@@ -560,7 +845,9 @@ mod tests {
         #[tokio::test]
         async fn handles_empty_urls() {
             let client = HttpClient::new().unwrap();
-            let invalid = client.validate_urls_with_progress(vec![], 4, 0, None).await;
+            let invalid = client
+                .validate_urls_with_progress(vec![], 4, 0, None, None)
+                .await;
             assert!(invalid.is_empty());
         }
 
@@ -584,13 +871,64 @@ mod tests {
                 url.clone(),
             )];
 
-            let invalid = client.validate_urls_with_progress(urls, 4, 0, None).await;
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
 
             assert_eq!(invalid.len(), 1);
             assert_eq!(invalid[0].0, "com.example.pkg");
             assert_eq!(invalid[0].1, "2.0.0");
             assert_eq!(invalid[0].2, url);
         }
+
+        #[tokio::test]
+        async fn sorts_invalid_urls_by_package_and_version() {
+            if !can_bind_localhost() {
+                return;
+            }
+            let mock_server = MockServer::start().await;
+            // The first URLs answer last, so completion order is reversed.
+            for (route, delay_ms) in [("/a", 300), ("/b", 150), ("/c", 0)] {
+                Mock::given(method("HEAD"))
+                    .and(path(route))
+                    .respond_with(
+                        ResponseTemplate::new(404).set_delay(Duration::from_millis(delay_ms)),
+                    )
+                    .mount(&mock_server)
+                    .await;
+            }
+
+            let client = HttpClient::new().unwrap();
+            let entry = |id: &str, version: &str, route: &str| {
+                (
+                    id.to_string(),
+                    version.to_string(),
+                    format!("{}{}", mock_server.uri(), route),
+                )
+            };
+            let urls = vec![
+                entry("com.example.b", "1.0.0", "/a"),
+                entry("com.example.a", "2.0.0", "/b"),
+                entry("com.example.a", "1.0.0", "/c"),
+            ];
+
+            let invalid = client
+                .validate_urls_with_progress(urls, 4, 0, None, None)
+                .await;
+
+            let order: Vec<_> = invalid
+                .iter()
+                .map(|(id, version, _, _)| (id.as_str(), version.as_str()))
+                .collect();
+            assert_eq!(
+                order,
+                vec![
+                    ("com.example.a", "1.0.0"),
+                    ("com.example.a", "2.0.0"),
+                    ("com.example.b", "1.0.0"),
+                ]
+            );
+        }
     }
 
     mod http_client_new {
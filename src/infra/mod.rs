@@ -1,12 +1,31 @@
 mod filesystem;
+mod gitea;
 mod github;
+mod gitlab;
 mod http;
+mod output_sink;
+mod provider;
+mod release_cache;
 mod retry;
+mod url_rewrite;
 
-pub use filesystem::{read_json, write_json};
+pub use filesystem::{read_json, write_json, write_text};
 pub(crate) use filesystem::{read_to_string_if_exists, remove_file_if_exists, write_atomic_file};
-pub use github::{GitHubApi, GitHubClient};
-pub use http::{HttpApi, HttpClient};
+pub use gitea::GiteaClient;
+pub use github::{
+    AssetMatchStrategy, DEFAULT_ASSET_TIMEOUT_SECS, GitHubApi, GitHubAppAuth, GitHubAuth,
+    GitHubClient, RateLimitStatus,
+};
+pub use gitlab::GitLabClient;
+pub use http::{
+    DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_TIMEOUT_SECS, HttpApi, HttpClient, HttpTimeouts,
+    UrlCheckMethod, UrlCheckOutcome,
+};
+pub use output_sink::{FileSink, MemorySink, OutputSink};
+pub use provider::ProviderClient;
+pub use release_cache::release_cache_path;
+pub use retry::{HostRetries, RetryObserver};
+pub use url_rewrite::UrlRewrite;
 
 #[cfg(test)]
 pub use github::MockGitHubApi;
@@ -0,0 +1,74 @@
+use super::filesystem::write_text;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Destination for files produced by commands such as `generate`.
+pub trait OutputSink {
+    /// Writes `content` to `path`, replacing any previous content.
+    fn write(&self, path: &Path, content: &str) -> Result<()>;
+}
+
+/// Writes output atomically to the filesystem.
+#[derive(Debug, Default)]
+pub struct FileSink;
+
+impl OutputSink for FileSink {
+    fn write(&self, path: &Path, content: &str) -> Result<()> {
+        write_text(path, content)
+    }
+}
+
+/// Captures output in memory, keyed by path.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    files: Mutex<HashMap<PathBuf, String>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the content last written to `path`, if any.
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.files.lock().unwrap().get(path.as_ref()).cloned()
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn write(&self, path: &Path, content: &str) -> Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn memory_sink_keeps_last_write_per_path() {
+        let sink = MemorySink::new();
+        sink.write(Path::new("index.json"), "first").unwrap();
+        sink.write(Path::new("index.json"), "second").unwrap();
+
+        assert_eq!(sink.get("index.json").as_deref(), Some("second"));
+        assert!(sink.get("other.json").is_none());
+    }
+
+    #[test]
+    fn file_sink_writes_to_disk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("index.json");
+
+        FileSink.write(&path, "{}").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+    }
+}
@@ -0,0 +1,246 @@
+use super::gitea::GiteaClient;
+use super::github::{GitHubApi, GitHubClient, RateLimitStatus};
+use super::gitlab::GitLabClient;
+use super::retry::RetryObserver;
+use crate::domain::{Provider, Release, Repository};
+use crate::error::Result;
+use async_trait::async_trait;
+
+/// Sends each repository to the client of its hosting provider.
+///
+/// Downloads go to the client whose host serves the URL, so GitLab and Gitea
+/// tokens reach private instances; any other URL goes through the GitHub
+/// client.
+pub struct ProviderClient<
+    H: GitHubApi = GitHubClient,
+    L: GitHubApi = GitLabClient,
+    T: GitHubApi = GiteaClient,
+> {
+    github: H,
+    gitlab: L,
+    gitea: T,
+}
+
+impl<H: GitHubApi, L: GitHubApi, T: GitHubApi> ProviderClient<H, L, T> {
+    pub fn new(github: H, gitlab: L, gitea: T) -> Self {
+        Self {
+            github,
+            gitlab,
+            gitea,
+        }
+    }
+
+    fn client_for(&self, repo: &Repository) -> &dyn GitHubApi {
+        match repo.provider {
+            Provider::GitHub => &self.github,
+            Provider::GitLab => &self.gitlab,
+            Provider::Gitea => &self.gitea,
+        }
+    }
+
+    fn client_for_url(&self, url: &str) -> &dyn GitHubApi {
+        if self.gitlab.serves_url(url) {
+            &self.gitlab
+        } else if self.gitea.serves_url(url) {
+            &self.gitea
+        } else {
+            &self.github
+        }
+    }
+}
+
+#[async_trait]
+impl<H: GitHubApi, L: GitHubApi, T: GitHubApi> GitHubApi for ProviderClient<H, L, T> {
+    async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+        self.client_for(repo).get_releases(repo, asset_name).await
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        max_concurrent: usize,
+        max_retries: u32,
+        retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        let mut github = Vec::new();
+        let mut gitlab = Vec::new();
+        let mut gitea = Vec::new();
+        for release in releases {
+            match release.asset_url() {
+                Some(url) if self.gitlab.serves_url(url) => gitlab.push(release),
+                Some(url) if self.gitea.serves_url(url) => gitea.push(release),
+                _ => github.push(release),
+            }
+        }
+
+        // One batch at a time keeps the overall limit at `max_concurrent`.
+        let mut results = Vec::new();
+        for (client, batch) in [
+            (&self.github as &dyn GitHubApi, github),
+            (&self.gitlab, gitlab),
+            (&self.gitea, gitea),
+        ] {
+            if !batch.is_empty() {
+                results.extend(
+                    client
+                        .download_assets(batch, max_concurrent, max_retries, retry_observer)
+                        .await,
+                );
+            }
+        }
+        results
+    }
+
+    async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        self.client_for(repo).verify_repository(repo).await
+    }
+
+    async fn download_file(&self, url: &str, max_retries: u32) -> Result<Vec<u8>> {
+        self.client_for_url(url)
+            .download_file(url, max_retries)
+            .await
+    }
+
+    /// Reports the GitHub client's limit; GitLab and Gitea limits are not tracked.
+    fn rate_limit(&self) -> Option<RateLimitStatus> {
+        self.github.rate_limit()
+    }
+
+    /// Only the GitHub client caches release listings.
+    fn save_release_cache(&self) {
+        self.github.save_release_cache()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::MockGitHubApi;
+
+    #[tokio::test]
+    async fn routes_repositories_by_provider() {
+        let mut github = MockGitHubApi::new();
+        github
+            .expect_get_releases()
+            .withf(|repo, _| repo.to_string() == "owner/repo")
+            .returning(|_, _| Ok(vec![Release::new("v1.0.0".to_string(), None)]));
+        let mut gitlab = MockGitHubApi::new();
+        gitlab
+            .expect_get_releases()
+            .withf(|repo, _| repo.to_string() == "gitlab:group/repo")
+            .returning(|_, _| Ok(vec![Release::new("v2.0.0".to_string(), None)]));
+        let mut gitea = MockGitHubApi::new();
+        gitea
+            .expect_get_releases()
+            .withf(|repo, _| repo.to_string() == "gitea:org/repo")
+            .returning(|_, _| Ok(vec![Release::new("v3.0.0".to_string(), None)]));
+        let client = ProviderClient::new(github, gitlab, gitea);
+
+        let github_releases = client
+            .get_releases(&Repository::parse("owner/repo").unwrap(), "package.json")
+            .await
+            .unwrap();
+        let gitlab_releases = client
+            .get_releases(
+                &Repository::parse("gitlab:group/repo").unwrap(),
+                "package.json",
+            )
+            .await
+            .unwrap();
+        let gitea_releases = client
+            .get_releases(
+                &Repository::parse("gitea:org/repo").unwrap(),
+                "package.json",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(github_releases[0].version(), "1.0.0");
+        assert_eq!(gitlab_releases[0].version(), "2.0.0");
+        assert_eq!(gitea_releases[0].version(), "3.0.0");
+    }
+
+    #[tokio::test]
+    async fn routes_downloads_by_url_host() {
+        let mut github = MockGitHubApi::new();
+        github
+            .expect_download_assets()
+            .withf(|releases, _, _, _| releases.len() == 1 && releases[0].version() == "1.0.0")
+            .returning(|releases, _, _, _| {
+                releases
+                    .into_iter()
+                    .map(|r| (r, Ok("github".to_string())))
+                    .collect()
+            });
+        github
+            .expect_download_file()
+            .withf(|url, _| url == "https://example.com/package.zip")
+            .returning(|_, _| Ok(b"github".to_vec()));
+        let mut gitlab = MockGitHubApi::new();
+        gitlab
+            .expect_serves_url()
+            .returning(|url| url.starts_with("https://gitlab.com/"));
+        gitlab
+            .expect_download_assets()
+            .withf(|releases, _, _, _| releases.len() == 1 && releases[0].version() == "2.0.0")
+            .returning(|releases, _, _, _| {
+                releases
+                    .into_iter()
+                    .map(|r| (r, Ok("gitlab".to_string())))
+                    .collect()
+            });
+        let mut gitea = MockGitHubApi::new();
+        gitea
+            .expect_serves_url()
+            .returning(|url| url.starts_with("https://git.example.com/"));
+        gitea
+            .expect_download_file()
+            .withf(|url, _| url == "https://git.example.com/package.zip")
+            .returning(|_, _| Ok(b"gitea".to_vec()));
+        let client = ProviderClient::new(github, gitlab, gitea);
+
+        let mut downloads = client
+            .download_assets(
+                vec![
+                    Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://github.com/owner/repo/package.json".to_string()),
+                    ),
+                    Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://gitlab.com/group/repo/package.json".to_string()),
+                    ),
+                ],
+                4,
+                0,
+                None,
+            )
+            .await
+            .into_iter()
+            .map(|(release, result)| (release.version().to_string(), result.unwrap()))
+            .collect::<Vec<_>>();
+        downloads.sort();
+
+        assert_eq!(
+            downloads,
+            vec![
+                ("1.0.0".to_string(), "github".to_string()),
+                ("2.0.0".to_string(), "gitlab".to_string()),
+            ]
+        );
+        assert_eq!(
+            client
+                .download_file("https://example.com/package.zip", 0)
+                .await
+                .unwrap(),
+            b"github"
+        );
+        assert_eq!(
+            client
+                .download_file("https://git.example.com/package.zip", 0)
+                .await
+                .unwrap(),
+            b"gitea"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::filesystem::{read_to_string_if_exists, write_atomic_file};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Returns the path of the release listing cache next to the given lock file.
+pub fn release_cache_path(lock_path: &Path) -> PathBuf {
+    lock_path.with_extension("cache")
+}
+
+/// The parts of a GitHub release that asset selection needs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ListedRelease {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<ListedAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ListedAsset {
+    pub name: String,
+    pub size: i64,
+    pub browser_download_url: String,
+}
+
+/// One page of a repository's release listing with the validators GitHub
+/// sent for it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CachedPage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    pub releases: Vec<ListedRelease>,
+}
+
+/// Release listings from earlier runs, keyed by repository, so unchanged
+/// pages can be revalidated with conditional requests instead of re-listed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ReleaseCache {
+    repositories: BTreeMap<String, Vec<CachedPage>>,
+}
+
+impl ReleaseCache {
+    /// Loads the cache, returning an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string_if_exists(path).map_err(|e| Error::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let Some(content) = content else {
+            return Ok(Self::default());
+        };
+
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse {
+            source: path.display().to_string(),
+            error: e,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string(self).map_err(Error::JsonSerialize)?;
+        write_atomic_file(path, &content).map_err(|e| Error::FileWrite {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    /// Returns the cached page `index` (starting at 0) of `repository`.
+    pub fn page(&self, repository: &str, index: usize) -> Option<&CachedPage> {
+        self.repositories.get(repository)?.get(index)
+    }
+
+    /// Replaces every cached page of `repository`.
+    pub fn insert(&mut self, repository: &str, pages: Vec<CachedPage>) {
+        self.repositories.insert(repository.to_string(), pages);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = release_cache_path(&dir.path().join("indexes/main.lock"));
+        assert_eq!(path, dir.path().join("indexes/main.cache"));
+        assert!(ReleaseCache::load(&path).unwrap().page("o/r", 0).is_none());
+
+        let page = CachedPage {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            releases: vec![ListedRelease {
+                tag_name: "v1.0.0".to_string(),
+                assets: vec![ListedAsset {
+                    name: "package.json".to_string(),
+                    size: 10,
+                    browser_download_url: "https://example.com/package.json".to_string(),
+                }],
+            }],
+        };
+        let mut cache = ReleaseCache::default();
+        cache.insert("o/r", vec![page.clone()]);
+        cache.save(&path).unwrap();
+
+        let loaded = ReleaseCache::load(&path).unwrap();
+        assert_eq!(loaded.page("o/r", 0), Some(&page));
+        assert!(loaded.page("o/r", 1).is_none());
+    }
+}
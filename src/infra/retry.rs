@@ -1,9 +1,72 @@
+use crate::error::{Error, Result};
+use reqwest::Url;
 use std::time::Duration;
+use tracing::{debug, warn};
 
 const RETRY_DELAY_BASE_MS: u64 = 500;
 const RETRY_DELAY_MAX_MS: u64 = 30_000;
+/// Fraction of the backoff delay added or removed at random, so clients that
+/// failed together do not retry in lockstep.
+const RETRY_JITTER: f64 = 0.25;
 
+/// Receives a notification each time an operation backs off before retrying.
+pub trait RetryObserver: Send + Sync {
+    /// Called before waiting for retry `attempt` (starting at 1) of `url`.
+    fn on_retry(&self, url: &str, attempt: u32);
+}
+
+/// A retry budget that overrides the global one for downloads from a host.
+///
+/// `host` is either an exact host name or `*.domain`, which matches any
+/// subdomain of `domain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostRetries {
+    pub host: String,
+    pub max_retries: u32,
+}
+
+impl HostRetries {
+    fn matches(&self, host: &str) -> bool {
+        match self.host.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.')),
+            None => self.host.eq_ignore_ascii_case(host),
+        }
+    }
+}
+
+/// Returns the retry budget for `url`: the first matching host override,
+/// or `default` when none applies.
+pub(crate) fn retries_for_url(overrides: &[HostRetries], url: &str, default: u32) -> u32 {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_ascii_lowercase))
+    else {
+        return default;
+    };
+    overrides
+        .iter()
+        .find(|o| o.matches(&host))
+        .map_or(default, |o| o.max_retries)
+}
+
+/// Returns the delay before retry `attempt`: exponential backoff with
+/// ±[`RETRY_JITTER`] random jitter, capped at [`RETRY_DELAY_MAX_MS`].
 pub(crate) fn retry_backoff_delay(attempt: u32) -> Duration {
+    jittered_backoff_delay(attempt, fastrand::f64())
+}
+
+/// Applies jitter to the backoff delay for `attempt`, where `sample` in
+/// `[0, 1)` maps linearly onto `[-RETRY_JITTER, +RETRY_JITTER]`.
+fn jittered_backoff_delay(attempt: u32, sample: f64) -> Duration {
+    let factor = 1.0 - RETRY_JITTER + 2.0 * RETRY_JITTER * sample.clamp(0.0, 1.0);
+    base_backoff_delay(attempt)
+        .mul_f64(factor)
+        .min(Duration::from_millis(RETRY_DELAY_MAX_MS))
+}
+
+fn base_backoff_delay(attempt: u32) -> Duration {
     let exponent = attempt.saturating_sub(1).min(16);
     let factor = 1u64 << exponent;
     let delay_ms = RETRY_DELAY_BASE_MS
@@ -12,17 +75,123 @@ pub(crate) fn retry_backoff_delay(attempt: u32) -> Duration {
     Duration::from_millis(delay_ms)
 }
 
+fn should_retry_download_error(error: &Error) -> bool {
+    match error {
+        Error::Http { source, .. } => {
+            if let Some(status) = source.status() {
+                return status.is_server_error() || status.as_u16() == 429;
+            }
+            source.is_timeout() || source.is_connect() || source.is_request()
+        }
+        _ => false,
+    }
+}
+
+/// Runs the download `f` for `url`, retrying transient HTTP failures up to
+/// `max_retries` times with exponential backoff.
+pub(crate) async fn retry_download<T, F, Fut>(
+    url: &str,
+    max_retries: u32,
+    retry_observer: Option<&dyn RetryObserver>,
+    f: F,
+) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let total_attempts = max_retries + 1;
+    let mut last_error: Option<Error> = None;
+
+    for attempt in 0..total_attempts {
+        if attempt > 0 {
+            let delay = retry_backoff_delay(attempt);
+            warn!(attempt, max_retries, ?delay, "Retrying download");
+            if let Some(observer) = retry_observer {
+                observer.on_retry(url, attempt);
+            }
+            tokio::time::sleep(delay).await;
+        }
+
+        match f().await {
+            Ok(output) => return Ok(output),
+            Err(e) => {
+                debug!(url, attempt, error = %e, "Download attempt failed");
+                if !should_retry_download_error(&e) || attempt + 1 >= total_attempts {
+                    return Err(e);
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        Error::ConfigValidation("Retry loop finished without attempts".to_string())
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn backoff_starts_at_base_delay() {
-        assert_eq!(retry_backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(base_backoff_delay(1), Duration::from_millis(500));
     }
 
     #[test]
     fn backoff_is_capped() {
-        assert_eq!(retry_backoff_delay(30), Duration::from_millis(30_000));
+        assert_eq!(base_backoff_delay(30), Duration::from_millis(30_000));
+        assert!(retry_backoff_delay(30) <= Duration::from_millis(30_000));
+    }
+
+    #[test]
+    fn jitter_stays_within_a_quarter_of_the_base_delay() {
+        assert_eq!(jittered_backoff_delay(2, 0.0), Duration::from_millis(750));
+        assert_eq!(jittered_backoff_delay(2, 0.5), Duration::from_millis(1000));
+        assert_eq!(jittered_backoff_delay(2, 1.0), Duration::from_millis(1250));
+
+        let mut rng = fastrand::Rng::with_seed(7);
+        for attempt in 1..=6 {
+            let base = base_backoff_delay(attempt);
+            let delay = jittered_backoff_delay(attempt, rng.f64());
+            assert!(delay >= base.mul_f64(0.75), "{delay:?} below {base:?}");
+            assert!(delay <= base.mul_f64(1.25), "{delay:?} above {base:?}");
+        }
+        for _ in 0..100 {
+            let delay = retry_backoff_delay(1);
+            assert!((375..=625).contains(&delay.as_millis()), "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn host_override_applies_to_matching_hosts_only() {
+        let overrides = vec![
+            HostRetries {
+                host: "cdn.example.com".to_string(),
+                max_retries: 1,
+            },
+            HostRetries {
+                host: "*.mirror.example".to_string(),
+                max_retries: 0,
+            },
+        ];
+
+        assert_eq!(
+            retries_for_url(&overrides, "https://cdn.example.com/a.json", 3),
+            1
+        );
+        assert_eq!(
+            retries_for_url(&overrides, "https://eu.mirror.example/a.json", 3),
+            0
+        );
+        assert_eq!(
+            retries_for_url(&overrides, "https://mirror.example/a.json", 3),
+            3
+        );
+        assert_eq!(
+            retries_for_url(&overrides, "https://github.com/a.json", 3),
+            3
+        );
+        assert_eq!(retries_for_url(&overrides, "not a url", 3), 3);
     }
 }
@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+/// Prefix rewrites applied to URLs before they are requested, e.g. to fetch
+/// GitHub release assets through a mirror.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlRewrite {
+    /// `(from, to)` prefixes, longest `from` first.
+    rules: Vec<(String, String)>,
+}
+
+impl UrlRewrite {
+    pub fn new(rules: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut rules: Vec<_> = rules.into_iter().collect();
+        // Longest prefix first, so the most specific rule wins.
+        rules.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Replaces the first matching `from` prefix of `url` with its `to`
+    /// prefix. URLs without a matching prefix are returned unchanged.
+    pub fn apply<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        self.rules
+            .iter()
+            .find_map(|(from, to)| {
+                url.strip_prefix(from.as_str())
+                    .map(|rest| Cow::Owned(format!("{to}{rest}")))
+            })
+            .unwrap_or(Cow::Borrowed(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(rules: &[(&str, &str)]) -> UrlRewrite {
+        UrlRewrite::new(
+            rules
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string())),
+        )
+    }
+
+    #[test]
+    fn rewrites_only_prefix_matches() {
+        let rewrite = rewrite(&[("https://github.com/", "https://mirror.example/gh/")]);
+
+        assert_eq!(
+            rewrite.apply("https://github.com/o/r/releases/download/v1/package.json"),
+            "https://mirror.example/gh/o/r/releases/download/v1/package.json"
+        );
+        assert_eq!(
+            rewrite.apply("https://example.com/?from=https://github.com/"),
+            "https://example.com/?from=https://github.com/"
+        );
+        assert_eq!(
+            rewrite.apply("http://github.com/o/r"),
+            "http://github.com/o/r"
+        );
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let rewrite = rewrite(&[
+            ("https://github.com/", "https://mirror.example/gh/"),
+            ("https://github.com/org/", "https://org-mirror.example/"),
+        ]);
+
+        assert_eq!(
+            rewrite.apply("https://github.com/org/repo.zip"),
+            "https://org-mirror.example/repo.zip"
+        );
+        assert_eq!(
+            rewrite.apply("https://github.com/other/repo.zip"),
+            "https://mirror.example/gh/other/repo.zip"
+        );
+    }
+}
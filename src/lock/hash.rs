@@ -0,0 +1,105 @@
+use sha2::{Digest, Sha256, Sha512};
+
+/// Digest algorithm used for hashes stored in the lockfile.
+///
+/// Hashes are written as `<algorithm>:<hex>` so the algorithm travels with
+/// the value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Hashes `content` and returns the prefixed digest.
+    pub fn digest(self, content: &str) -> String {
+        let hex = match self {
+            Self::Sha256 => format!("{:x}", Sha256::digest(content.as_bytes())),
+            Self::Sha512 => format!("{:x}", Sha512::digest(content.as_bytes())),
+        };
+        format!("{}:{}", self.prefix(), hex)
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "sha256" => Some(Self::Sha256),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+}
+
+/// Lowercase hex SHA-256 of raw bytes, the form used by `zipSHA256`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Splits a stored hash into its algorithm and hex digest.
+///
+/// Values without a prefix were written before hashes carried one and are
+/// read as SHA-256. Returns `None` for an unknown prefix.
+pub fn parse_hash(hash: &str) -> Option<(HashAlgorithm, &str)> {
+    match hash.split_once(':') {
+        Some((prefix, hex)) => HashAlgorithm::from_prefix(prefix).map(|algo| (algo, hex)),
+        None => Some((HashAlgorithm::Sha256, hash)),
+    }
+}
+
+/// Compares two hashes by algorithm and digest, so a legacy unprefixed
+/// SHA-256 value matches its prefixed form but never a different algorithm.
+pub fn hashes_match(a: &str, b: &str) -> bool {
+    match (parse_hash(a), parse_hash(b)) {
+        (Some((algo_a, hex_a)), Some((algo_b, hex_b))) => {
+            algo_a == algo_b && hex_a.eq_ignore_ascii_case(hex_b)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_is_prefixed_with_algorithm() {
+        let sha256 = HashAlgorithm::Sha256.digest("content");
+        let sha512 = HashAlgorithm::Sha512.digest("content");
+
+        assert!(sha256.starts_with("sha256:"));
+        assert_eq!(sha256.len(), "sha256:".len() + 64);
+        assert!(sha512.starts_with("sha512:"));
+        assert_eq!(sha512.len(), "sha512:".len() + 128);
+    }
+
+    #[test]
+    fn parse_hash_reads_unprefixed_value_as_sha256() {
+        assert_eq!(
+            parse_hash("abc123"),
+            Some((HashAlgorithm::Sha256, "abc123"))
+        );
+        assert_eq!(
+            parse_hash("sha512:abc123"),
+            Some((HashAlgorithm::Sha512, "abc123"))
+        );
+        assert_eq!(parse_hash("md5:abc123"), None);
+    }
+
+    #[test]
+    fn hashes_match_is_prefix_aware() {
+        let prefixed = HashAlgorithm::Sha256.digest("content");
+        let legacy = prefixed.strip_prefix("sha256:").unwrap();
+
+        assert!(hashes_match(&prefixed, &prefixed));
+        assert!(hashes_match(legacy, &prefixed));
+        assert!(!hashes_match(&prefixed, &format!("sha512:{}", legacy)));
+        assert!(!hashes_match("md5:abc", "md5:abc"));
+    }
+}
@@ -1,13 +1,15 @@
+use super::hash::{HashAlgorithm, hashes_match, parse_hash};
 use super::package_manifest::PackageManifest;
-use crate::config::Manifest;
+use crate::config::{ConfigFormat, Manifest};
 use crate::domain::Repository;
 use crate::error::{Error, Result};
 use crate::infra::write_atomic_file;
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Current lockfile version that will be written.
 const LOCKFILE_VERSION: u32 = 1;
@@ -18,6 +20,9 @@ const MIN_SUPPORTED_VERSION: u32 = 1;
 /// Maximum supported lockfile version for reading.
 const MAX_SUPPORTED_VERSION: u32 = 1;
 
+/// First line of the optional header comment, also used to detect it on load.
+const HEADER_MARKER: &str = "# This file is generated by voyager. Do not edit it by hand.";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lockfile {
     pub version: u32,
@@ -25,6 +30,10 @@ pub struct Lockfile {
     pub manifest_hash: Option<String>,
     #[serde(default)]
     pub packages: Vec<LockedPackage>,
+    /// Whether the loaded file started with the "generated by voyager"
+    /// comment, so saving keeps it.
+    #[serde(skip)]
+    header_comment: bool,
 }
 
 impl Default for Lockfile {
@@ -39,6 +48,7 @@ impl Lockfile {
             version: LOCKFILE_VERSION,
             manifest_hash: None,
             packages: Vec::new(),
+            header_comment: false,
         }
     }
 
@@ -70,6 +80,7 @@ impl Lockfile {
         }
 
         lockfile = Self::migrate(lockfile)?;
+        lockfile.header_comment = content.starts_with(HEADER_MARKER);
 
         Ok(lockfile)
     }
@@ -88,11 +99,38 @@ impl Lockfile {
         }
     }
 
+    /// Whether the loaded file started with the header comment.
+    pub fn has_header_comment(&self) -> bool {
+        self.header_comment
+    }
+
+    /// Serializes the lockfile, with the header comment when `header_comment`
+    /// is set or the loaded file already had one.
+    pub fn to_toml_string(
+        &self,
+        header_comment: bool,
+    ) -> std::result::Result<String, toml::ser::Error> {
+        let content = toml::to_string_pretty(self)?;
+        if !header_comment && !self.header_comment {
+            return Ok(content);
+        }
+
+        Ok(format!(
+            "{}\n# voyager {}, written {}\n\n{}",
+            HEADER_MARKER,
+            env!("CARGO_PKG_VERSION"),
+            now_utc(),
+            content
+        ))
+    }
+
     pub fn save(&self, path: &Path) -> Result<()> {
-        let content = toml::to_string_pretty(self).map_err(|e| Error::TomlSerialize {
-            path: path.display().to_string(),
-            source: e,
-        })?;
+        let content = self
+            .to_toml_string(false)
+            .map_err(|e| Error::TomlSerialize {
+                path: path.display().to_string(),
+                source: e,
+            })?;
 
         write_atomic_file(path, &content).map_err(|e| Error::FileWrite {
             path: path.display().to_string(),
@@ -100,6 +138,69 @@ impl Lockfile {
         })
     }
 
+    /// Returns the algorithm of the stored manifest hash, defaulting to
+    /// SHA-256 when there is none.
+    pub fn manifest_hash_algorithm(&self) -> HashAlgorithm {
+        self.manifest_hash
+            .as_deref()
+            .and_then(parse_hash)
+            .map(|(algorithm, _)| algorithm)
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the stored manifest hash matches `hash`, comparing
+    /// algorithm and digest.
+    pub fn manifest_hash_matches(&self, hash: &str) -> bool {
+        self.manifest_hash
+            .as_deref()
+            .is_some_and(|stored| hashes_match(stored, hash))
+    }
+
+    /// Brings the packages in line with `manifest`: stale packages are
+    /// removed unless retained, missing ones are added, a changed repository
+    /// drops the locked versions, and packages follow manifest order.
+    pub fn reconcile(&mut self, manifest: &Manifest) -> ReconcileChanges {
+        let manifest_order: HashMap<&str, usize> = manifest
+            .packages
+            .iter()
+            .enumerate()
+            .map(|(index, pkg)| (pkg.id.as_str(), index))
+            .collect();
+        let mut changes = ReconcileChanges::default();
+
+        self.packages.retain(|pkg| {
+            let keep = pkg.retained || manifest_order.contains_key(pkg.id.as_str());
+            if !keep {
+                changes.removed.push(pkg.id.clone());
+            }
+            keep
+        });
+
+        for package in &manifest.packages {
+            if self.get_package(&package.id).is_none() {
+                changes.added.push(package.id.clone());
+            }
+            let locked_pkg = self.get_or_insert_package(&package.id, &package.repository);
+            locked_pkg.retained = false;
+            if locked_pkg.repository != package.repository {
+                locked_pkg.repository = package.repository.clone();
+                locked_pkg.versions.clear();
+                changes.repository_changed.push(package.id.clone());
+            }
+        }
+
+        let position = |pkg: &LockedPackage| {
+            manifest_order
+                .get(pkg.id.as_str())
+                .copied()
+                .unwrap_or(usize::MAX)
+        };
+        changes.reordered = !self.packages.is_sorted_by_key(position);
+        self.packages.sort_by_key(position);
+
+        changes
+    }
+
     pub fn get_package(&self, id: &str) -> Option<&LockedPackage> {
         self.packages.iter().find(|p| p.id == id)
     }
@@ -120,23 +221,56 @@ impl Lockfile {
                 id: id.to_string(),
                 repository: repository.clone(),
                 versions: Vec::new(),
+                retained: false,
             });
             self.packages.last_mut().unwrap()
         }
     }
 }
 
+/// What [`Lockfile::reconcile`] changed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReconcileChanges {
+    /// Stale packages no longer in the manifest.
+    pub removed: Vec<String>,
+    /// Manifest packages that had no entry yet.
+    pub added: Vec<String>,
+    /// Packages whose repository changed, dropping their locked versions.
+    pub repository_changed: Vec<String>,
+    /// Whether packages were moved into manifest order.
+    pub reordered: bool,
+}
+
+impl ReconcileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+            && self.added.is_empty()
+            && self.repository_changed.is_empty()
+            && !self.reordered
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockedPackage {
     pub id: String,
     pub repository: Repository,
     #[serde(default)]
     pub versions: Vec<LockedVersion>,
+    /// Kept after the package was removed from the manifest with
+    /// `remove --keep-lock`, so fetch does not prune its versions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub retained: bool,
 }
 
 impl LockedPackage {
+    /// Versions locked from their release. Unverified versions are left out
+    /// so fetch downloads them again.
     pub fn existing_versions(&self) -> HashSet<String> {
-        self.versions.iter().map(|v| v.version.clone()).collect()
+        self.versions
+            .iter()
+            .filter(|v| !v.unverified)
+            .map(|v| v.version.clone())
+            .collect()
     }
 
     pub fn get_version(&self, version: &str) -> Option<&LockedVersion> {
@@ -148,6 +282,36 @@ impl LockedPackage {
             self.versions.push(version);
         }
     }
+
+    /// Drops all but the `count` newest versions by semver and returns the
+    /// dropped ones. Kept versions stay in their current order; versions that
+    /// are not valid semver rank below every valid one.
+    pub fn keep_latest(&mut self, count: usize) -> Vec<String> {
+        if self.versions.len() <= count {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<(Option<Version>, usize)> = self
+            .versions
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (Version::parse(&v.version).ok(), index))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let keep: HashSet<usize> = ranked.iter().take(count).map(|(_, index)| *index).collect();
+
+        let mut removed = Vec::new();
+        let mut index = 0;
+        self.versions.retain(|v| {
+            let kept = keep.contains(&index);
+            index += 1;
+            if !kept {
+                removed.push(v.version.clone());
+            }
+            kept
+        });
+        removed
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +320,14 @@ pub struct LockedVersion {
     pub tag: String,
     pub url: String,
     pub hash: String,
+    /// The package.json exactly as it was hashed, kept by `fetch --store-raw`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_manifest: Option<String>,
+    /// Rebuilt by `migrate` from a published index instead of fetched: the
+    /// asset URL is guessed and the hash covers the index entry, so verify
+    /// reports it and fetch locks it again.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unverified: bool,
     pub manifest: PackageManifest,
 }
 
@@ -167,43 +339,99 @@ impl LockedVersion {
             tag,
             url,
             hash,
+            raw_manifest: None,
+            unverified: false,
             manifest,
         }
     }
+
+    /// Rehashes `raw_content`, the content passed to [`LockedVersion::new`],
+    /// with `algorithm` instead of the default SHA-256.
+    pub fn with_hash_algorithm(mut self, raw_content: &str, algorithm: HashAlgorithm) -> Self {
+        self.hash = algorithm.digest(raw_content);
+        self
+    }
+
+    /// Keeps `raw_content` alongside its hash so the locked manifest can be
+    /// audited later.
+    pub fn with_raw_manifest(mut self, raw_content: &str) -> Self {
+        self.raw_manifest = Some(raw_content.to_string());
+        self
+    }
+
+    /// Marks the version as not fetched from its release.
+    pub fn unverified(mut self) -> Self {
+        self.unverified = true;
+        self
+    }
+}
+
+/// Current time as an RFC 3339 UTC timestamp.
+pub(crate) fn now_utc() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_utc(now)
 }
 
+/// Formats Unix seconds as an RFC 3339 UTC timestamp.
+pub(crate) fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// Hashes content with the default algorithm, SHA-256.
 pub fn compute_hash(content: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
-    let result = hasher.finalize();
-    format!("sha256:{:x}", result)
+    HashAlgorithm::Sha256.digest(content)
 }
 
 /// Computes a hash of the manifest file by normalizing it first.
 /// This ensures that whitespace/comment changes don't affect the hash.
 pub fn compute_manifest_hash(path: &Path) -> Result<String> {
-    let content = fs::read_to_string(path).map_err(|e| Error::FileRead {
-        path: path.display().to_string(),
-        source: e,
-    })?;
-
-    let manifest: Manifest = toml::from_str(&content).map_err(|e| Error::TomlParse {
-        path: path.display().to_string(),
-        source: e,
-    })?;
-
+    let manifest = Manifest::load_unvalidated(path, ConfigFormat::Auto)?;
     compute_manifest_hash_from_manifest(&manifest, path)
 }
 
 /// Computes a hash from an in-memory Manifest.
 /// Use this when you have already loaded the manifest and want to avoid re-reading the file.
 pub fn compute_manifest_hash_from_manifest(manifest: &Manifest, path: &Path) -> Result<String> {
+    compute_manifest_hash_with(manifest, path, HashAlgorithm::default())
+}
+
+/// Computes a manifest hash with the given algorithm.
+pub fn compute_manifest_hash_with(
+    manifest: &Manifest,
+    path: &Path,
+    algorithm: HashAlgorithm,
+) -> Result<String> {
     let normalized = toml::to_string(manifest).map_err(|e| Error::TomlSerialize {
         path: path.display().to_string(),
         source: e,
     })?;
 
-    Ok(compute_hash(&normalized))
+    Ok(algorithm.digest(&normalized))
 }
 
 #[cfg(test)]
@@ -294,6 +522,73 @@ mod tests {
         assert_eq!(loaded.packages.len(), 1);
         assert_eq!(loaded.packages[0].id, "com.example.test");
         assert_eq!(loaded.packages[0].versions.len(), 1);
+        assert_eq!(loaded.packages[0].versions[0].raw_manifest, None);
+        assert!(!fs::read_to_string(&path).unwrap().contains("raw_manifest"));
+    }
+
+    #[test]
+    fn raw_manifest_roundtrips_and_matches_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.lock");
+        let raw = "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\"\n}\n";
+
+        let mut lockfile = Lockfile::new();
+        let pkg = lockfile.get_or_insert_package("com.example.test", &repo("owner/repo"));
+        pkg.add_version(
+            LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/v1.0.0/package.json".to_string(),
+                raw,
+                create_test_version_output(),
+            )
+            .with_raw_manifest(raw),
+        );
+        lockfile.save(&path).unwrap();
+
+        let loaded = Lockfile::load(&path).unwrap();
+        let version = &loaded.packages[0].versions[0];
+        assert_eq!(version.raw_manifest.as_deref(), Some(raw));
+        assert_eq!(compute_hash(raw), version.hash);
+    }
+
+    #[test]
+    fn lockfile_header_comment_is_written_and_kept_on_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.lock");
+
+        let mut lockfile = Lockfile::new();
+        lockfile.manifest_hash = Some("sha256:abc".to_string());
+        fs::write(&path, lockfile.to_toml_string(true).unwrap()).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(HEADER_MARKER));
+        assert!(content.contains(&format!(
+            "# voyager {}, written ",
+            env!("CARGO_PKG_VERSION")
+        )));
+
+        let loaded = Lockfile::load(&path).unwrap();
+        assert_eq!(loaded.manifest_hash.as_deref(), Some("sha256:abc"));
+        assert!(loaded.has_header_comment());
+        assert!(
+            loaded
+                .to_toml_string(false)
+                .unwrap()
+                .starts_with(HEADER_MARKER)
+        );
+        assert!(
+            !Lockfile::new()
+                .to_toml_string(false)
+                .unwrap()
+                .starts_with('#')
+        );
+    }
+
+    #[test]
+    fn format_utc_renders_rfc3339() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14T22:13:20Z");
     }
 
     #[test]
@@ -340,13 +635,21 @@ repository = "owner/repo"
                     v.version = "2.0.0".to_string();
                     v
                 }),
+                LockedVersion::new("v3.0.0".to_string(), "url3".to_string(), "content3", {
+                    let mut v = create_test_version_output();
+                    v.version = "3.0.0".to_string();
+                    v
+                })
+                .unverified(),
             ],
+            retained: false,
         };
 
         let existing = pkg.existing_versions();
         assert!(existing.contains("1.0.0"));
         assert!(existing.contains("2.0.0"));
         assert!(!existing.contains("3.0.0"));
+        assert!(!existing.contains("4.0.0"));
     }
 
     #[test]
@@ -355,6 +658,7 @@ repository = "owner/repo"
             id: "test".to_string(),
             repository: repo("owner/repo"),
             versions: vec![],
+            retained: false,
         };
 
         let version = LockedVersion::new(
@@ -370,6 +674,31 @@ repository = "owner/repo"
         assert_eq!(pkg.versions.len(), 1);
     }
 
+    #[test]
+    fn keep_latest_keeps_newest_versions_in_order() {
+        let version = |v: &str| {
+            let mut manifest = create_test_version_output();
+            manifest.version = v.to_string();
+            LockedVersion::new(format!("v{v}"), format!("url-{v}"), v, manifest)
+        };
+        let mut pkg = LockedPackage {
+            id: "test".to_string(),
+            repository: repo("owner/repo"),
+            versions: ["2.0.0", "10.0.0", "1.0.0", "not-semver", "2.0.0-beta.1"]
+                .into_iter()
+                .map(version)
+                .collect(),
+            retained: false,
+        };
+
+        let removed = pkg.keep_latest(2);
+
+        let kept: Vec<_> = pkg.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(kept, vec!["2.0.0", "10.0.0"]);
+        assert_eq!(removed, vec!["1.0.0", "not-semver", "2.0.0-beta.1"]);
+        assert!(pkg.keep_latest(5).is_empty());
+    }
+
     #[test]
     fn get_or_insert_package_creates_new() {
         let mut lockfile = Lockfile::new();
@@ -1,8 +1,11 @@
+mod hash;
 mod lockfile;
 mod package_manifest;
 
+pub use hash::{HashAlgorithm, hashes_match, parse_hash, sha256_hex};
 pub use lockfile::{
-    LockedPackage, LockedVersion, Lockfile, compute_manifest_hash,
-    compute_manifest_hash_from_manifest,
+    LockedPackage, LockedVersion, Lockfile, ReconcileChanges, compute_hash, compute_manifest_hash,
+    compute_manifest_hash_from_manifest, compute_manifest_hash_with,
 };
+pub(crate) use lockfile::{format_utc, now_utc};
 pub use package_manifest::{PackageAuthor, PackageManifest, Sample};
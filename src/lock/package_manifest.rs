@@ -68,6 +68,21 @@ pub struct PackageManifest {
     pub extra: IndexMap<String, Value>,
 }
 
+impl PackageManifest {
+    /// Returns the keywords trimmed, lowercased and without duplicates or
+    /// empty entries, in first-seen order.
+    pub fn normalized_keywords(&self) -> Vec<String> {
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in &self.keywords {
+            let keyword = keyword.trim().to_lowercase();
+            if !keyword.is_empty() && !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+        keywords
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
 pub struct PackageAuthor {
     pub name: String,
@@ -222,6 +237,23 @@ mod tests {
         assert_eq!(manifest.author, PackageAuthor::default());
     }
 
+    #[test]
+    fn normalizes_keywords() {
+        let json = r#"{
+            "name": "com.example.pkg",
+            "version": "1.2.3",
+            "url": "https://example.com/pkg.zip",
+            "keywords": [" Avatar", "avatar", "", "TOOLS ", "  ", "tools", "Shader"]
+        }"#;
+
+        let manifest: PackageManifest = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            manifest.normalized_keywords(),
+            ["avatar", "tools", "shader"]
+        );
+    }
+
     #[test]
     fn deserializes_vpm_extension_fields() {
         let json = r#"{
@@ -1,11 +1,15 @@
 use clap::Parser;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
-use voyager::cli::{Cli, Commands, ConfigPaths};
+use voyager::cli::{Cli, Commands, ConfigPaths, ErrorFormat, FetchArgs};
 use voyager::commands;
+use voyager::config::Registry;
 use voyager::context::AppContext;
 use voyager::error::Error;
-use voyager::infra::HttpClient;
+use voyager::infra::{
+    GitHubAuth, GitHubClient, GitLabClient, GiteaClient, HttpClient, ProviderClient, UrlRewrite,
+    release_cache_path,
+};
 use voyager::term;
 
 #[tokio::main]
@@ -13,59 +17,190 @@ async fn main() -> std::process::ExitCode {
     dotenvy::dotenv().ok();
 
     let cli = Cli::parse();
-    let paths = ConfigPaths::new(cli.config.clone());
+    let mut paths = ConfigPaths::new(cli.config.clone())
+        .with_lock_extension(&cli.lock_ext)
+        .with_lock_comment(cli.lock_comment)
+        .with_config_format(cli.config_format.into());
+    if let Some(lock) = cli.lock.clone() {
+        paths = paths.with_lock_path(lock);
+    }
 
     term::init(cli.quiet, cli.color);
     init_tracing(cli.verbose);
 
-    if let Err(e) = install_rustls_provider() {
-        term::error(&e);
+    let error_context = cli.error_context || cli.verbose >= 2;
+    let error_format = cli.error_format;
+    let result = match install_rustls_provider() {
+        Ok(()) => run(cli.command, paths).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        report_error(&e, error_format, error_context);
         return e.exit_code().into();
     }
 
-    if let Err(e) = run(cli.command, paths).await {
-        term::error(&e);
-        if matches!(e, Error::ManifestHashMismatch) {
-            term::hint("Run 'voy lock' to validate and accept changes.");
+    std::process::ExitCode::SUCCESS
+}
+
+fn report_error(e: &Error, format: ErrorFormat, error_context: bool) {
+    let message = if error_context {
+        term::error_chain(e)
+    } else {
+        e.to_string()
+    };
+    match format {
+        ErrorFormat::Text => {
+            term::error(message);
+            if matches!(e, Error::ManifestHashMismatch) {
+                term::hint("Run 'voy lock' to validate and accept changes.");
+            }
+        }
+        ErrorFormat::Json => {
+            let report = serde_json::json!({
+                "code": e.code(),
+                "message": message,
+                "exit_code": e.exit_code().0,
+            });
+            eprintln!("{}", report);
         }
-        return e.exit_code().into();
     }
-
-    std::process::ExitCode::SUCCESS
 }
 
 async fn run(command: Commands, paths: ConfigPaths) -> Result<(), Error> {
     match command {
         Commands::Fetch(args) => {
-            term::warn_if_no_github_token(args.github_token.as_deref());
-            let ctx = AppContext::new(paths, args.github_token.as_deref())?;
-            commands::fetch::execute(args, &ctx).await
+            let auth = args.github_auth.auth()?;
+            term::warn_if_anonymous_github(&auth);
+            let build = fetch_client_factory(&args, auth, &paths);
+            let ctx = AppContext::with_client_factory(paths, build);
+            commands::fetch::execute(*args, &ctx).await
         }
         Commands::Generate(args) => commands::generate::execute(args, &paths),
         Commands::Validate(args) => {
-            let http = Arc::new(HttpClient::new()?);
+            let http = Arc::new(
+                HttpClient::new()?
+                    .with_timeouts(args.timeouts.timeouts())?
+                    .with_method(args.method.into())
+                    .with_url_rewrite(UrlRewrite::new(registry(&paths)?.url_rewrite)),
+            );
             commands::validate::execute(args, http).await
         }
         Commands::Init(args) => commands::init::execute(args, &paths),
+        Commands::Migrate(args) => commands::migrate::execute(args, &paths),
         Commands::Add(args) => {
-            term::warn_if_no_github_token(args.github_token.as_deref());
-            let ctx = AppContext::new(paths, args.github_token.as_deref())?;
+            let auth = args.github_auth.auth()?;
+            term::warn_if_anonymous_github(&auth);
+            let ctx = AppContext::with_auth(
+                paths,
+                auth,
+                args.gitlab_token.as_deref(),
+                args.gitea_token.as_deref(),
+            );
             commands::add::execute(args, &ctx).await
         }
         Commands::Lock(args) => {
-            let ctx = AppContext::new(paths, args.github_token.as_deref())?;
+            let auth = args.github_auth.auth()?;
+            let ctx = AppContext::with_auth(
+                paths,
+                auth,
+                args.gitlab_token.as_deref(),
+                args.gitea_token.as_deref(),
+            );
             commands::lock::execute(args, &ctx).await
         }
         Commands::List(args) => commands::list::execute(args, &paths),
         Commands::Remove(args) => commands::remove::execute(args, &paths),
+        Commands::Prune(args) => commands::prune::execute(args, &paths),
         Commands::Info(args) => commands::info::execute(args, &paths),
+        Commands::Verify(args) => {
+            let auth = args.github_auth.auth()?;
+            term::warn_if_anonymous_github(&auth);
+            let ctx = AppContext::with_auth(
+                paths,
+                auth,
+                args.gitlab_token.as_deref(),
+                args.gitea_token.as_deref(),
+            );
+            commands::verify::execute(args, &ctx).await
+        }
+        Commands::Outdated(args) => {
+            let auth = args.github_auth.auth()?;
+            term::warn_if_anonymous_github(&auth);
+            let ctx = AppContext::with_auth(
+                paths,
+                auth,
+                args.gitlab_token.as_deref(),
+                args.gitea_token.as_deref(),
+            );
+            commands::outdated::execute(args, &ctx).await
+        }
+        Commands::Urls(args) => {
+            let http = Arc::new(HttpClient::new()?);
+            commands::urls::execute(args, &paths, http).await
+        }
+        Commands::Diff(args) => {
+            let http = Arc::new(HttpClient::new()?);
+            commands::diff::execute(args, &paths, http).await
+        }
         Commands::Completions(args) => {
-            args.generate();
+            match &args.out_dir {
+                Some(dir) => {
+                    for path in args.write_to(dir)? {
+                        term::success(format!("Wrote {}", path.display()));
+                    }
+                }
+                None => args.generate(),
+            }
             Ok(())
         }
     }
 }
 
+/// Reads the `[registry]` table of the manifest, if there is one.
+fn registry(paths: &ConfigPaths) -> Result<Registry, Error> {
+    let config_path = paths.config_path();
+    if !config_path.exists() {
+        return Ok(Registry::default());
+    }
+    Registry::load_as(config_path, paths.config_format())
+}
+
+/// Returns how fetch builds its release client once the command has loaded
+/// the manifest's `[registry]` settings.
+fn fetch_client_factory(
+    args: &FetchArgs,
+    auth: GitHubAuth,
+    paths: &ConfigPaths,
+) -> impl Fn(&Registry) -> Result<ProviderClient, Error> + Send + Sync + 'static {
+    let timeouts = args.timeouts.timeouts();
+    let asset_timeout = args.asset_timeout;
+    let asset_match = args.asset_match.into();
+    let asset_regex = args.asset_regex.clone();
+    let retries_per_host = args.retries_per_host.clone();
+    let max_asset_bytes = args.max_asset_bytes;
+    // A dry run writes nothing, so it does not use the release cache either.
+    let cache_path =
+        (!args.no_cache && !args.dry_run).then(|| release_cache_path(paths.lock_path()));
+    let gitlab_token = args.gitlab_token.clone();
+    let gitea_token = args.gitea_token.clone();
+    move |registry| {
+        let mut github = GitHubClient::with_auth_and_timeouts(auth.clone(), timeouts)?
+            .with_asset_timeout(asset_timeout)?
+            .with_asset_match(asset_match)
+            .with_asset_regex(asset_regex.clone())
+            .with_host_retries(retries_per_host.clone())
+            .with_max_asset_bytes(max_asset_bytes)
+            .with_url_rewrite(UrlRewrite::new(registry.url_rewrite.clone()));
+        if let Some(cache_path) = &cache_path {
+            github = github.with_release_cache(cache_path);
+        }
+        let gitlab = GitLabClient::new(gitlab_token.as_deref())?.with_timeouts(timeouts)?;
+        let gitea = GiteaClient::new(registry.gitea_url.as_deref(), gitea_token.as_deref())?
+            .with_timeouts(timeouts)?;
+        Ok(ProviderClient::new(github, gitlab, gitea))
+    }
+}
+
 fn install_rustls_provider() -> Result<(), Error> {
     rustls::crypto::ring::default_provider()
         .install_default()
@@ -11,6 +11,9 @@ pub struct VpmOutput {
     pub url: String,
     pub author: String,
     pub packages: IndexMap<String, PackageOutput>,
+    /// Top-level keys outside the VPM schema, such as build metadata.
+    #[serde(default, flatten, skip_serializing_if = "IndexMap::is_empty")]
+    pub extra: IndexMap<String, Value>,
 }
 
 impl VpmOutput {
@@ -34,6 +37,7 @@ impl VpmOutput {
             url: manifest.vpm.url.clone(),
             author: manifest.vpm.author.clone(),
             packages,
+            extra: IndexMap::new(),
         }
     }
 
@@ -291,6 +295,7 @@ repository = "owner/repo"
                 url: "https://test.com".to_string(),
                 author: "Author".to_string(),
                 packages: IndexMap::new(),
+                extra: IndexMap::new(),
             };
 
             let urls = output.collect_urls();
@@ -0,0 +1,82 @@
+use crate::lock::Lockfile;
+use serde::Serialize;
+
+/// One locked version's asset, as written by `generate --assets`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssetEntry {
+    pub package_id: String,
+    pub version: String,
+    /// URL of the package zip, as published in the index.
+    pub url: String,
+    /// The zip's `zipSHA256`, if the package declares one.
+    pub hash: Option<String>,
+}
+
+/// Lists the zip of every locked version, in lock file order, for mirroring.
+pub fn asset_list(lockfile: &Lockfile) -> Vec<AssetEntry> {
+    lockfile
+        .packages
+        .iter()
+        .flat_map(|package| {
+            package.versions.iter().map(|version| AssetEntry {
+                package_id: package.id.clone(),
+                version: version.version.clone(),
+                url: version.manifest.url.clone(),
+                hash: Some(version.manifest.zip_sha256.clone()).filter(|hash| !hash.is_empty()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Repository;
+    use crate::lock::{LockedPackage, LockedVersion, PackageManifest};
+
+    fn locked_package(id: &str, versions: &[&str]) -> LockedPackage {
+        LockedPackage {
+            id: id.to_string(),
+            repository: Repository::parse("owner/repo").unwrap(),
+            versions: versions
+                .iter()
+                .map(|version| {
+                    let manifest: PackageManifest = serde_json::from_value(serde_json::json!({
+                        "name": id,
+                        "version": version,
+                        "url": format!("https://example.com/{id}/{version}.zip"),
+                        "zipSHA256": "ab".repeat(32),
+                    }))
+                    .unwrap();
+                    LockedVersion::new(
+                        format!("v{version}"),
+                        format!("https://example.com/{id}/{version}/package.json"),
+                        version,
+                        manifest,
+                    )
+                })
+                .collect(),
+            retained: false,
+        }
+    }
+
+    #[test]
+    fn lists_every_locked_version() {
+        let mut lockfile = Lockfile::new();
+        lockfile
+            .packages
+            .push(locked_package("com.example.a", &["1.0.0", "2.0.0"]));
+        lockfile
+            .packages
+            .push(locked_package("com.example.b", &["0.1.0"]));
+
+        let assets = asset_list(&lockfile);
+
+        let total: usize = lockfile.packages.iter().map(|p| p.versions.len()).sum();
+        assert_eq!(assets.len(), total);
+        assert_eq!(assets[2].package_id, "com.example.b");
+        assert_eq!(assets[2].version, "0.1.0");
+        assert_eq!(assets[2].url, "https://example.com/com.example.b/0.1.0.zip");
+        assert_eq!(assets[2].hash, Some("ab".repeat(32)));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::{Error, Result};
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// Produces a unified diff of a file against a VCS ref.
+pub trait DiffSource {
+    fn diff(&self, path: &Path, git_ref: &str) -> Result<String>;
+}
+
+/// Runs `git diff` in the directory containing the file.
+pub struct GitDiffSource;
+
+impl DiffSource for GitDiffSource {
+    fn diff(&self, path: &Path, git_ref: &str) -> Result<String> {
+        let dir = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let file_name = path.file_name().unwrap_or(path.as_os_str());
+
+        let output = Command::new("git")
+            .args(["diff", "--unified=0", git_ref, "--"])
+            .arg(file_name)
+            .current_dir(dir)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::ConfigValidation(format!(
+                "git diff against '{}' failed: {}",
+                git_ref,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Returns the IDs of packages in `config_path` whose entries changed since `git_ref`.
+pub fn packages_changed_since(
+    source: &impl DiffSource,
+    config_path: &Path,
+    git_ref: &str,
+) -> Result<HashSet<String>> {
+    let diff = source.diff(config_path, git_ref)?;
+    let content = std::fs::read_to_string(config_path).map_err(|e| Error::FileRead {
+        path: config_path.display().to_string(),
+        source: e,
+    })?;
+
+    let changed = changed_package_ids(&content, &diff);
+    debug!(git_ref, changed = ?changed, "Resolved changed packages");
+    Ok(changed)
+}
+
+/// Maps the changed lines of a unified diff onto the `[[packages]]` entries
+/// of the current config content.
+fn changed_package_ids(content: &str, diff: &str) -> HashSet<String> {
+    let changed_lines = changed_lines(diff);
+    let mut changed = HashSet::new();
+
+    for entry in package_entries(content) {
+        if changed_lines
+            .iter()
+            .any(|line| (entry.start..=entry.end).contains(line))
+            && let Some(id) = entry.id
+        {
+            changed.insert(id);
+        }
+    }
+
+    changed
+}
+
+/// Returns the 1-based line numbers touched in the new file of a unified diff.
+/// Pure deletions are attributed to the line they follow.
+fn changed_lines(diff: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for header in diff.lines().filter(|line| line.starts_with("@@")) {
+        let Some(new_range) = header
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix('+'))
+        else {
+            continue;
+        };
+        let (start, count) = match new_range.split_once(',') {
+            Some((start, count)) => (start.parse::<usize>().ok(), count.parse::<usize>().ok()),
+            None => (new_range.parse::<usize>().ok(), Some(1)),
+        };
+        let (Some(start), Some(count)) = (start, count) else {
+            continue;
+        };
+
+        if count == 0 {
+            lines.push(start.max(1));
+        } else {
+            lines.extend(start..start + count);
+        }
+    }
+    lines
+}
+
+struct PackageEntry {
+    id: Option<String>,
+    start: usize,
+    end: usize,
+}
+
+fn package_entries(content: &str) -> Vec<PackageEntry> {
+    let mut entries: Vec<PackageEntry> = Vec::new();
+    let mut in_package = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+
+        if trimmed == "[[packages]]" {
+            entries.push(PackageEntry {
+                id: None,
+                start: line_number,
+                end: line_number,
+            });
+            in_package = true;
+            continue;
+        }
+
+        if trimmed.starts_with('[') && !trimmed.starts_with("[packages.") {
+            in_package = false;
+            continue;
+        }
+
+        if in_package && let Some(entry) = entries.last_mut() {
+            entry.end = line_number;
+            if entry.id.is_none() {
+                entry.id = parse_id(trimmed);
+            }
+        }
+    }
+
+    entries
+}
+
+fn parse_id(line: &str) -> Option<String> {
+    let (key, value) = line.split_once('=')?;
+    if key.trim() != "id" {
+        return None;
+    }
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"[vpm]
+id = "com.example.vpm"
+name = "Example"
+author = "Author"
+url = "https://example.com/index.json"
+
+[[packages]]
+id = "com.example.vpm.one"
+repository = "owner/one"
+
+[[packages]]
+id = "com.example.vpm.two"
+repository = "owner/two"
+tags = ["core"]
+"#;
+
+    #[test]
+    fn maps_changed_lines_to_package_ids() {
+        let diff = "\
+diff --git a/voyager.toml b/voyager.toml
+--- a/voyager.toml
++++ b/voyager.toml
+@@ -14 +14 @@ repository = \"owner/two\"
+-tags = []
++tags = [\"core\"]
+";
+        let changed = changed_package_ids(CONFIG, diff);
+        assert_eq!(changed, HashSet::from(["com.example.vpm.two".to_string()]));
+    }
+
+    #[test]
+    fn ignores_changes_outside_packages() {
+        let diff = "@@ -3 +3 @@\n-name = \"Old\"\n+name = \"Example\"\n";
+        assert!(changed_package_ids(CONFIG, diff).is_empty());
+    }
+
+    #[test]
+    fn attributes_pure_deletions_to_preceding_line() {
+        let diff = "@@ -10,0 +9,0 @@\n-tags = [\"old\"]\n";
+        let changed = changed_package_ids(CONFIG, diff);
+        assert_eq!(changed, HashSet::from(["com.example.vpm.one".to_string()]));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::error::{Error, Result};
+use crate::lock::Lockfile;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// `vpmDependencies` edges between the packages of a lockfile.
+///
+/// Dependencies on packages outside the lockfile are ignored; they cannot
+/// take part in a cycle within this repository.
+pub struct DependencyGraph {
+    edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from the dependencies of every locked version.
+    pub fn from_lockfile(lockfile: &Lockfile) -> Self {
+        let mut edges: BTreeMap<String, BTreeSet<String>> = lockfile
+            .packages
+            .iter()
+            .map(|package| (package.id.clone(), BTreeSet::new()))
+            .collect();
+
+        for package in &lockfile.packages {
+            let dependencies: Vec<String> = package
+                .versions
+                .iter()
+                .flat_map(|version| version.manifest.vpm_dependencies.keys())
+                .filter(|id| edges.contains_key(id.as_str()))
+                .cloned()
+                .collect();
+            if let Some(targets) = edges.get_mut(&package.id) {
+                targets.extend(dependencies);
+            }
+        }
+
+        Self { edges }
+    }
+
+    /// Returns the first cycle found, as the package path that closes it
+    /// (e.g. `a -> b -> a`). Packages are visited in id order so the result
+    /// is deterministic.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut state: BTreeMap<&str, Visit> = BTreeMap::new();
+        let mut stack: Vec<&str> = Vec::new();
+
+        for start in self.edges.keys() {
+            if let Some(cycle) = self.visit(start, &mut state, &mut stack) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    fn visit<'a>(
+        &'a self,
+        node: &'a str,
+        state: &mut BTreeMap<&'a str, Visit>,
+        stack: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        match state.get(node) {
+            Some(Visit::Done) => return None,
+            Some(Visit::InProgress) => {
+                let from = stack.iter().position(|id| *id == node).unwrap_or(0);
+                let mut path: Vec<String> = stack[from..].iter().map(|id| id.to_string()).collect();
+                path.push(node.to_string());
+                return Some(path);
+            }
+            None => {}
+        }
+
+        state.insert(node, Visit::InProgress);
+        stack.push(node);
+        for target in self.edges.get(node).into_iter().flatten() {
+            if let Some(cycle) = self.visit(target, state, stack) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        state.insert(node, Visit::Done);
+        None
+    }
+
+    /// Fails with [`Error::DependencyCycle`] if the graph has a cycle.
+    pub fn check_acyclic(&self) -> Result<()> {
+        match self.find_cycle() {
+            Some(path) => Err(Error::DependencyCycle { path }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Repository;
+    use crate::lock::{LockedPackage, LockedVersion, PackageManifest};
+
+    fn package(id: &str, dependencies: &[&str]) -> LockedPackage {
+        let mut manifest: PackageManifest = serde_json::from_value(serde_json::json!({
+            "name": id,
+            "version": "1.0.0",
+            "displayName": id,
+            "description": "desc",
+            "url": format!("https://example.com/{}.zip", id),
+            "author": { "name": "Author" }
+        }))
+        .unwrap();
+        for dependency in dependencies {
+            manifest
+                .vpm_dependencies
+                .insert(dependency.to_string(), ">=1.0.0".to_string());
+        }
+
+        LockedPackage {
+            id: id.to_string(),
+            repository: Repository::parse("owner/repo").unwrap(),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                format!("https://example.com/{}.zip", id),
+                "{}",
+                manifest,
+            )],
+            retained: false,
+        }
+    }
+
+    fn lockfile(packages: Vec<LockedPackage>) -> Lockfile {
+        let mut lockfile = Lockfile::new();
+        lockfile.packages = packages;
+        lockfile
+    }
+
+    #[test]
+    fn detects_mutual_dependency() {
+        let lockfile = lockfile(vec![
+            package("com.example.a", &["com.example.b"]),
+            package("com.example.b", &["com.example.a"]),
+        ]);
+
+        let err = DependencyGraph::from_lockfile(&lockfile)
+            .check_acyclic()
+            .unwrap_err();
+
+        match err {
+            Error::DependencyCycle { path } => {
+                assert_eq!(
+                    path,
+                    vec!["com.example.a", "com.example.b", "com.example.a"]
+                );
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn ignores_external_and_acyclic_dependencies() {
+        let lockfile = lockfile(vec![
+            package("com.example.a", &["com.example.b", "com.vrchat.base"]),
+            package("com.example.b", &["com.vrchat.base"]),
+        ]);
+
+        assert!(
+            DependencyGraph::from_lockfile(&lockfile)
+                .find_cycle()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::config::Package;
+use crate::error::{Error, Result};
+use crate::infra::{read_to_string_if_exists, remove_file_if_exists, write_atomic_file};
+use crate::lock::LockedPackage;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Returns the checkpoint path that lives next to the given config file.
+pub fn checkpoint_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("checkpoint")
+}
+
+/// Packages completed by an in-progress fetch, persisted so that an
+/// interrupted run can be resumed without refetching them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FetchCheckpoint {
+    packages: Vec<LockedPackage>,
+}
+
+impl FetchCheckpoint {
+    /// Loads a checkpoint, returning an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string_if_exists(path).map_err(|e| Error::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let Some(content) = content else {
+            return Ok(Self::default());
+        };
+
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse {
+            source: path.display().to_string(),
+            error: e,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)?;
+        write_atomic_file(path, &content).map_err(|e| Error::FileWrite {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    pub fn remove(path: &Path) -> Result<()> {
+        remove_file_if_exists(path).map_err(|e| Error::FileWrite {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    /// Records a completed package, replacing any earlier entry for it.
+    pub fn record(&mut self, package: LockedPackage) {
+        self.packages.retain(|p| p.id != package.id);
+        self.packages.push(package);
+    }
+
+    /// Returns the completed state for a package, if it was recorded against
+    /// the same repository.
+    pub fn completed(&self, package: &Package) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|p| p.id == package.id && p.repository == package.repository)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Repository;
+    use tempfile::TempDir;
+
+    fn repo(s: &str) -> Repository {
+        Repository::parse(s).unwrap()
+    }
+
+    fn package(id: &str, repository: &str) -> Package {
+        Package {
+            id: id.to_string(),
+            repository: repo(repository),
+            suffix: None,
+            tags: Vec::new(),
+            versions: Vec::new(),
+            version_range: None,
+            asset_name: None,
+        }
+    }
+
+    #[test]
+    fn load_returns_empty_when_file_is_missing() {
+        let dir = TempDir::new().unwrap();
+        let checkpoint = FetchCheckpoint::load(&dir.path().join("voyager.checkpoint")).unwrap();
+        assert!(
+            checkpoint
+                .completed(&package("com.example.pkg", "owner/repo"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = checkpoint_path(&dir.path().join("voyager.toml"));
+
+        let mut checkpoint = FetchCheckpoint::default();
+        checkpoint.record(LockedPackage {
+            id: "com.example.pkg".to_string(),
+            repository: repo("owner/repo"),
+            versions: Vec::new(),
+            retained: false,
+        });
+        checkpoint.save(&path).unwrap();
+
+        let loaded = FetchCheckpoint::load(&path).unwrap();
+        assert!(
+            loaded
+                .completed(&package("com.example.pkg", "owner/repo"))
+                .is_some()
+        );
+        assert!(
+            loaded
+                .completed(&package("com.example.pkg", "owner/other"))
+                .is_none()
+        );
+
+        FetchCheckpoint::remove(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
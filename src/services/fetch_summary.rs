@@ -0,0 +1,115 @@
+use super::package_fetcher::FetchProgressReporter;
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Version counts of one package after a fetch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PackageFetchSummary {
+    /// Package ID from the manifest.
+    #[serde(rename = "id")]
+    pub package_id: String,
+    /// Versions that were already locked and kept.
+    pub existing: usize,
+    /// Versions newly added to the lockfile.
+    pub new: usize,
+    /// Versions whose download or validation failed.
+    pub failed: usize,
+}
+
+/// Version counts summed over all packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FetchTotals {
+    pub existing: usize,
+    pub new: usize,
+    pub failed: usize,
+}
+
+/// Machine-readable result of `voy fetch --format json`.
+///
+/// Packages are listed in manifest order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FetchSummary {
+    pub packages: Vec<PackageFetchSummary>,
+    pub total: FetchTotals,
+}
+
+/// Collects per-package counts from fetch progress events.
+#[derive(Debug, Default)]
+pub struct FetchSummaryReporter {
+    packages: Mutex<Vec<PackageFetchSummary>>,
+}
+
+impl FetchSummaryReporter {
+    /// Returns the counts reported so far with their totals.
+    pub fn summary(&self) -> FetchSummary {
+        let packages = self.packages.lock().unwrap().clone();
+        let total = packages
+            .iter()
+            .fold(FetchTotals::default(), |acc, pkg| FetchTotals {
+                existing: acc.existing + pkg.existing,
+                new: acc.new + pkg.new,
+                failed: acc.failed + pkg.failed,
+            });
+        FetchSummary { packages, total }
+    }
+
+    fn update(&self, package_id: &str, f: impl FnOnce(&mut PackageFetchSummary)) {
+        let mut packages = self.packages.lock().unwrap();
+        let index = match packages.iter().position(|p| p.package_id == package_id) {
+            Some(index) => index,
+            None => {
+                packages.push(PackageFetchSummary {
+                    package_id: package_id.to_string(),
+                    ..Default::default()
+                });
+                packages.len() - 1
+            }
+        };
+        f(&mut packages[index]);
+    }
+}
+
+impl FetchProgressReporter for FetchSummaryReporter {
+    fn on_fetching_releases(&self, _package_id: &str) {}
+
+    fn on_downloading(&self, _package_id: &str, _version_count: usize) {}
+
+    fn on_done(&self, package_id: &str, existing: usize, new: usize) {
+        self.update(package_id, |pkg| {
+            pkg.existing = existing;
+            pkg.new = new;
+        });
+    }
+
+    fn on_failed(&self, package_id: &str, failed: usize) {
+        self.update(package_id, |pkg| pkg.failed = failed);
+    }
+
+    fn on_retrying(&self, _package_id: &str, _attempt: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_done_and_failed_events_in_report_order() {
+        let reporter = FetchSummaryReporter::default();
+        reporter.on_failed("com.example.b", 2);
+        reporter.on_done("com.example.b", 1, 0);
+        reporter.on_done("com.example.a", 3, 4);
+
+        let json = serde_json::to_value(reporter.summary()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "packages": [
+                    { "id": "com.example.b", "existing": 1, "new": 0, "failed": 2 },
+                    { "id": "com.example.a", "existing": 3, "new": 4, "failed": 0 }
+                ],
+                "total": { "existing": 4, "new": 4, "failed": 2 }
+            })
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::config::Manifest;
+use crate::domain::Repository;
+use crate::error::{Error, Result};
+use crate::infra::{read_to_string_if_exists, write_atomic_file};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Returns the path of the fetched-repositories state next to the given config file.
+pub fn fetched_repositories_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("fetched")
+}
+
+/// Repositories contacted by earlier successful fetches, so that
+/// `--only-new-repos` can skip them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FetchedRepositories {
+    repositories: BTreeSet<String>,
+}
+
+impl FetchedRepositories {
+    /// Loads the state, returning an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string_if_exists(path).map_err(|e| Error::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let Some(content) = content else {
+            return Ok(Self::default());
+        };
+
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse {
+            source: path.display().to_string(),
+            error: e,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)?;
+        write_atomic_file(path, &content).map_err(|e| Error::FileWrite {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    pub fn contains(&self, repository: &Repository) -> bool {
+        self.repositories.contains(&repository.to_string())
+    }
+
+    /// Records the repositories of the given manifest packages as fetched.
+    pub fn record(&mut self, manifest: &Manifest, package_ids: &HashSet<String>) {
+        self.repositories.extend(
+            manifest
+                .packages
+                .iter()
+                .filter(|p| package_ids.contains(&p.id))
+                .map(|p| p.repository.to_string()),
+        );
+    }
+
+    /// Returns the IDs of packages whose repository has not been fetched yet.
+    pub fn new_packages(&self, manifest: &Manifest) -> HashSet<String> {
+        manifest
+            .packages
+            .iter()
+            .filter(|p| !self.contains(&p.repository))
+            .map(|p| p.id.clone())
+            .collect()
+    }
+}
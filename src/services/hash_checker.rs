@@ -1,7 +1,7 @@
 use super::manifest_lock_tx::recover_manifest_lock_transaction;
-use crate::config::Manifest;
+use crate::config::{ConfigFormat, Manifest};
 use crate::error::{Error, Result};
-use crate::lock::{Lockfile, compute_manifest_hash_from_manifest};
+use crate::lock::{Lockfile, compute_manifest_hash_with};
 use std::path::Path;
 
 pub struct HashCheckResult {
@@ -12,15 +12,18 @@ pub struct HashCheckResult {
 
 /// Loads manifest and lockfile, checking for hash mismatch.
 /// Returns error if manifest was modified outside of voyager.
-pub fn check_and_load(config_path: &Path, lock_path: &Path) -> Result<HashCheckResult> {
+pub fn check_and_load(
+    config_path: &Path,
+    lock_path: &Path,
+    format: ConfigFormat,
+) -> Result<HashCheckResult> {
     recover_manifest_lock_transaction(config_path, lock_path)?;
-    let manifest = Manifest::load(config_path)?;
-    let current_hash = compute_manifest_hash_from_manifest(&manifest, config_path)?;
+    let manifest = Manifest::load_as(config_path, format)?;
     let lockfile = Lockfile::load_or_default(lock_path)?;
+    let current_hash =
+        compute_manifest_hash_with(&manifest, config_path, lockfile.manifest_hash_algorithm())?;
 
-    if let Some(stored_hash) = &lockfile.manifest_hash
-        && stored_hash != &current_hash
-    {
+    if lockfile.manifest_hash.is_some() && !lockfile.manifest_hash_matches(&current_hash) {
         return Err(Error::ManifestHashMismatch);
     }
 
@@ -0,0 +1,214 @@
+use crate::output::{VersionOutput, VpmOutput};
+use indexmap::IndexMap;
+use serde::Serialize;
+
+/// How a package differs between two indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageChange {
+    /// Only present in the new index
+    Added,
+    /// Only present in the old index
+    Removed,
+    /// Present in both with different or changed versions
+    Modified,
+}
+
+/// Version differences of a single package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageDiff {
+    pub id: String,
+    pub change: PackageChange,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Versions present in both indexes whose entries differ.
+    pub changed: Vec<String>,
+}
+
+/// Package and version differences between two indexes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexDiff {
+    pub packages: Vec<PackageDiff>,
+}
+
+impl IndexDiff {
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+}
+
+/// Compares two indexes by package ID and version key.
+///
+/// Packages are reported in the order of `new`, followed by packages
+/// that only exist in `old`.
+pub fn diff_indexes(old: &VpmOutput, new: &VpmOutput) -> IndexDiff {
+    let mut packages = Vec::new();
+
+    for (id, new_package) in &new.packages {
+        let (change, added, removed, changed) = match old.packages.get(id) {
+            Some(old_package) => (
+                PackageChange::Modified,
+                keys_missing_from(&new_package.versions, &old_package.versions),
+                keys_missing_from(&old_package.versions, &new_package.versions),
+                changed_versions(&old_package.versions, &new_package.versions),
+            ),
+            None => (
+                PackageChange::Added,
+                new_package.versions.keys().cloned().collect(),
+                Vec::new(),
+                Vec::new(),
+            ),
+        };
+
+        if change == PackageChange::Added
+            || !added.is_empty()
+            || !removed.is_empty()
+            || !changed.is_empty()
+        {
+            packages.push(PackageDiff {
+                id: id.clone(),
+                change,
+                added,
+                removed,
+                changed,
+            });
+        }
+    }
+
+    for (id, old_package) in &old.packages {
+        if !new.packages.contains_key(id) {
+            packages.push(PackageDiff {
+                id: id.clone(),
+                change: PackageChange::Removed,
+                added: Vec::new(),
+                removed: old_package.versions.keys().cloned().collect(),
+                changed: Vec::new(),
+            });
+        }
+    }
+
+    IndexDiff { packages }
+}
+
+fn keys_missing_from<V>(from: &IndexMap<String, V>, other: &IndexMap<String, V>) -> Vec<String> {
+    from.keys()
+        .filter(|key| !other.contains_key(*key))
+        .cloned()
+        .collect()
+}
+
+/// Versions of `new` whose entry differs from the same version in `old`,
+/// compared by their serialized JSON.
+fn changed_versions(
+    old: &IndexMap<String, VersionOutput>,
+    new: &IndexMap<String, VersionOutput>,
+) -> Vec<String> {
+    new.iter()
+        .filter(|(version, entry)| {
+            old.get(*version).is_some_and(|old_entry| {
+                serde_json::to_value(old_entry).ok() != serde_json::to_value(entry).ok()
+            })
+        })
+        .map(|(version, _)| version.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(packages: &[(&str, &[&str])]) -> VpmOutput {
+        let packages: serde_json::Map<String, serde_json::Value> = packages
+            .iter()
+            .map(|(id, versions)| {
+                let versions: serde_json::Map<String, serde_json::Value> = versions
+                    .iter()
+                    .map(|version| {
+                        (
+                            version.to_string(),
+                            serde_json::json!({
+                                "name": id,
+                                "version": version,
+                                "displayName": id,
+                                "description": "desc",
+                                "url": format!("https://example.com/{id}-{version}.zip"),
+                                "author": { "name": "Author" }
+                            }),
+                        )
+                    })
+                    .collect();
+                (id.to_string(), serde_json::json!({ "versions": versions }))
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "name": "Example",
+            "id": "com.example.vpm",
+            "url": "https://example.com/index.json",
+            "author": "Author",
+            "packages": packages
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_added_version() {
+        let old = index(&[("com.example.pkg", &["1.0.0"])]);
+        let new = index(&[("com.example.pkg", &["1.1.0", "1.0.0"])]);
+
+        let diff = diff_indexes(&old, &new);
+
+        assert_eq!(
+            diff.packages,
+            vec![PackageDiff {
+                id: "com.example.pkg".to_string(),
+                change: PackageChange::Modified,
+                added: vec!["1.1.0".to_string()],
+                removed: Vec::new(),
+                changed: Vec::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_packages() {
+        let old = index(&[("com.example.old", &["1.0.0"])]);
+        let new = index(&[("com.example.new", &["2.0.0"])]);
+
+        let diff = diff_indexes(&old, &new);
+
+        assert_eq!(diff.packages.len(), 2);
+        assert_eq!(diff.packages[0].id, "com.example.new");
+        assert_eq!(diff.packages[0].change, PackageChange::Added);
+        assert_eq!(diff.packages[0].added, vec!["2.0.0".to_string()]);
+        assert_eq!(diff.packages[1].id, "com.example.old");
+        assert_eq!(diff.packages[1].change, PackageChange::Removed);
+        assert_eq!(diff.packages[1].removed, vec!["1.0.0".to_string()]);
+    }
+
+    #[test]
+    fn reports_changed_version_entries() {
+        let old = index(&[("com.example.pkg", &["1.0.0", "1.1.0"])]);
+        let mut new = index(&[("com.example.pkg", &["1.0.0", "1.1.0"])]);
+        new.packages
+            .get_mut("com.example.pkg")
+            .and_then(|pkg| pkg.versions.get_mut("1.1.0"))
+            .unwrap()
+            .url = "https://mirror.example.com/pkg-1.1.0.zip".to_string();
+
+        let diff = diff_indexes(&old, &new);
+
+        assert_eq!(diff.packages.len(), 1);
+        assert_eq!(diff.packages[0].change, PackageChange::Modified);
+        assert!(diff.packages[0].added.is_empty());
+        assert_eq!(diff.packages[0].changed, vec!["1.1.0".to_string()]);
+    }
+
+    #[test]
+    fn identical_indexes_have_no_diff() {
+        let old = index(&[("com.example.pkg", &["1.0.0"])]);
+        let new = index(&[("com.example.pkg", &["1.0.0"])]);
+
+        assert!(diff_indexes(&old, &new).is_empty());
+    }
+}
@@ -1,31 +1,106 @@
 use crate::config::Manifest;
 use crate::error::{Error, Result};
-use crate::lock::{Lockfile, PackageManifest};
+use crate::lock::{Lockfile, PackageManifest, now_utc};
 use crate::output::{Author, VersionOutput, VpmOutput};
 use indexmap::IndexMap;
+use semver::Version;
+use std::cmp::Ordering;
 use tracing::info;
 
+/// Order of the `packages` map in the generated index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PackageOrder {
+    /// Keep the order packages are declared in the manifest.
+    #[default]
+    Manifest,
+    /// Sort packages alphabetically by ID.
+    Alphabetical,
+}
+
+/// Order of the `versions` map of each package in the generated index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionOrder {
+    /// Keep the lockfile order (newest fetched first).
+    #[default]
+    Lock,
+    /// Oldest version first, in SemVer order.
+    Asc,
+    /// Newest version first, in SemVer order.
+    Desc,
+}
+
+/// Default size above which a generated index is reported as too large (100 MiB).
+pub const DEFAULT_MAX_INDEX_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Options controlling the shape of the generated index.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    pub package_order: PackageOrder,
+    pub version_order: VersionOrder,
+    /// Minimum number of versions every package must have.
+    pub min_versions: usize,
+    /// Only include packages carrying this tag.
+    pub tag: Option<String>,
+    /// Drop packages that have no versions from the output.
+    pub omit_empty_packages: bool,
+    /// Fail when the newest versions of two packages share a display name.
+    pub reject_duplicate_display_names: bool,
+    /// Leave out package.json fields that are not part of the VPM schema.
+    pub drop_extra: bool,
+    /// Add build provenance under the top-level [`METADATA_KEY`].
+    pub emit_metadata: bool,
+    /// Leave author emails out of the output (the lockfile keeps them).
+    pub redact_email: bool,
+}
+
+/// Top-level index key holding the metadata written by `--emit-metadata`.
+pub const METADATA_KEY: &str = "voyager";
+
 /// Generates VPM index output from a manifest and lockfile.
 ///
 /// This function transforms the locked package data into the VPM index format
 /// that can be published for VCC (VRChat Creator Companion) to consume.
 pub fn generate_from_lockfile(manifest: &Manifest, lockfile: &Lockfile) -> Result<VpmOutput> {
+    generate_from_lockfile_with(manifest, lockfile, &GenerateOptions::default())
+}
+
+/// Generates VPM index output, applying the given shaping options.
+pub fn generate_from_lockfile_with(
+    manifest: &Manifest,
+    lockfile: &Lockfile,
+    options: &GenerateOptions,
+) -> Result<VpmOutput> {
     let mut output = VpmOutput::from_manifest(manifest);
+    let mut below_minimum = Vec::new();
 
     for package in &manifest.packages {
+        if let Some(tag) = &options.tag
+            && !package.has_tag(tag)
+        {
+            output.packages.shift_remove(&package.id);
+            continue;
+        }
+
         let locked_pkg = lockfile.get_package(&package.id).ok_or_else(|| {
             Error::ConfigValidation(format!(
                 "Lock file missing package '{}'. Run 'voy fetch' first.",
                 package.id
             ))
         })?;
+        if locked_pkg.versions.len() < options.min_versions {
+            below_minimum.push(format!("{} ({})", package.id, locked_pkg.versions.len()));
+        }
         let mut versions = IndexMap::new();
 
         for locked_version in &locked_pkg.versions {
-            versions.insert(
-                locked_version.version.clone(),
-                to_output_version(&locked_version.manifest),
-            );
+            let mut version = to_output_version(&locked_version.manifest);
+            if options.drop_extra {
+                version.extra.clear();
+            }
+            if options.redact_email {
+                version.author.email.clear();
+            }
+            versions.insert(locked_version.version.clone(), version);
         }
 
         // VpmOutput::from_manifest() already creates entries for all packages,
@@ -37,6 +112,54 @@ pub fn generate_from_lockfile(manifest: &Manifest, lockfile: &Lockfile) -> Resul
             .versions = versions;
     }
 
+    if !below_minimum.is_empty() {
+        return Err(Error::ConfigValidation(format!(
+            "Packages with fewer than {} version(s): {}",
+            options.min_versions,
+            below_minimum.join(", ")
+        )));
+    }
+
+    if options.omit_empty_packages {
+        output
+            .packages
+            .retain(|_, package| !package.versions.is_empty());
+    }
+
+    if options.reject_duplicate_display_names
+        && let Some(message) = duplicate_display_name_warning(&output)
+    {
+        return Err(Error::ConfigValidation(message));
+    }
+
+    if options.emit_metadata {
+        output.extra.insert(
+            METADATA_KEY.to_string(),
+            serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "generatedAt": now_utc(),
+                "manifestHash": lockfile.manifest_hash,
+            }),
+        );
+    }
+
+    if options.package_order == PackageOrder::Alphabetical {
+        output.packages.sort_keys();
+    }
+
+    if options.version_order != VersionOrder::Lock {
+        let descending = options.version_order == VersionOrder::Desc;
+        for package in output.packages.values_mut() {
+            package.versions.sort_by(|a, _, b, _| {
+                if descending {
+                    compare_versions(b, a)
+                } else {
+                    compare_versions(a, b)
+                }
+            });
+        }
+    }
+
     info!(
         packages = output.packages.len(),
         "Index generation completed"
@@ -45,6 +168,114 @@ pub fn generate_from_lockfile(manifest: &Manifest, lockfile: &Lockfile) -> Resul
     Ok(output)
 }
 
+/// Groups packages by the display name of their newest version, returning
+/// only names used by more than one package.
+fn duplicate_display_names(output: &VpmOutput) -> Vec<(String, Vec<String>)> {
+    let mut by_name: IndexMap<&str, Vec<String>> = IndexMap::new();
+    for (package_id, package) in &output.packages {
+        let newest = package
+            .versions
+            .iter()
+            .max_by(|(a, _), (b, _)| compare_versions(a, b))
+            .map(|(_, version)| version);
+        if let Some(version) = newest {
+            by_name
+                .entry(version.display_name.as_str())
+                .or_default()
+                .push(package_id.clone());
+        }
+    }
+
+    by_name
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(name, ids)| (name.to_string(), ids))
+        .collect()
+}
+
+/// Serializes the index as pretty JSON, or as compact JSON with `minify`.
+/// With `sort_keys`, every object's keys (package IDs, versions, `extra`
+/// fields and nested values) are sorted so the output does not depend on
+/// input ordering.
+pub fn serialize_index(output: &VpmOutput, sort_keys: bool, minify: bool) -> Result<String> {
+    if !sort_keys {
+        return to_json(output, minify);
+    }
+
+    let value = serde_json::to_value(output).map_err(Error::JsonSerialize)?;
+    to_json(&sorted_keys(value), minify)
+}
+
+fn to_json<T: serde::Serialize>(value: &T, minify: bool) -> Result<String> {
+    let json = if minify {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    };
+    json.map_err(Error::JsonSerialize)
+}
+
+fn sorted_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted_keys(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sorted_keys).collect())
+        }
+        other => other,
+    }
+}
+
+/// Returns a message naming every display name that the newest versions of
+/// more than one package share, since VCC cannot tell those packages apart.
+pub fn duplicate_display_name_warning(output: &VpmOutput) -> Option<String> {
+    let duplicates = duplicate_display_names(output);
+    if duplicates.is_empty() {
+        return None;
+    }
+
+    let details: Vec<String> = duplicates
+        .iter()
+        .map(|(name, ids)| format!("'{}' ({})", name, ids.join(", ")))
+        .collect();
+    Some(format!(
+        "Packages share a display name: {}",
+        details.join("; ")
+    ))
+}
+
+/// Returns a message if the serialized index is larger than `max_size` bytes.
+pub fn index_size_warning(json: &str, max_size: u64) -> Option<String> {
+    let size = json.len() as u64;
+    (size > max_size).then(|| {
+        format!(
+            "Generated index is {} bytes, exceeding the limit of {} bytes",
+            size, max_size
+        )
+    })
+}
+
+/// Compares version strings by SemVer precedence.
+///
+/// Versions that fail to parse sort after valid ones, falling back to a
+/// lexical comparison among themselves.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
 fn to_output_version(manifest: &PackageManifest) -> VersionOutput {
     VersionOutput {
         name: manifest.name.clone(),
@@ -90,6 +321,9 @@ mod tests {
 
     fn create_manifest() -> Manifest {
         Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
             vpm: Vpm {
                 id: "com.example.vpm".to_string(),
                 name: "Example VPM".to_string(),
@@ -100,12 +334,24 @@ mod tests {
                 Package {
                     id: "com.example.pkg1".to_string(),
                     repository: Repository::parse("owner/repo1").unwrap(),
+                    suffix: None,
+                    tags: Vec::new(),
+                    versions: Vec::new(),
+                    version_range: None,
+                    asset_name: None,
                 },
                 Package {
                     id: "com.example.pkg2".to_string(),
                     repository: Repository::parse("owner/repo2").unwrap(),
+                    suffix: None,
+                    tags: Vec::new(),
+                    versions: Vec::new(),
+                    version_range: None,
+                    asset_name: None,
                 },
             ],
+            included: Default::default(),
+            format: Default::default(),
         }
     }
 
@@ -155,6 +401,7 @@ mod tests {
                 r#"{"name": "pkg1"}"#,
                 create_version_output("pkg1", "1.0.0"),
             )],
+            retained: false,
         };
         lockfile.packages.push(pkg1);
 
@@ -176,6 +423,7 @@ mod tests {
                 r#"{"name": "pkg2"}"#,
                 create_version_output("pkg2", "2.0.0"),
             )],
+            retained: false,
         };
         let pkg1 = LockedPackage {
             id: "com.example.pkg1".to_string(),
@@ -186,6 +434,7 @@ mod tests {
                 r#"{"name": "pkg1"}"#,
                 create_version_output("pkg1", "1.0.0"),
             )],
+            retained: false,
         };
         lockfile.packages.push(pkg2);
         lockfile.packages.push(pkg1);
@@ -204,6 +453,9 @@ mod tests {
     #[test]
     fn generate_includes_all_versions() {
         let manifest = Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
             vpm: Vpm {
                 id: "com.example.vpm".to_string(),
                 name: "Example VPM".to_string(),
@@ -213,7 +465,14 @@ mod tests {
             packages: vec![Package {
                 id: "com.example.pkg".to_string(),
                 repository: repo("owner/repo"),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
             }],
+            included: Default::default(),
+            format: Default::default(),
         };
 
         let mut lockfile = Lockfile::new();
@@ -234,6 +493,7 @@ mod tests {
                     create_version_output("pkg", "2.0.0"),
                 ),
             ],
+            retained: false,
         };
         lockfile.packages.push(pkg);
 
@@ -244,9 +504,197 @@ mod tests {
         assert!(pkg_output.versions.contains_key("2.0.0"));
     }
 
+    #[test]
+    fn generate_sorts_packages_alphabetically() {
+        let mut manifest = create_manifest();
+        manifest.packages.reverse();
+
+        let mut lockfile = Lockfile::new();
+        for (id, repository) in [
+            ("com.example.pkg2", "owner/repo2"),
+            ("com.example.pkg1", "owner/repo1"),
+        ] {
+            lockfile.packages.push(LockedPackage {
+                id: id.to_string(),
+                repository: repo(repository),
+                versions: vec![],
+                retained: false,
+            });
+        }
+
+        let options = GenerateOptions {
+            package_order: PackageOrder::Alphabetical,
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        let keys: Vec<_> = output.packages.keys().cloned().collect();
+        assert_eq!(
+            keys,
+            vec![
+                "com.example.pkg1".to_string(),
+                "com.example.pkg2".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_orders_version_keys() {
+        let manifest = Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
+            vpm: Vpm {
+                id: "com.example.vpm".to_string(),
+                name: "Example VPM".to_string(),
+                author: "Example Author".to_string(),
+                url: "https://example.com/vpm.json".to_string(),
+            },
+            packages: vec![Package {
+                id: "com.example.pkg".to_string(),
+                repository: repo("owner/repo"),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
+            }],
+            included: Default::default(),
+            format: Default::default(),
+        };
+
+        let mut lockfile = Lockfile::new();
+        let versions = ["10.0.0", "2.0.0", "1.0.0-beta.1", "1.0.0"]
+            .into_iter()
+            .map(|version| {
+                LockedVersion::new(
+                    format!("v{version}"),
+                    format!("https://example.com/v{version}.zip"),
+                    r#"{"name": "pkg"}"#,
+                    create_version_output("pkg", version),
+                )
+            })
+            .collect();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg".to_string(),
+            repository: repo("owner/repo"),
+            versions,
+            retained: false,
+        });
+
+        let version_keys = |version_order| {
+            let options = GenerateOptions {
+                version_order,
+                ..Default::default()
+            };
+            let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+            output.packages["com.example.pkg"]
+                .versions
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            version_keys(VersionOrder::Lock),
+            vec!["10.0.0", "2.0.0", "1.0.0-beta.1", "1.0.0"]
+        );
+        assert_eq!(
+            version_keys(VersionOrder::Asc),
+            vec!["1.0.0-beta.1", "1.0.0", "2.0.0", "10.0.0"]
+        );
+        assert_eq!(
+            version_keys(VersionOrder::Desc),
+            vec!["10.0.0", "2.0.0", "1.0.0", "1.0.0-beta.1"]
+        );
+    }
+
+    #[test]
+    fn generate_enforces_min_versions() {
+        let manifest = Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
+            vpm: Vpm {
+                id: "com.example.vpm".to_string(),
+                name: "Example VPM".to_string(),
+                author: "Example Author".to_string(),
+                url: "https://example.com/vpm.json".to_string(),
+            },
+            packages: vec![Package {
+                id: "com.example.pkg".to_string(),
+                repository: repo("owner/repo"),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
+            }],
+            included: Default::default(),
+            format: Default::default(),
+        };
+
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg".to_string(),
+            repository: repo("owner/repo"),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/v1.zip".to_string(),
+                r#"{"name": "pkg"}"#,
+                create_version_output("pkg", "1.0.0"),
+            )],
+            retained: false,
+        });
+
+        let strict = GenerateOptions {
+            min_versions: 2,
+            ..Default::default()
+        };
+        let err = generate_from_lockfile_with(&manifest, &lockfile, &strict).unwrap_err();
+        assert!(matches!(err, Error::ConfigValidation(_)));
+        assert!(err.to_string().contains("com.example.pkg (1)"));
+
+        let lenient = GenerateOptions {
+            min_versions: 1,
+            ..Default::default()
+        };
+        assert!(generate_from_lockfile_with(&manifest, &lockfile, &lenient).is_ok());
+    }
+
+    #[test]
+    fn generate_includes_only_tagged_packages() {
+        let mut manifest = create_manifest();
+        manifest.packages[0].tags = vec!["core".to_string()];
+        manifest.packages[1].tags = vec!["experimental".to_string()];
+
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/pkg1/package.json".to_string(),
+                r#"{"name": "pkg1"}"#,
+                create_version_output("pkg1", "1.0.0"),
+            )],
+            retained: false,
+        });
+
+        let options = GenerateOptions {
+            tag: Some("core".to_string()),
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        let keys: Vec<_> = output.packages.keys().cloned().collect();
+        assert_eq!(keys, vec!["com.example.pkg1".to_string()]);
+    }
+
     #[test]
     fn generate_preserves_vpm_extension_fields() {
         let manifest = Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
             vpm: Vpm {
                 id: "com.example.vpm".to_string(),
                 name: "Example VPM".to_string(),
@@ -256,7 +704,14 @@ mod tests {
             packages: vec![Package {
                 id: "com.example.pkg".to_string(),
                 repository: repo("owner/repo"),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
             }],
+            included: Default::default(),
+            format: Default::default(),
         };
 
         let mut lockfile = Lockfile::new();
@@ -287,6 +742,7 @@ mod tests {
                 r#"{"name":"pkg"}"#,
                 pkg_manifest,
             )],
+            retained: false,
         };
         lockfile.packages.push(pkg);
 
@@ -314,4 +770,275 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn serialize_index_with_sort_keys_ignores_extra_order() {
+        let mut manifest = create_manifest();
+        manifest.packages.truncate(1);
+        let index_with_extra = |extra: &[(&str, serde_json::Value)]| {
+            let mut version = create_version_output("com.example.pkg1", "1.0.0");
+            version.extra = extra
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect();
+            let mut lockfile = Lockfile::new();
+            lockfile.packages.push(LockedPackage {
+                id: "com.example.pkg1".to_string(),
+                repository: repo("owner/repo1"),
+                versions: vec![LockedVersion::new(
+                    "v1.0.0".to_string(),
+                    "https://example.com/v1.0.0.zip".to_string(),
+                    "{}",
+                    version,
+                )],
+                retained: false,
+            });
+            generate_from_lockfile(&manifest, &lockfile).unwrap()
+        };
+
+        let first = index_with_extra(&[
+            ("zeta", serde_json::json!({ "b": 1, "a": 2 })),
+            ("alpha", serde_json::json!(true)),
+        ]);
+        let second = index_with_extra(&[
+            ("alpha", serde_json::json!(true)),
+            ("zeta", serde_json::json!({ "a": 2, "b": 1 })),
+        ]);
+
+        let sorted = serialize_index(&first, true, false).unwrap();
+        assert_eq!(sorted, serialize_index(&second, true, false).unwrap());
+        assert!(sorted.find("\"alpha\"").unwrap() < sorted.find("\"zeta\"").unwrap());
+        assert_ne!(
+            serialize_index(&first, false, false).unwrap(),
+            serialize_index(&second, false, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn index_size_warning_fires_only_above_threshold() {
+        let manifest = create_manifest();
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: (0..200)
+                .map(|minor| {
+                    let version = format!("1.{minor}.0");
+                    LockedVersion::new(
+                        format!("v{version}"),
+                        format!("https://example.com/v{version}.zip"),
+                        "{}",
+                        create_version_output("com.example.pkg1", &version),
+                    )
+                })
+                .collect(),
+            retained: false,
+        });
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg2".to_string(),
+            repository: repo("owner/repo2"),
+            versions: Vec::new(),
+            retained: false,
+        });
+
+        let output = generate_from_lockfile(&manifest, &lockfile).unwrap();
+        let json = serde_json::to_string_pretty(&output).unwrap();
+        let size = json.len() as u64;
+
+        assert!(size > 10_000);
+        let message = index_size_warning(&json, size - 1).unwrap();
+        assert!(message.contains(&size.to_string()));
+        assert!(index_size_warning(&json, size).is_none());
+        assert!(index_size_warning(&json, DEFAULT_MAX_INDEX_SIZE).is_none());
+    }
+
+    #[test]
+    fn generate_drops_extra_fields_only_when_requested() {
+        let mut manifest = create_manifest();
+        manifest.packages.truncate(1);
+        let mut version = create_version_output("com.example.pkg1", "1.0.0");
+        version
+            .extra
+            .insert("customField".to_string(), serde_json::json!("value"));
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/v1.zip".to_string(),
+                "{}",
+                version,
+            )],
+            retained: false,
+        });
+
+        let output = generate_from_lockfile(&manifest, &lockfile).unwrap();
+        let json = serialize_index(&output, false, false).unwrap();
+        assert!(json.contains("customField"));
+
+        let options = GenerateOptions {
+            drop_extra: true,
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        let json = serialize_index(&output, false, false).unwrap();
+        assert!(!json.contains("customField"));
+        assert!(json.contains("\"displayName\""));
+    }
+
+    #[test]
+    fn generate_redacts_author_email_only_when_requested() {
+        let mut manifest = create_manifest();
+        manifest.packages.truncate(1);
+        let mut version = create_version_output("com.example.pkg1", "1.0.0");
+        version.author.email = "author@example.com".to_string();
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/v1.zip".to_string(),
+                "{}",
+                version,
+            )],
+            retained: false,
+        });
+
+        let output = generate_from_lockfile(&manifest, &lockfile).unwrap();
+        let json = serialize_index(&output, false, false).unwrap();
+        assert!(json.contains("author@example.com"));
+
+        let options = GenerateOptions {
+            redact_email: true,
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        let json = serialize_index(&output, false, false).unwrap();
+        assert!(!json.contains("author@example.com"));
+        assert!(!json.contains("\"email\""));
+        assert_eq!(
+            lockfile.packages[0].versions[0].manifest.author.email,
+            "author@example.com"
+        );
+    }
+
+    #[test]
+    fn generate_emits_metadata_only_when_requested() {
+        let mut manifest = create_manifest();
+        manifest.packages.truncate(1);
+        let mut lockfile = Lockfile::new();
+        lockfile.manifest_hash = Some("sha256:abc".to_string());
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: Vec::new(),
+            retained: false,
+        });
+
+        let output = generate_from_lockfile(&manifest, &lockfile).unwrap();
+        assert!(output.extra.is_empty());
+
+        let options = GenerateOptions {
+            emit_metadata: true,
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&serialize_index(&output, false, false).unwrap()).unwrap();
+        let metadata = &json[METADATA_KEY];
+        assert_eq!(metadata["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata["manifestHash"], "sha256:abc");
+        assert!(metadata["generatedAt"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn generate_omits_empty_packages_only_when_requested() {
+        let manifest = create_manifest();
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg1".to_string(),
+            repository: repo("owner/repo1"),
+            versions: vec![LockedVersion::new(
+                "v1.0.0".to_string(),
+                "https://example.com/v1.zip".to_string(),
+                "{}",
+                create_version_output("com.example.pkg1", "1.0.0"),
+            )],
+            retained: false,
+        });
+        lockfile.packages.push(LockedPackage {
+            id: "com.example.pkg2".to_string(),
+            repository: repo("owner/repo2"),
+            versions: Vec::new(),
+            retained: false,
+        });
+
+        let output = generate_from_lockfile(&manifest, &lockfile).unwrap();
+        assert!(output.packages.contains_key("com.example.pkg2"));
+
+        let options = GenerateOptions {
+            omit_empty_packages: true,
+            ..Default::default()
+        };
+        let output = generate_from_lockfile_with(&manifest, &lockfile, &options).unwrap();
+        assert!(output.packages.contains_key("com.example.pkg1"));
+        assert!(!output.packages.contains_key("com.example.pkg2"));
+    }
+
+    #[test]
+    fn duplicate_display_names_warn_or_fail_when_requested() {
+        let manifest = create_manifest();
+        let display_named = |id: &str, version: &str, display_name: &str| {
+            let mut manifest = create_version_output(id, version);
+            manifest.display_name = display_name.to_string();
+            LockedVersion::new(
+                format!("v{version}"),
+                format!("https://example.com/{id}-{version}.zip"),
+                "{}",
+                manifest,
+            )
+        };
+        let lockfile_with = |pkg2_name: &str| {
+            let mut lockfile = Lockfile::new();
+            lockfile.packages.push(LockedPackage {
+                id: "com.example.pkg1".to_string(),
+                repository: repo("owner/repo1"),
+                versions: vec![display_named("com.example.pkg1", "1.0.0", "Shared")],
+                retained: false,
+            });
+            lockfile.packages.push(LockedPackage {
+                id: "com.example.pkg2".to_string(),
+                repository: repo("owner/repo2"),
+                versions: vec![
+                    display_named("com.example.pkg2", "2.0.0", pkg2_name),
+                    display_named("com.example.pkg2", "1.0.0", "Shared"),
+                ],
+                retained: false,
+            });
+            lockfile
+        };
+        let options = GenerateOptions {
+            reject_duplicate_display_names: true,
+            ..Default::default()
+        };
+
+        let err =
+            generate_from_lockfile_with(&manifest, &lockfile_with("Shared"), &options).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'Shared' (com.example.pkg1, com.example.pkg2)")
+        );
+
+        let distinct =
+            generate_from_lockfile_with(&manifest, &lockfile_with("Distinct"), &options).unwrap();
+        assert_eq!(duplicate_display_name_warning(&distinct), None);
+
+        let shared = generate_from_lockfile(&manifest, &lockfile_with("Shared")).unwrap();
+        assert_eq!(
+            duplicate_display_name_warning(&shared).as_deref(),
+            Some("Packages share a display name: 'Shared' (com.example.pkg1, com.example.pkg2)")
+        );
+    }
 }
@@ -0,0 +1,203 @@
+use crate::config::{Manifest, Package, Vpm};
+use crate::domain::{GITHUB_HOST, Repository};
+use crate::error::{Error, Result};
+use crate::lock::{LockedPackage, LockedVersion, Lockfile, PackageManifest};
+use crate::output::{VersionOutput, VpmOutput};
+use reqwest::Url;
+
+/// Manifest and lockfile rebuilt from a published index.
+#[derive(Debug)]
+pub struct MigratedIndex {
+    pub manifest: Manifest,
+    pub lockfile: Lockfile,
+    /// Packages left out, with the reason.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Rebuilds a manifest and lockfile from `index`.
+///
+/// The repository and tag of each version are guessed from GitHub release
+/// download URLs (`https://github.com/{owner}/{repo}/releases/download/{tag}/...`).
+/// Packages whose repository cannot be guessed, or whose id lies outside the
+/// index id, are skipped. Versions are locked as unverified, since their
+/// package.json assets are never downloaded; `fetch` locks them again.
+pub fn migrate_index(index: &VpmOutput) -> Result<MigratedIndex> {
+    let mut manifest = Manifest::new(Vpm {
+        id: index.id.clone(),
+        name: index.name.clone(),
+        author: index.author.clone(),
+        url: index.url.clone(),
+    });
+    let mut lockfile = Lockfile::new();
+    let mut skipped = Vec::new();
+    let prefix = format!("{}.", index.id);
+
+    for (package_id, package) in &index.packages {
+        if !package_id.starts_with(&prefix) {
+            skipped.push((
+                package_id.clone(),
+                format!("id does not start with '{}'", prefix),
+            ));
+            continue;
+        }
+        let Some(repository) = package
+            .versions
+            .values()
+            .find_map(|version| release_location(&version.url).map(|(repo, _)| repo))
+        else {
+            skipped.push((
+                package_id.clone(),
+                "no GitHub release URL to take the repository from".to_string(),
+            ));
+            continue;
+        };
+
+        let mut versions = Vec::with_capacity(package.versions.len());
+        for version in package.versions.values() {
+            let tag = release_location(&version.url)
+                .filter(|(repo, _)| *repo == repository)
+                .map(|(_, tag)| tag)
+                .unwrap_or_else(|| version.version.clone());
+            versions.push(locked_version(&repository, tag, version)?);
+        }
+
+        manifest.packages.push(Package {
+            id: package_id.clone(),
+            repository: repository.clone(),
+            suffix: None,
+            tags: Vec::new(),
+            versions: Vec::new(),
+            version_range: None,
+            asset_name: None,
+        });
+        lockfile.packages.push(LockedPackage {
+            id: package_id.clone(),
+            repository,
+            versions,
+            retained: false,
+        });
+    }
+
+    Ok(MigratedIndex {
+        manifest,
+        lockfile,
+        skipped,
+    })
+}
+
+/// Locks `version` as if its package.json had been fetched from the `tag`
+/// release of `repository`, hashing the index entry in its place. The result
+/// is marked unverified because neither the URL nor the hash come from the
+/// release.
+fn locked_version(
+    repository: &Repository,
+    tag: String,
+    version: &VersionOutput,
+) -> Result<LockedVersion> {
+    let raw = serde_json::to_string_pretty(version).map_err(Error::JsonSerialize)?;
+    let manifest: PackageManifest = serde_json::from_str(&raw).map_err(|e| Error::JsonParse {
+        source: format!("{} {}", version.name, version.version),
+        error: e,
+    })?;
+    let asset_url = format!(
+        "https://{}/{}/{}/releases/download/{}/package.json",
+        GITHUB_HOST, repository.owner, repository.repo, tag
+    );
+    Ok(LockedVersion::new(tag, asset_url, &raw, manifest).unverified())
+}
+
+/// Splits a GitHub release download URL into its repository and tag.
+fn release_location(url: &str) -> Option<(Repository, String)> {
+    let url = Url::parse(url).ok()?;
+    if url.host_str()? != GITHUB_HOST {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    match segments.as_slice() {
+        [owner, repo, "releases", "download", tag, file] if !file.is_empty() => {
+            let repository = Repository::parse(&format!("{}/{}", owner, repo)).ok()?;
+            Some((repository, (*tag).to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(packages: serde_json::Value) -> VpmOutput {
+        serde_json::from_value(serde_json::json!({
+            "name": "Example",
+            "id": "com.example",
+            "url": "https://example.com/index.json",
+            "author": "Example",
+            "packages": packages,
+        }))
+        .unwrap()
+    }
+
+    fn version(name: &str, version: &str, url: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "version": version,
+            "displayName": name,
+            "description": "",
+            "author": { "name": "Example" },
+            "url": url,
+        })
+    }
+
+    #[test]
+    fn guesses_repository_and_tag_from_release_urls() {
+        let index = index(serde_json::json!({
+            "com.example.pkg": { "versions": {
+                "1.0.0": version("com.example.pkg", "1.0.0",
+                    "https://github.com/owner/repo/releases/download/v1.0.0/pkg.zip"),
+                "1.1.0": version("com.example.pkg", "1.1.0",
+                    "https://cdn.example.com/pkg-1.1.0.zip"),
+            }},
+        }));
+
+        let migrated = migrate_index(&index).unwrap();
+
+        assert!(migrated.skipped.is_empty());
+        assert_eq!(migrated.manifest.vpm.id, "com.example");
+        assert_eq!(
+            migrated.manifest.packages[0].repository.to_string(),
+            "owner/repo"
+        );
+        let versions = &migrated.lockfile.packages[0].versions;
+        assert_eq!(versions[0].tag, "v1.0.0");
+        assert_eq!(
+            versions[0].url,
+            "https://github.com/owner/repo/releases/download/v1.0.0/package.json"
+        );
+        assert_eq!(
+            versions[0].manifest.url,
+            "https://github.com/owner/repo/releases/download/v1.0.0/pkg.zip"
+        );
+        assert_eq!(versions[1].tag, "1.1.0");
+        assert!(versions.iter().all(|v| v.unverified));
+    }
+
+    #[test]
+    fn skips_packages_without_repository_or_outside_the_index_id() {
+        let index = index(serde_json::json!({
+            "com.example.pkg": { "versions": {
+                "1.0.0": version("com.example.pkg", "1.0.0", "https://cdn.example.com/pkg.zip"),
+            }},
+            "org.other.pkg": { "versions": {
+                "1.0.0": version("org.other.pkg", "1.0.0",
+                    "https://github.com/owner/repo/releases/download/v1.0.0/pkg.zip"),
+            }},
+        }));
+
+        let migrated = migrate_index(&index).unwrap();
+
+        assert!(migrated.manifest.packages.is_empty());
+        assert!(migrated.lockfile.packages.is_empty());
+        let ids: Vec<&str> = migrated.skipped.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["com.example.pkg", "org.other.pkg"]);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::domain::{Release, strip_build_metadata};
+use crate::error::Result;
+use crate::infra::{GitHubApi, RetryObserver};
+use crate::lock::{LockedVersion, Lockfile, PackageManifest, hashes_match, parse_hash};
+use futures::stream::{self, StreamExt};
+use indicatif::ProgressBar;
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+/// Re-downloads locked package.json assets and compares them with their
+/// recorded hashes and locked manifests.
+pub struct LockVerifier<G: GitHubApi> {
+    github: Arc<G>,
+    max_concurrent: usize,
+    max_retries: u32,
+}
+
+pub struct VerifyResult {
+    pub total: usize,
+    pub verified: usize,
+    pub failures: Vec<VerifyFailure>,
+}
+
+pub struct VerifyFailure {
+    pub package_id: String,
+    pub version: String,
+    pub reason: String,
+}
+
+impl<G: GitHubApi> LockVerifier<G> {
+    pub fn new(github: Arc<G>, max_concurrent: usize, max_retries: u32) -> Self {
+        Self {
+            github,
+            max_concurrent,
+            max_retries,
+        }
+    }
+
+    /// Verifies every locked version, with at most `max_concurrent` downloads
+    /// in flight. `progress` is advanced once per finished version.
+    #[instrument(skip_all, fields(package_count = lockfile.packages.len(), max_concurrent = self.max_concurrent))]
+    pub async fn verify(
+        &self,
+        lockfile: &Lockfile,
+        progress: Option<&ProgressBar>,
+        retry_observer: Option<&dyn RetryObserver>,
+    ) -> Result<VerifyResult> {
+        let jobs: Vec<_> = lockfile
+            .packages
+            .iter()
+            .flat_map(|package| {
+                package
+                    .versions
+                    .iter()
+                    .map(move |version| (package.id.as_str(), version))
+            })
+            .collect();
+        let total = jobs.len();
+
+        info!(version_count = total, "Re-downloading locked versions");
+
+        let mut failures: Vec<VerifyFailure> = stream::iter(jobs)
+            .map(|(package_id, locked)| async move {
+                if locked.unverified {
+                    return Some(VerifyFailure {
+                        package_id: package_id.to_string(),
+                        version: locked.version.clone(),
+                        reason: "imported by migrate and never fetched; run `voy fetch` to lock it"
+                            .to_string(),
+                    });
+                }
+                if let Some(reason) = locked.raw_manifest.as_deref().and_then(|raw| {
+                    check_hash(&locked.hash, raw)
+                        .map(|reason| format!("stored raw manifest: {}", reason))
+                }) {
+                    return Some(VerifyFailure {
+                        package_id: package_id.to_string(),
+                        version: locked.version.clone(),
+                        reason,
+                    });
+                }
+                let release = Release::new(locked.tag.clone(), Some(locked.url.clone()));
+                // Each version is downloaded on its own so the outer stream
+                // bounds concurrency across packages.
+                let result = self
+                    .github
+                    .download_assets(vec![release], 1, self.max_retries, retry_observer)
+                    .await
+                    .into_iter()
+                    .next()
+                    .map(|(_, result)| result);
+
+                let reason = match result {
+                    Some(Ok(content)) => check_hash(&locked.hash, &content)
+                        .or_else(|| check_manifest(locked, &content)),
+                    Some(Err(e)) => Some(format!("download failed: {}", e)),
+                    None => Some("download returned no result".to_string()),
+                };
+                reason.map(|reason| VerifyFailure {
+                    package_id: package_id.to_string(),
+                    version: locked.version.clone(),
+                    reason,
+                })
+            })
+            .buffer_unordered(self.max_concurrent.max(1))
+            .inspect(|_| {
+                if let Some(bar) = progress {
+                    bar.inc(1);
+                }
+            })
+            .filter_map(|failure| async move { failure })
+            .collect()
+            .await;
+
+        failures.sort_by(|a, b| {
+            a.package_id
+                .cmp(&b.package_id)
+                .then_with(|| a.version.cmp(&b.version))
+        });
+        for failure in &failures {
+            warn!(
+                package_id = %failure.package_id,
+                version = %failure.version,
+                reason = %failure.reason,
+                "Locked version failed verification"
+            );
+        }
+
+        Ok(VerifyResult {
+            total,
+            verified: total - failures.len(),
+            failures,
+        })
+    }
+}
+
+/// Returns why `content` does not match `expected`, using the algorithm
+/// recorded in the expected hash.
+fn check_hash(expected: &str, content: &str) -> Option<String> {
+    let Some((algorithm, _)) = parse_hash(expected) else {
+        return Some(format!("unsupported hash '{}'", expected));
+    };
+    let actual = algorithm.digest(content);
+    if hashes_match(expected, &actual) {
+        None
+    } else {
+        Some(format!(
+            "hash mismatch (locked {}, got {})",
+            expected, actual
+        ))
+    }
+}
+
+/// Returns why the locked manifest does not match the one parsed from
+/// `content`.
+///
+/// Fields that fetch itself fills in are taken from the locked manifest: a
+/// `zipSHA256` missing upstream, `+build` metadata stripped from the
+/// version and normalized `keywords`.
+fn check_manifest(locked: &LockedVersion, content: &str) -> Option<String> {
+    let mut parsed: PackageManifest = match serde_json::from_str(content) {
+        Ok(parsed) => parsed,
+        Err(e) => return Some(format!("package.json no longer parses: {}", e)),
+    };
+    if parsed.zip_sha256.is_empty() {
+        parsed.zip_sha256 = locked.manifest.zip_sha256.clone();
+    }
+    if strip_build_metadata(&parsed.version) == locked.manifest.version {
+        parsed.version = locked.manifest.version.clone();
+    }
+    if parsed.normalized_keywords() == locked.manifest.keywords {
+        parsed.keywords = locked.manifest.keywords.clone();
+    }
+
+    let (Ok(serde_json::Value::Object(expected)), Ok(serde_json::Value::Object(actual))) = (
+        serde_json::to_value(&locked.manifest),
+        serde_json::to_value(&parsed),
+    ) else {
+        return Some("manifest could not be serialized".to_string());
+    };
+    let field = expected
+        .iter()
+        .find(|(key, value)| actual.get(*key) != Some(*value))
+        .map(|(key, _)| key)
+        .or_else(|| actual.keys().find(|key| !expected.contains_key(*key)))?;
+    Some(format!(
+        "locked manifest differs from package.json in '{}'",
+        field
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Repository;
+    use crate::lock::{LockedPackage, LockedVersion, PackageAuthor, PackageManifest};
+    use async_trait::async_trait;
+    use indexmap::IndexMap;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Serves assets by URL and records when each download starts and ends.
+    #[derive(Default)]
+    struct RecordingGitHub {
+        assets: HashMap<String, String>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl GitHubApi for RecordingGitHub {
+        async fn get_releases(
+            &self,
+            _repo: &Repository,
+            _asset_name: &str,
+        ) -> Result<Vec<Release>> {
+            Ok(Vec::new())
+        }
+
+        async fn download_assets<'a>(
+            &self,
+            releases: Vec<Release>,
+            _max_concurrent: usize,
+            _max_retries: u32,
+            _retry_observer: Option<&'a dyn RetryObserver>,
+        ) -> Vec<(Release, Result<String>)> {
+            let mut results = Vec::new();
+            for release in releases {
+                let url = release.asset_url().unwrap_or_default().to_string();
+                self.calls.lock().unwrap().push(format!("start {}", url));
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                self.calls.lock().unwrap().push(format!("end {}", url));
+                let content = self.assets.get(&url).cloned().unwrap_or_default();
+                results.push((release, Ok(content)));
+            }
+            results
+        }
+
+        async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
+            Ok(())
+        }
+
+        async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+            Err(crate::error::Error::ConfigValidation(format!(
+                "unexpected download: {url}"
+            )))
+        }
+    }
+
+    fn manifest(version: &str) -> PackageManifest {
+        PackageManifest {
+            name: "com.example.pkg".to_string(),
+            version: version.to_string(),
+            display_name: "Package".to_string(),
+            description: String::new(),
+            unity: String::new(),
+            unity_release: String::new(),
+            dependencies: IndexMap::new(),
+            keywords: vec![],
+            author: PackageAuthor {
+                name: "Author".to_string(),
+                email: String::new(),
+                url: String::new(),
+            },
+            vpm_dependencies: IndexMap::new(),
+            legacy_folders: IndexMap::new(),
+            legacy_files: IndexMap::new(),
+            legacy_packages: vec![],
+            documentation_url: String::new(),
+            changelog_url: String::new(),
+            licenses_url: String::new(),
+            samples: vec![],
+            hide_in_editor: None,
+            package_type: String::new(),
+            zip_sha256: String::new(),
+            url: String::new(),
+            license: String::new(),
+            extra: IndexMap::new(),
+        }
+    }
+
+    fn lockfile_with(github: &mut RecordingGitHub, versions: &[&str]) -> Lockfile {
+        let mut package = LockedPackage {
+            id: "com.example.pkg".to_string(),
+            repository: Repository::parse("owner/repo").unwrap(),
+            versions: Vec::new(),
+            retained: false,
+        };
+        for version in versions {
+            let url = format!("https://example.com/{}/package.json", version);
+            let content = serde_json::to_string(&manifest(version)).unwrap();
+            github.assets.insert(url.clone(), content.clone());
+            package.versions.push(LockedVersion::new(
+                format!("v{}", version),
+                url,
+                &content,
+                manifest(version),
+            ));
+        }
+        let mut lockfile = Lockfile::new();
+        lockfile.packages.push(package);
+        lockfile
+    }
+
+    #[tokio::test]
+    async fn concurrency_of_one_serializes_downloads() {
+        let mut github = RecordingGitHub::default();
+        let lockfile = lockfile_with(&mut github, &["1.0.0", "1.1.0", "1.2.0"]);
+        let github = Arc::new(github);
+
+        let result = LockVerifier::new(github.clone(), 1, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.total, 3);
+        assert_eq!(result.verified, 3);
+        let calls = github.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 6);
+        for pair in calls.chunks(2) {
+            let url = pair[0].strip_prefix("start ").unwrap();
+            assert_eq!(pair[1], format!("end {}", url));
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_hash_mismatch() {
+        let mut github = RecordingGitHub::default();
+        let lockfile = lockfile_with(&mut github, &["1.0.0", "2.0.0"]);
+        github.assets.insert(
+            "https://example.com/2.0.0/package.json".to_string(),
+            "tampered".to_string(),
+        );
+
+        let result = LockVerifier::new(Arc::new(github), 4, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.verified, 1);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].version, "2.0.0");
+        assert!(result.failures[0].reason.contains("hash mismatch"));
+    }
+
+    #[tokio::test]
+    async fn reports_tampered_locked_manifest() {
+        let mut github = RecordingGitHub::default();
+        let mut lockfile = lockfile_with(&mut github, &["1.0.0", "2.0.0"]);
+        lockfile.packages[0].versions[1].manifest.url = "https://evil.example/pkg.zip".to_string();
+
+        let result = LockVerifier::new(Arc::new(github), 4, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.verified, 1);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].version, "2.0.0");
+        assert_eq!(
+            result.failures[0].reason,
+            "locked manifest differs from package.json in 'url'"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_stored_raw_manifest_that_does_not_match_its_hash() {
+        let mut github = RecordingGitHub::default();
+        let mut lockfile = lockfile_with(&mut github, &["1.0.0"]);
+        let locked = lockfile.packages[0].versions.remove(0);
+        let raw = github.assets[&locked.url].clone();
+        lockfile.packages[0]
+            .versions
+            .push(locked.with_raw_manifest(&raw.replace("Package", "Tampered")));
+
+        let result = LockVerifier::new(Arc::new(github), 1, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.failures.len(), 1);
+        assert!(
+            result.failures[0]
+                .reason
+                .starts_with("stored raw manifest: hash mismatch")
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_unverified_versions_without_downloading_them() {
+        let mut github = RecordingGitHub::default();
+        let mut lockfile = lockfile_with(&mut github, &["1.0.0", "2.0.0"]);
+        let locked = lockfile.packages[0].versions.remove(1);
+        lockfile.packages[0].versions.push(locked.unverified());
+        let github = Arc::new(github);
+
+        let result = LockVerifier::new(github.clone(), 1, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.verified, 1);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].version, "2.0.0");
+        assert!(result.failures[0].reason.contains("voy fetch"));
+        assert_eq!(github.calls.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn accepts_fields_filled_in_by_fetch() {
+        let mut github = RecordingGitHub::default();
+        let mut lockfile = lockfile_with(&mut github, &["1.0.0"]);
+        let locked = &mut lockfile.packages[0].versions[0];
+        let mut upstream = manifest("1.0.0+build.5");
+        upstream.zip_sha256 = String::new();
+        let content = serde_json::to_string(&upstream).unwrap();
+        locked.hash = crate::lock::compute_hash(&content);
+        locked.manifest.zip_sha256 = "ab".repeat(32);
+        github.assets.insert(locked.url.clone(), content);
+
+        let result = LockVerifier::new(Arc::new(github), 1, 0)
+            .verify(&lockfile, None, None)
+            .await
+            .unwrap();
+
+        assert!(
+            result.failures.is_empty(),
+            "{:?}",
+            result.failures[0].reason
+        );
+    }
+}
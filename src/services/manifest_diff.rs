@@ -0,0 +1,203 @@
+use crate::lock::PackageManifest;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Manifest keys reported as dependency changes instead of field changes.
+const DEPENDENCY_KEYS: [&str; 2] = ["vpmDependencies", "dependencies"];
+
+/// A top-level `package.json` field whose value differs between two versions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// A dependency whose version requirement changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Dependencies added, removed or changed between two versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyDiff {
+    pub added: IndexMap<String, String>,
+    pub removed: IndexMap<String, String>,
+    pub changed: Vec<DependencyChange>,
+}
+
+impl DependencyDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Field-by-field differences between two stored package manifests.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDiff {
+    pub from: String,
+    pub to: String,
+    pub fields: Vec<FieldChange>,
+    pub vpm_dependencies: DependencyDiff,
+    pub dependencies: DependencyDiff,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.vpm_dependencies.is_empty() && self.dependencies.is_empty()
+    }
+}
+
+/// Compares two manifests by their serialized `package.json` fields.
+///
+/// Fields are reported in key order; a field missing from one side is
+/// reported with `None` for that side.
+pub fn diff_manifests(old: &PackageManifest, new: &PackageManifest) -> ManifestDiff {
+    let old_fields = fields_of(old);
+    let new_fields = fields_of(new);
+
+    let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+
+    let fields = keys
+        .into_iter()
+        .filter(|key| !DEPENDENCY_KEYS.contains(&key.as_str()))
+        .filter_map(|key| {
+            let old = old_fields.get(key);
+            let new = new_fields.get(key);
+            (old != new).then(|| FieldChange {
+                field: key.clone(),
+                old: old.cloned(),
+                new: new.cloned(),
+            })
+        })
+        .collect();
+
+    ManifestDiff {
+        from: old.version.clone(),
+        to: new.version.clone(),
+        fields,
+        vpm_dependencies: diff_dependencies(&old.vpm_dependencies, &new.vpm_dependencies),
+        dependencies: diff_dependencies(&old.dependencies, &new.dependencies),
+    }
+}
+
+fn fields_of(manifest: &PackageManifest) -> serde_json::Map<String, Value> {
+    match serde_json::to_value(manifest) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
+fn diff_dependencies(
+    old: &IndexMap<String, String>,
+    new: &IndexMap<String, String>,
+) -> DependencyDiff {
+    let added = new
+        .iter()
+        .filter(|(name, _)| !old.contains_key(*name))
+        .map(|(name, version)| (name.clone(), version.clone()))
+        .collect();
+    let removed = old
+        .iter()
+        .filter(|(name, _)| !new.contains_key(*name))
+        .map(|(name, version)| (name.clone(), version.clone()))
+        .collect();
+    let changed = old
+        .iter()
+        .filter_map(|(name, old_version)| {
+            let new_version = new.get(name)?;
+            (new_version != old_version).then(|| DependencyChange {
+                name: name.clone(),
+                old: old_version.clone(),
+                new: new_version.clone(),
+            })
+        })
+        .collect();
+
+    DependencyDiff {
+        added,
+        removed,
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(version: &str, unity: &str, vpm_dependencies: Value) -> PackageManifest {
+        serde_json::from_value(serde_json::json!({
+            "name": "com.example.pkg",
+            "version": version,
+            "displayName": "Example",
+            "description": "desc",
+            "unity": unity,
+            "author": { "name": "Author" },
+            "vpmDependencies": vpm_dependencies,
+            "url": "https://example.com/pkg.zip"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_dependencies() {
+        let old = manifest(
+            "1.0.0",
+            "2022.3",
+            serde_json::json!({ "com.example.old": ">=1.0.0", "com.example.base": "1.0.0" }),
+        );
+        let new = manifest(
+            "2.0.0",
+            "2022.3",
+            serde_json::json!({ "com.example.base": "2.0.0", "com.example.new": ">=0.1.0" }),
+        );
+
+        let diff = diff_manifests(&old, &new);
+
+        assert_eq!(
+            diff.vpm_dependencies.added,
+            IndexMap::from([("com.example.new".to_string(), ">=0.1.0".to_string())])
+        );
+        assert_eq!(
+            diff.vpm_dependencies.removed,
+            IndexMap::from([("com.example.old".to_string(), ">=1.0.0".to_string())])
+        );
+        assert_eq!(
+            diff.vpm_dependencies.changed,
+            vec![DependencyChange {
+                name: "com.example.base".to_string(),
+                old: "1.0.0".to_string(),
+                new: "2.0.0".to_string(),
+            }]
+        );
+        assert!(diff.dependencies.is_empty());
+    }
+
+    #[test]
+    fn reports_changed_and_missing_fields() {
+        let old = manifest("1.0.0", "2022.3", serde_json::json!({}));
+        let new = manifest("1.1.0", "", serde_json::json!({}));
+
+        let diff = diff_manifests(&old, &new);
+
+        let fields: Vec<_> = diff.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, vec!["unity", "version"]);
+        assert_eq!(diff.fields[0].old, Some(Value::from("2022.3")));
+        assert_eq!(diff.fields[0].new, None);
+    }
+
+    #[test]
+    fn identical_manifests_have_no_diff() {
+        let old = manifest("1.0.0", "2022.3", serde_json::json!({}));
+
+        let diff = diff_manifests(&old, &old.clone());
+
+        assert!(diff.is_empty());
+    }
+}
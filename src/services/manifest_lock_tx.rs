@@ -5,7 +5,9 @@ use crate::infra::{
 };
 use crate::lock::Lockfile;
 use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions, TryLockError};
 use std::path::{Path, PathBuf};
+use tracing::warn;
 
 #[cfg(test)]
 use std::fs;
@@ -18,8 +20,48 @@ struct ManifestLockTransaction {
     new_lock: String,
 }
 
-fn transaction_path(config_path: &Path) -> PathBuf {
-    config_path.with_extension("txn")
+/// Returns the path of the transaction log kept next to the lock file.
+pub fn transaction_path(lock_path: &Path) -> PathBuf {
+    lock_path.with_extension("txn")
+}
+
+/// Returns the path of the advisory lock file that serializes manifest+lock
+/// writes across processes.
+pub fn write_lock_path(lock_path: &Path) -> PathBuf {
+    lock_path.with_extension("lck")
+}
+
+/// Takes the exclusive advisory lock on [`write_lock_path`], blocking until
+/// any other process holding it is done and warning once if it has to wait.
+/// The lock is released when the returned file is dropped; the file itself
+/// stays in place.
+fn acquire_write_lock(lock_path: &Path) -> Result<File> {
+    let path = write_lock_path(lock_path);
+    let to_error = |e| Error::FileWrite {
+        path: path.display().to_string(),
+        source: e,
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(to_error)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(to_error)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            warn!(
+                path = %path.display(),
+                "Waiting for another voyager process to release the write lock"
+            );
+            file.lock().map_err(to_error)?;
+        }
+        Err(TryLockError::Error(e)) => return Err(to_error(e)),
+    }
+    Ok(file)
 }
 
 fn write_atomic(path: &Path, content: &str) -> Result<()> {
@@ -44,27 +86,17 @@ fn read_optional_file(path: &Path) -> Result<Option<String>> {
 }
 
 fn serialize_manifest(manifest: &Manifest, config_path: &Path) -> Result<String> {
-    toml::to_string_pretty(manifest).map_err(|e| Error::TomlSerialize {
-        path: config_path.display().to_string(),
-        source: e,
-    })
+    manifest.to_file_string(config_path)
 }
 
-fn serialize_lock(lockfile: &Lockfile, lock_path: &Path) -> Result<String> {
-    toml::to_string_pretty(lockfile).map_err(|e| Error::TomlSerialize {
-        path: lock_path.display().to_string(),
-        source: e,
-    })
-}
-
-fn write_transaction_log(config_path: &Path, tx: &ManifestLockTransaction) -> Result<()> {
-    let tx_path = transaction_path(config_path);
+fn write_transaction_log(lock_path: &Path, tx: &ManifestLockTransaction) -> Result<()> {
+    let tx_path = transaction_path(lock_path);
     let content = serde_json::to_string_pretty(tx).map_err(Error::JsonSerialize)?;
     write_atomic(&tx_path, &content)
 }
 
-fn load_transaction_log(config_path: &Path) -> Result<Option<ManifestLockTransaction>> {
-    let tx_path = transaction_path(config_path);
+fn load_transaction_log(lock_path: &Path) -> Result<Option<ManifestLockTransaction>> {
+    let tx_path = transaction_path(lock_path);
     let Some(content) = read_optional_file(&tx_path)? else {
         return Ok(None);
     };
@@ -82,7 +114,15 @@ fn load_transaction_log(config_path: &Path) -> Result<Option<ManifestLockTransac
 ///   by deleting the log.
 /// - Otherwise, files are rolled back to their previous state and the log is removed.
 pub fn recover_manifest_lock_transaction(config_path: &Path, lock_path: &Path) -> Result<()> {
-    let Some(tx) = load_transaction_log(config_path)? else {
+    if !transaction_path(lock_path).exists() {
+        return Ok(());
+    }
+    let _guard = acquire_write_lock(lock_path)?;
+    recover_locked(config_path, lock_path)
+}
+
+fn recover_locked(config_path: &Path, lock_path: &Path) -> Result<()> {
+    let Some(tx) = load_transaction_log(lock_path)? else {
         return Ok(());
     };
 
@@ -104,12 +144,12 @@ pub fn recover_manifest_lock_transaction(config_path: &Path, lock_path: &Path) -
     let lock_is_new = current_lock.as_deref() == Some(tx.new_lock.as_str());
 
     if manifest_is_new && lock_is_new {
-        remove_file_if_exists(&transaction_path(config_path))?;
+        remove_file_if_exists(&transaction_path(lock_path))?;
         return Ok(());
     }
 
     if manifest_is_old && lock_is_old {
-        remove_file_if_exists(&transaction_path(config_path))?;
+        remove_file_if_exists(&transaction_path(lock_path))?;
         return Ok(());
     }
 
@@ -124,54 +164,106 @@ pub fn recover_manifest_lock_transaction(config_path: &Path, lock_path: &Path) -
             Some(old_lock) => write_atomic(lock_path, &old_lock)?,
             None => remove_file_if_exists(lock_path)?,
         }
-        remove_file_if_exists(&transaction_path(config_path))?;
+        remove_file_if_exists(&transaction_path(lock_path))?;
         return Ok(());
     }
 
     Err(Error::ConfigValidation(format!(
         "Found unresolved manifest/lock transaction '{}', but current files do not match \
          a recoverable state. Please inspect files and remove the transaction file manually.",
-        transaction_path(config_path).display()
+        transaction_path(lock_path).display()
     )))
 }
 
-/// Saves `manifest` and `lockfile` as a crash-recoverable transaction.
-///
-/// A transaction log is written first. If a crash occurs mid-update, the next run
-/// can recover by calling `recover_manifest_lock_transaction`.
-pub fn save_manifest_and_lock(
-    manifest: &Manifest,
+/// Serializes `lockfile` exactly as a [`LockWriter`] with the same header
+/// setting saves it.
+pub fn serialize_lock(
     lockfile: &Lockfile,
-    config_path: &Path,
     lock_path: &Path,
-) -> Result<()> {
-    recover_manifest_lock_transaction(config_path, lock_path)?;
-
-    let old_manifest = read_optional_file(config_path)?;
-    let old_lock = read_optional_file(lock_path)?;
+    header_comment: bool,
+) -> Result<String> {
+    lockfile
+        .to_toml_string(header_comment)
+        .map_err(|e| Error::TomlSerialize {
+            path: lock_path.display().to_string(),
+            source: e,
+        })
+}
 
-    let tx = ManifestLockTransaction {
-        old_manifest,
-        old_lock,
-        new_manifest: serialize_manifest(manifest, config_path)?,
-        new_lock: serialize_lock(lockfile, lock_path)?,
-    };
+/// Writes the manifest and lock file of one config, adding the lock file
+/// header comment when enabled.
+///
+/// The writer holds the exclusive lock on [`write_lock_path`] until it is
+/// dropped. Commands acquire it before loading the files they save, so no
+/// other process can change them between the load and the save.
+#[derive(Debug)]
+pub struct LockWriter {
+    config_path: PathBuf,
+    lock_path: PathBuf,
+    header_comment: bool,
+    _write_lock: File,
+}
 
-    write_transaction_log(config_path, &tx)?;
+impl LockWriter {
+    /// Takes the write lock, blocking until any other writer is done, then
+    /// recovers a transaction an interrupted writer left behind.
+    pub fn acquire(config_path: &Path, lock_path: &Path) -> Result<Self> {
+        let write_lock = acquire_write_lock(lock_path)?;
+        recover_locked(config_path, lock_path)?;
+        Ok(Self {
+            config_path: config_path.to_path_buf(),
+            lock_path: lock_path.to_path_buf(),
+            header_comment: false,
+            _write_lock: write_lock,
+        })
+    }
 
-    let write_result = (|| -> Result<()> {
-        write_atomic(config_path, &tx.new_manifest)?;
-        write_atomic(lock_path, &tx.new_lock)?;
-        Ok(())
-    })();
+    /// Writes the "generated by voyager" header comment into saved lock files.
+    pub fn with_header_comment(mut self, enabled: bool) -> Self {
+        self.header_comment = enabled;
+        self
+    }
 
-    if let Err(e) = write_result {
-        let _ = recover_manifest_lock_transaction(config_path, lock_path);
-        return Err(e);
+    /// Saves the lock file alone, leaving the manifest untouched.
+    pub fn save_lock(&self, lockfile: &Lockfile) -> Result<()> {
+        let content = serialize_lock(lockfile, &self.lock_path, self.header_comment)?;
+        write_atomic(&self.lock_path, &content)
     }
 
-    remove_file_if_exists(&transaction_path(config_path))?;
-    Ok(())
+    /// Saves `manifest` and `lockfile` as a crash-recoverable transaction.
+    ///
+    /// A transaction log is written first. If a crash occurs mid-update, the next run
+    /// can recover by calling `recover_manifest_lock_transaction`.
+    pub fn save_manifest_and_lock(&self, manifest: &Manifest, lockfile: &Lockfile) -> Result<()> {
+        let config_path = self.config_path.as_path();
+        let lock_path = self.lock_path.as_path();
+
+        let old_manifest = read_optional_file(config_path)?;
+        let old_lock = read_optional_file(lock_path)?;
+
+        let tx = ManifestLockTransaction {
+            old_manifest,
+            old_lock,
+            new_manifest: serialize_manifest(manifest, config_path)?,
+            new_lock: serialize_lock(lockfile, lock_path, self.header_comment)?,
+        };
+
+        write_transaction_log(lock_path, &tx)?;
+
+        let write_result = (|| -> Result<()> {
+            write_atomic(config_path, &tx.new_manifest)?;
+            write_atomic(lock_path, &tx.new_lock)?;
+            Ok(())
+        })();
+
+        if let Err(e) = write_result {
+            let _ = recover_locked(config_path, lock_path);
+            return Err(e);
+        }
+
+        remove_file_if_exists(&transaction_path(lock_path))?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +275,9 @@ mod tests {
 
     fn sample_manifest(name: &str) -> Manifest {
         Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
             vpm: Vpm {
                 id: "com.example.vpm".to_string(),
                 name: name.to_string(),
@@ -192,7 +287,14 @@ mod tests {
             packages: vec![Package {
                 id: "com.example.vpm.pkg".to_string(),
                 repository: Repository::parse("owner/repo").unwrap(),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
             }],
+            included: Default::default(),
+            format: Default::default(),
         }
     }
 
@@ -213,13 +315,16 @@ mod tests {
 
         let new_manifest = sample_manifest("New");
         let new_lock = sample_lock("hash-new");
-        save_manifest_and_lock(&new_manifest, &new_lock, &config_path, &lock_path).unwrap();
+        LockWriter::acquire(&config_path, &lock_path)
+            .unwrap()
+            .save_manifest_and_lock(&new_manifest, &new_lock)
+            .unwrap();
 
         let persisted = Manifest::load(&config_path).unwrap();
         assert_eq!(persisted.vpm.name, "New");
         let persisted_lock = Lockfile::load(&lock_path).unwrap();
         assert_eq!(persisted_lock.manifest_hash.as_deref(), Some("hash-new"));
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -230,13 +335,16 @@ mod tests {
 
         let manifest = sample_manifest("New");
         let lock = sample_lock("hash-new");
-        save_manifest_and_lock(&manifest, &lock, &config_path, &lock_path).unwrap();
+        LockWriter::acquire(&config_path, &lock_path)
+            .unwrap()
+            .save_manifest_and_lock(&manifest, &lock)
+            .unwrap();
 
         let persisted = Manifest::load(&config_path).unwrap();
         assert_eq!(persisted.vpm.name, "New");
         let persisted_lock = Lockfile::load(&lock_path).unwrap();
         assert_eq!(persisted_lock.manifest_hash.as_deref(), Some("hash-new"));
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -249,7 +357,7 @@ mod tests {
             old_manifest: None,
             old_lock: None,
             new_manifest: serialize_manifest(&sample_manifest("New"), &config_path).unwrap(),
-            new_lock: serialize_lock(&sample_lock("new"), &lock_path).unwrap(),
+            new_lock: serialize_lock(&sample_lock("new"), &lock_path, false).unwrap(),
         };
         write_transaction_log(&config_path, &tx).unwrap();
         write_atomic(&config_path, &tx.new_manifest).unwrap();
@@ -258,7 +366,7 @@ mod tests {
 
         assert!(!config_path.exists());
         assert!(!lock_path.exists());
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -272,17 +380,20 @@ mod tests {
         old_manifest.save(&config_path).unwrap();
         fs::write(&blocker_path, "x").unwrap();
 
-        let result = save_manifest_and_lock(
-            &sample_manifest("New"),
-            &sample_lock("hash"),
-            &config_path,
-            &lock_path,
-        );
+        // The lock file's directory cannot be created, so the write lock is
+        // taken next to the manifest instead.
+        let writer = LockWriter {
+            config_path: config_path.clone(),
+            lock_path: lock_path.clone(),
+            header_comment: false,
+            _write_lock: acquire_write_lock(&config_path).unwrap(),
+        };
+        let result = writer.save_manifest_and_lock(&sample_manifest("New"), &sample_lock("hash"));
         assert!(result.is_err());
 
         let persisted = Manifest::load(&config_path).unwrap();
         assert_eq!(persisted.vpm.name, "Old");
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -298,9 +409,9 @@ mod tests {
 
         let tx = ManifestLockTransaction {
             old_manifest: Some(serialize_manifest(&old_manifest, &config_path).unwrap()),
-            old_lock: Some(serialize_lock(&old_lock, &lock_path).unwrap()),
+            old_lock: Some(serialize_lock(&old_lock, &lock_path, false).unwrap()),
             new_manifest: serialize_manifest(&sample_manifest("New"), &config_path).unwrap(),
-            new_lock: serialize_lock(&sample_lock("new"), &lock_path).unwrap(),
+            new_lock: serialize_lock(&sample_lock("new"), &lock_path, false).unwrap(),
         };
         write_transaction_log(&config_path, &tx).unwrap();
         write_atomic(&config_path, &tx.new_manifest).unwrap();
@@ -311,7 +422,7 @@ mod tests {
         assert_eq!(recovered_manifest.vpm.name, "Old");
         let recovered_lock = Lockfile::load(&lock_path).unwrap();
         assert_eq!(recovered_lock.manifest_hash.as_deref(), Some("old"));
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -327,7 +438,7 @@ mod tests {
             old_manifest: Some(serialize_manifest(&old_manifest, &config_path).unwrap()),
             old_lock: None,
             new_manifest: serialize_manifest(&sample_manifest("New"), &config_path).unwrap(),
-            new_lock: serialize_lock(&sample_lock("new"), &lock_path).unwrap(),
+            new_lock: serialize_lock(&sample_lock("new"), &lock_path, false).unwrap(),
         };
         write_transaction_log(&config_path, &tx).unwrap();
         write_atomic(&config_path, &tx.new_manifest).unwrap();
@@ -339,7 +450,7 @@ mod tests {
         assert_eq!(recovered_manifest.vpm.name, "New");
         let recovered_lock = Lockfile::load(&lock_path).unwrap();
         assert_eq!(recovered_lock.manifest_hash.as_deref(), Some("new"));
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -355,9 +466,9 @@ mod tests {
 
         let tx = ManifestLockTransaction {
             old_manifest: Some(serialize_manifest(&old_manifest, &config_path).unwrap()),
-            old_lock: Some(serialize_lock(&old_lock, &lock_path).unwrap()),
+            old_lock: Some(serialize_lock(&old_lock, &lock_path, false).unwrap()),
             new_manifest: serialize_manifest(&sample_manifest("New"), &config_path).unwrap(),
-            new_lock: serialize_lock(&sample_lock("new"), &lock_path).unwrap(),
+            new_lock: serialize_lock(&sample_lock("new"), &lock_path, false).unwrap(),
         };
         write_transaction_log(&config_path, &tx).unwrap();
 
@@ -367,7 +478,7 @@ mod tests {
         assert_eq!(recovered_manifest.vpm.name, "Old");
         let recovered_lock = Lockfile::load(&lock_path).unwrap();
         assert_eq!(recovered_lock.manifest_hash.as_deref(), Some("old"));
-        assert!(!transaction_path(&config_path).exists());
+        assert!(!transaction_path(&lock_path).exists());
     }
 
     #[test]
@@ -380,13 +491,13 @@ mod tests {
         old_manifest.save(&config_path).unwrap();
         let old_lock = sample_lock("old");
         old_lock.save(&lock_path).unwrap();
-        let old_lock_content = serialize_lock(&old_lock, &lock_path).unwrap();
+        let old_lock_content = serialize_lock(&old_lock, &lock_path, false).unwrap();
 
         let tx = ManifestLockTransaction {
             old_manifest: Some(serialize_manifest(&old_manifest, &config_path).unwrap()),
             old_lock: Some(old_lock_content.clone()),
             new_manifest: serialize_manifest(&sample_manifest("New"), &config_path).unwrap(),
-            new_lock: serialize_lock(&sample_lock("new"), &lock_path).unwrap(),
+            new_lock: serialize_lock(&sample_lock("new"), &lock_path, false).unwrap(),
         };
         write_transaction_log(&config_path, &tx).unwrap();
 
@@ -401,7 +512,27 @@ mod tests {
         let persisted_lock = fs::read_to_string(&lock_path).unwrap();
         assert_eq!(persisted_manifest, user_manifest);
         assert_eq!(persisted_lock, old_lock_content);
-        assert!(transaction_path(&config_path).exists());
+        assert!(transaction_path(&lock_path).exists());
+    }
+
+    #[test]
+    fn writer_adds_the_header_comment_to_every_save_when_enabled() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("voyager.toml");
+        let lock_path = dir.path().join("voyager.lock");
+        let writer = LockWriter::acquire(&config_path, &lock_path)
+            .unwrap()
+            .with_header_comment(true);
+
+        writer
+            .save_manifest_and_lock(&sample_manifest("New"), &sample_lock("new"))
+            .unwrap();
+        assert!(Lockfile::load(&lock_path).unwrap().has_header_comment());
+
+        writer.save_lock(&sample_lock("newer")).unwrap();
+        let saved = Lockfile::load(&lock_path).unwrap();
+        assert!(saved.has_header_comment());
+        assert_eq!(saved.manifest_hash.as_deref(), Some("newer"));
     }
 
     #[test]
@@ -412,7 +543,10 @@ mod tests {
         let manifest = sample_manifest("New");
         let lockfile = sample_lock("new");
 
-        save_manifest_and_lock(&manifest, &lockfile, &config_path, &lock_path).unwrap();
+        LockWriter::acquire(&config_path, &lock_path)
+            .unwrap()
+            .save_manifest_and_lock(&manifest, &lockfile)
+            .unwrap();
 
         assert!(config_path.exists());
         assert!(lock_path.exists());
@@ -1,11 +1,51 @@
+mod asset_list;
+mod changed_packages;
+mod dependency_graph;
+mod fetch_checkpoint;
+mod fetch_summary;
+mod fetched_repositories;
 mod hash_checker;
+mod index_diff;
 mod index_generator;
+mod index_migration;
+mod lock_verifier;
+mod manifest_diff;
 mod manifest_lock_tx;
+mod outdated;
 mod package_fetcher;
 mod url_validator;
+mod url_warmer;
+mod verified_repositories;
 
+pub use asset_list::{AssetEntry, asset_list};
+pub use changed_packages::{DiffSource, GitDiffSource, packages_changed_since};
+pub use dependency_graph::DependencyGraph;
+pub use fetch_checkpoint::{FetchCheckpoint, checkpoint_path};
+pub use fetch_summary::{FetchSummary, FetchSummaryReporter, FetchTotals, PackageFetchSummary};
+pub use fetched_repositories::{FetchedRepositories, fetched_repositories_path};
 pub use hash_checker::{HashCheckResult, check_and_load};
-pub use index_generator::generate_from_lockfile;
-pub use manifest_lock_tx::{recover_manifest_lock_transaction, save_manifest_and_lock};
-pub use package_fetcher::{FetchProgressReporter, FetcherConfig, PackageFetcher};
-pub use url_validator::{InvalidUrl, UrlValidator, ValidationResult};
+pub use index_diff::{IndexDiff, PackageChange, PackageDiff, diff_indexes};
+pub use index_generator::{
+    DEFAULT_MAX_INDEX_SIZE, GenerateOptions, METADATA_KEY, PackageOrder, VersionOrder,
+    duplicate_display_name_warning, generate_from_lockfile, generate_from_lockfile_with,
+    index_size_warning, serialize_index,
+};
+pub use index_migration::{MigratedIndex, migrate_index};
+pub use lock_verifier::{LockVerifier, VerifyFailure, VerifyResult};
+pub use manifest_diff::{
+    DependencyChange, DependencyDiff, FieldChange, ManifestDiff, diff_manifests,
+};
+pub use manifest_lock_tx::{
+    LockWriter, recover_manifest_lock_transaction, serialize_lock, transaction_path,
+    write_lock_path,
+};
+pub use outdated::{OutdatedPackage, outdated_packages};
+pub use package_fetcher::{FetchProgressReporter, FetcherConfig, PackageFetcher, PackagePlan};
+pub use url_validator::{
+    InvalidUrl, UrlValidator, ValidationReport, ValidationReportEntry, ValidationResult,
+    ValidationSummary,
+};
+pub use url_warmer::{url_list, warm_urls};
+pub use verified_repositories::{
+    VERIFIED_TTL_SECS, VerifiedRepositories, verified_repositories_path,
+};
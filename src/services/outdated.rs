@@ -0,0 +1,127 @@
+use super::index_generator::compare_versions;
+use super::package_fetcher::PackagePlan;
+use crate::lock::Lockfile;
+use serde::Serialize;
+
+/// A package with releases that are not in the lockfile yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutdatedPackage {
+    pub package_id: String,
+    /// Newest locked version, if any version is locked.
+    pub locked: Option<String>,
+    /// Newest version available from releases or the lockfile.
+    pub latest: String,
+    /// Released versions missing from the lockfile.
+    pub new_versions: Vec<String>,
+}
+
+/// Picks the packages whose fetch plan would add versions.
+pub fn outdated_packages(plans: &[PackagePlan], lockfile: &Lockfile) -> Vec<OutdatedPackage> {
+    plans
+        .iter()
+        .filter(|plan| !plan.added.is_empty())
+        .map(|plan| {
+            let locked = lockfile
+                .get_package(&plan.package_id)
+                .and_then(|p| {
+                    p.versions
+                        .iter()
+                        .map(|v| v.version.as_str())
+                        .max_by(|a, b| compare_versions(a, b))
+                })
+                .map(str::to_string);
+            let latest = plan
+                .added
+                .iter()
+                .map(String::as_str)
+                .chain(locked.as_deref())
+                .max_by(|a, b| compare_versions(a, b))
+                .unwrap_or_default()
+                .to_string();
+
+            OutdatedPackage {
+                package_id: plan.package_id.clone(),
+                locked,
+                latest,
+                new_versions: plan.added.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Repository;
+    use crate::lock::{LockedPackage, LockedVersion, PackageManifest};
+
+    fn locked(id: &str, versions: &[&str]) -> LockedPackage {
+        LockedPackage {
+            id: id.to_string(),
+            repository: Repository::parse("owner/repo").unwrap(),
+            versions: versions
+                .iter()
+                .map(|version| {
+                    let manifest: PackageManifest = serde_json::from_value(serde_json::json!({
+                        "name": id,
+                        "version": version,
+                        "url": "https://example.com/pkg.zip"
+                    }))
+                    .unwrap();
+                    LockedVersion::new(
+                        format!("v{version}"),
+                        "https://example.com/package.json".to_string(),
+                        "{}",
+                        manifest,
+                    )
+                })
+                .collect(),
+            retained: false,
+        }
+    }
+
+    fn plan(id: &str, added: &[&str]) -> PackagePlan {
+        PackagePlan {
+            package_id: id.to_string(),
+            added: added.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_only_packages_with_new_releases() {
+        let mut lockfile = Lockfile::new();
+        lockfile
+            .packages
+            .push(locked("com.example.current", &["1.0.0"]));
+        lockfile
+            .packages
+            .push(locked("com.example.behind", &["1.10.0", "1.9.0"]));
+
+        let plans = [
+            plan("com.example.current", &[]),
+            plan("com.example.behind", &["2.0.0", "1.11.0"]),
+            plan("com.example.new", &["0.1.0"]),
+        ];
+
+        let outdated = outdated_packages(&plans, &lockfile);
+
+        assert_eq!(
+            outdated,
+            vec![
+                OutdatedPackage {
+                    package_id: "com.example.behind".to_string(),
+                    locked: Some("1.10.0".to_string()),
+                    latest: "2.0.0".to_string(),
+                    new_versions: vec!["2.0.0".to_string(), "1.11.0".to_string()],
+                },
+                OutdatedPackage {
+                    package_id: "com.example.new".to_string(),
+                    locked: None,
+                    latest: "0.1.0".to_string(),
+                    new_versions: vec!["0.1.0".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -1,11 +1,17 @@
 use crate::config::{Manifest, Package, validation};
-use crate::domain::Release;
-use crate::error::{Error, Result};
-use crate::infra::GitHubApi;
-use crate::lock::{LockedPackage, LockedVersion, Lockfile, PackageManifest};
+use crate::domain::{Release, strip_build_metadata};
+use crate::error::{Error, FetchFailure, Result};
+use crate::infra::{GitHubApi, RetryObserver};
+use crate::lock::{
+    HashAlgorithm, LockedPackage, LockedVersion, Lockfile, PackageManifest, sha256_hex,
+};
+use crate::services::fetch_checkpoint::FetchCheckpoint;
 use futures::stream::{self, StreamExt};
+use indexmap::IndexMap;
 use semver::Version;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{info, instrument, warn};
 
@@ -16,14 +22,112 @@ pub struct PackageFetcher<G: GitHubApi> {
 
 pub struct FetcherConfig {
     pub max_concurrent: usize,
+    /// Packages fetched at once; `None` derives it from `max_concurrent`.
+    pub package_concurrency: Option<usize>,
+    /// Asset downloads per package at once; `None` splits `max_concurrent`
+    /// between the packages fetched at once.
+    pub download_concurrency: Option<usize>,
     pub max_retries: u32,
     pub asset_name: String,
+    /// Only process packages that have no locked versions yet.
+    pub only_missing: bool,
+    /// Only process packages with these IDs; `None` processes all packages.
+    pub only_packages: Option<HashSet<String>>,
+    /// Where completed packages are recorded as the fetch progresses.
+    pub checkpoint_path: Option<PathBuf>,
+    /// Reuse packages already completed in the checkpoint instead of refetching them.
+    pub resume: bool,
+    /// Lock versions without their `+build` metadata, keeping the original tag.
+    pub strip_build_metadata: bool,
+    /// Package ID prefixes that `vpmDependencies` may reference; empty allows any.
+    pub allowed_dependency_prefixes: Vec<String>,
+    /// Reject versions whose `unity` is below this `MAJOR.MINOR` floor.
+    pub min_unity: Option<String>,
+    /// Reject versions that do not declare `unity`.
+    pub require_unity: bool,
+    /// Download each new version's zip and fill in or check its `zipSHA256`.
+    pub verify_zip: bool,
+    /// Keep each new version's raw package.json in the lock file.
+    pub store_raw: bool,
+    /// Reject versions with blank `keywords` instead of dropping them.
+    pub strict_keywords: bool,
+    /// Algorithm for the hash of each new version's package.json.
+    pub hash_algorithm: HashAlgorithm,
+    /// Reject versions whose zip `url` is on another host than their
+    /// package.json asset instead of warning.
+    pub strict_url: bool,
+    /// Skip releases whose version has a semver prerelease component.
+    pub no_prerelease: bool,
+    /// Keep prereleases that are already locked when `no_prerelease` is set.
+    pub keep_existing: bool,
+    /// Reject versions with malformed `legacyFolders`/`legacyFiles` paths
+    /// instead of warning.
+    pub strict: bool,
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 5,
+            package_concurrency: None,
+            download_concurrency: None,
+            max_retries: 3,
+            asset_name: "package.json".to_string(),
+            only_missing: false,
+            only_packages: None,
+            checkpoint_path: None,
+            resume: false,
+            strip_build_metadata: false,
+            allowed_dependency_prefixes: Vec::new(),
+            min_unity: None,
+            require_unity: false,
+            verify_zip: false,
+            store_raw: false,
+            strict_keywords: false,
+            hash_algorithm: HashAlgorithm::default(),
+            strict_url: false,
+            no_prerelease: false,
+            keep_existing: false,
+            strict: false,
+        }
+    }
 }
 
 pub trait FetchProgressReporter: Send + Sync {
     fn on_fetching_releases(&self, package_id: &str);
     fn on_downloading(&self, package_id: &str, version_count: usize);
     fn on_done(&self, package_id: &str, existing: usize, new: usize);
+    /// Called before `on_done` when some of the package's versions failed.
+    fn on_failed(&self, package_id: &str, failed: usize);
+    fn on_retrying(&self, package_id: &str, attempt: u32);
+}
+
+/// Forwards download retries to the progress reporter of a single package.
+struct PackageRetryObserver<'a, P: FetchProgressReporter + ?Sized> {
+    package_id: &'a str,
+    progress: &'a P,
+}
+
+impl<P: FetchProgressReporter + ?Sized> RetryObserver for PackageRetryObserver<'_, P> {
+    fn on_retry(&self, _url: &str, attempt: u32) {
+        self.progress.on_retrying(self.package_id, attempt);
+    }
+}
+
+/// Versions a fetch would add, keep and prune for one package.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PackagePlan {
+    #[serde(rename = "id")]
+    pub package_id: String,
+    pub added: Vec<String>,
+    pub kept: Vec<String>,
+    pub pruned: Vec<String>,
+}
+
+impl PackagePlan {
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.pruned.is_empty()
+    }
 }
 
 struct PackageFetchResult {
@@ -31,7 +135,19 @@ struct PackageFetchResult {
     versions: Vec<LockedVersion>,
     existing_count: usize,
     new_count: usize,
-    failed_count: usize,
+    failures: Vec<FetchFailure>,
+}
+
+impl PackageFetchResult {
+    fn unchanged(existing_package: LockedPackage) -> Self {
+        Self {
+            package_id: existing_package.id,
+            existing_count: existing_package.versions.len(),
+            versions: existing_package.versions,
+            new_count: 0,
+            failures: Vec::new(),
+        }
+    }
 }
 
 impl<G: GitHubApi> PackageFetcher<G> {
@@ -43,6 +159,53 @@ impl<G: GitHubApi> PackageFetcher<G> {
         Self { github, config }
     }
 
+    /// Returns how many of `package_count` packages are fetched at once and
+    /// how many assets each of them downloads at once.
+    fn concurrency(&self, package_count: usize) -> (usize, usize) {
+        let packages = self
+            .config
+            .package_concurrency
+            .unwrap_or(self.config.max_concurrent)
+            .clamp(1, package_count.max(1));
+        let downloads = self
+            .config
+            .download_concurrency
+            .unwrap_or(self.config.max_concurrent / packages)
+            .max(1);
+        (packages, downloads)
+    }
+
+    /// Returns whether a package should be fetched under the configured filters.
+    /// Skipped packages keep their locked versions untouched.
+    fn should_fetch(&self, package_id: &str, has_versions: bool) -> bool {
+        if self.config.only_missing && has_versions {
+            return false;
+        }
+        if let Some(only_packages) = &self.config.only_packages
+            && !only_packages.contains(package_id)
+        {
+            return false;
+        }
+        true
+    }
+
+    /// Returns the IDs of the manifest packages that `fetch` would contact,
+    /// given `lockfile` as it is before the fetch.
+    pub fn selected_packages(&self, manifest: &Manifest, lockfile: &Lockfile) -> HashSet<String> {
+        manifest
+            .packages
+            .iter()
+            .filter(|package| {
+                // A changed repository drops the locked versions on reconcile.
+                let has_versions = lockfile.get_package(&package.id).is_some_and(|locked| {
+                    locked.repository == package.repository && !locked.versions.is_empty()
+                });
+                self.should_fetch(&package.id, has_versions)
+            })
+            .map(|package| package.id.clone())
+            .collect()
+    }
+
     fn parse_package_manifest(
         &self,
         content: &str,
@@ -54,6 +217,142 @@ impl<G: GitHubApi> PackageFetcher<G> {
         })
     }
 
+    /// Downloads each version's zip and fills in `zipSHA256` when the
+    /// package.json leaves it empty. Versions whose zip cannot be downloaded
+    /// or whose declared hash disagrees are dropped and recorded in `failures`.
+    async fn verify_zips(
+        &self,
+        package_id: &str,
+        versions: Vec<LockedVersion>,
+        concurrency: usize,
+        failures: &mut Vec<FetchFailure>,
+    ) -> Vec<LockedVersion> {
+        let results: Vec<(LockedVersion, Result<String>)> = stream::iter(versions)
+            .map(|version| async move {
+                let hash = self
+                    .github
+                    .download_file(&version.manifest.url, self.config.max_retries)
+                    .await
+                    .map(|bytes| sha256_hex(&bytes));
+                (version, hash)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let mut verified = Vec::with_capacity(results.len());
+        for (mut version, hash) in results {
+            let declared = &version.manifest.zip_sha256;
+            match hash {
+                Ok(hash) if declared.is_empty() || declared.eq_ignore_ascii_case(&hash) => {
+                    version.manifest.zip_sha256 = hash;
+                    verified.push(version);
+                }
+                Ok(hash) => {
+                    warn!(
+                        version = %version.version,
+                        declared = %declared,
+                        computed = %hash,
+                        "Rejected version whose zipSHA256 does not match its zip"
+                    );
+                    failures.push(FetchFailure {
+                        package_id: package_id.to_string(),
+                        tag: version.tag,
+                        reason: format!(
+                            "zipSHA256 '{}' does not match the zip's '{}'",
+                            declared, hash
+                        ),
+                    });
+                }
+                Err(e) => {
+                    warn!(
+                        version = %version.version,
+                        error = %e,
+                        "Failed to download zip for zipSHA256"
+                    );
+                    failures.push(FetchFailure {
+                        package_id: package_id.to_string(),
+                        tag: version.tag,
+                        reason: format!("failed to download zip for zipSHA256: {}", e),
+                    });
+                }
+            }
+        }
+        verified
+    }
+
+    /// Returns the version a release is locked under.
+    fn release_version<'a>(&self, release: &'a Release) -> &'a str {
+        if self.config.strip_build_metadata {
+            release.version_without_build_metadata()
+        } else {
+            release.version()
+        }
+    }
+
+    /// Returns new releases to download when build metadata is stripped.
+    ///
+    /// Releases whose stripped version collides with another release, or with
+    /// a version locked from a different tag, are rejected and recorded in `failures`.
+    fn filter_new_stripped(
+        &self,
+        releases: &[Release],
+        existing_package: &LockedPackage,
+        failures: &mut Vec<FetchFailure>,
+    ) -> Vec<Release> {
+        let mut releases_by_version: HashMap<&str, usize> = HashMap::new();
+        for release in releases.iter().filter(|r| r.asset_url().is_some()) {
+            *releases_by_version
+                .entry(self.release_version(release))
+                .or_default() += 1;
+        }
+
+        let mut new_releases = Vec::new();
+        for release in releases.iter().filter(|r| r.asset_url().is_some()) {
+            let version = self.release_version(release);
+            let existing = existing_package
+                .get_version(version)
+                .filter(|v| !v.unverified);
+            let collides = match existing {
+                Some(existing) if existing.tag == release.tag() => continue,
+                Some(_) => true,
+                None => releases_by_version[version] > 1,
+            };
+            if collides {
+                warn!(
+                    tag = %release.tag(),
+                    version,
+                    "Stripping build metadata collides with another release"
+                );
+                failures.push(FetchFailure {
+                    package_id: existing_package.id.clone(),
+                    tag: release.tag().to_string(),
+                    reason: format!(
+                        "version '{}' without build metadata collides with another release",
+                        version
+                    ),
+                });
+                continue;
+            }
+            new_releases.push(release.clone());
+        }
+        new_releases
+    }
+
+    /// Returns true if `dependency` equals or is nested under an allowed prefix.
+    /// Prefixes may be written as `com.vrchat`, `com.vrchat.` or `com.vrchat.*`.
+    fn is_dependency_allowed(&self, dependency: &str) -> bool {
+        let prefixes = &self.config.allowed_dependency_prefixes;
+        prefixes.is_empty()
+            || prefixes.iter().any(|prefix| {
+                let prefix = prefix.trim_end_matches('*').trim_end_matches('.');
+                dependency == prefix
+                    || dependency
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+    }
+
     fn validate_package_manifest(
         &self,
         package: &Package,
@@ -69,8 +368,13 @@ impl<G: GitHubApi> PackageFetcher<G> {
             )));
         }
 
-        let expected_version = release.version();
-        if manifest.version != expected_version {
+        let expected_version = self.release_version(release);
+        let manifest_version = if self.config.strip_build_metadata {
+            strip_build_metadata(&manifest.version)
+        } else {
+            &manifest.version
+        };
+        if manifest_version != expected_version {
             return Err(Error::ConfigValidation(format!(
                 "package.json version '{}' does not match release tag '{}' (expected '{}') for package '{}'",
                 manifest.version,
@@ -89,6 +393,14 @@ impl<G: GitHubApi> PackageFetcher<G> {
             )));
         }
 
+        if self.config.strict_keywords && manifest.keywords.iter().any(|k| k.trim().is_empty()) {
+            return Err(Error::ConfigValidation(format!(
+                "package.json keywords contain an empty keyword for package '{}' (release '{}')",
+                package.id,
+                release.tag()
+            )));
+        }
+
         if manifest.display_name.trim().is_empty() {
             return Err(Error::ConfigValidation(format!(
                 "package.json is missing required field 'displayName' for package '{}' (release '{}')",
@@ -121,18 +433,38 @@ impl<G: GitHubApi> PackageFetcher<G> {
                     release.tag()
                 )));
             }
+            if self.config.require_unity {
+                return Err(Error::ConfigValidation(format!(
+                    "package.json is missing required field 'unity' for package '{}' (release '{}')",
+                    package.id,
+                    release.tag()
+                )));
+            }
             warn!(
                 package_id = %package.id,
                 release = %release.tag(),
                 "package.json is missing recommended field 'unity'"
             );
-        } else if let Err(e) = validation::validate_unity_version(&manifest.unity) {
-            return Err(Error::ConfigValidation(format!(
-                "package.json field 'unity' is invalid for package '{}' (release '{}'): {}",
-                package.id,
-                release.tag(),
-                e
-            )));
+        } else {
+            let unity = validation::parse_unity_version(&manifest.unity).map_err(|e| {
+                Error::ConfigValidation(format!(
+                    "package.json field 'unity' is invalid for package '{}' (release '{}'): {}",
+                    package.id,
+                    release.tag(),
+                    e
+                ))
+            })?;
+            if let Some(min_unity) = &self.config.min_unity
+                && unity < validation::parse_unity_version(min_unity)?
+            {
+                return Err(Error::ConfigValidation(format!(
+                    "package.json field 'unity' '{}' is below the minimum '{}' for package '{}' (release '{}')",
+                    manifest.unity,
+                    min_unity,
+                    package.id,
+                    release.tag()
+                )));
+            }
         }
 
         if !manifest.unity_release.trim().is_empty()
@@ -163,6 +495,66 @@ impl<G: GitHubApi> PackageFetcher<G> {
             )));
         }
 
+        let asset_host = release.asset_url().and_then(validation::url_host);
+        let zip_host = validation::url_host(&manifest.url);
+        if let (Some(asset_host), Some(zip_host)) = (asset_host, zip_host)
+            && asset_host != zip_host
+        {
+            if self.config.strict_url {
+                return Err(Error::ConfigValidation(format!(
+                    "package.json field 'url' host '{}' differs from the release asset host '{}' for package '{}' (release '{}')",
+                    zip_host,
+                    asset_host,
+                    package.id,
+                    release.tag()
+                )));
+            }
+            warn!(
+                package_id = %package.id,
+                release = %release.tag(),
+                %zip_host,
+                %asset_host,
+                "package.json url is on another host than the release asset"
+            );
+        }
+
+        let legacy_entries = [
+            ("legacyFolders", &manifest.legacy_folders),
+            ("legacyFiles", &manifest.legacy_files),
+        ];
+        for (field, entries) in legacy_entries {
+            for (path, guid) in entries {
+                let checked = validation::validate_legacy_path(path).and_then(|()| {
+                    if guid.is_empty() {
+                        Ok(())
+                    } else {
+                        validation::validate_legacy_path(guid)
+                    }
+                });
+                let Err(e) = checked else {
+                    continue;
+                };
+                if self.config.strict {
+                    return Err(Error::ConfigValidation(format!(
+                        "package.json field '{}' entry '{}' is invalid for package '{}' (release '{}'): {}",
+                        field,
+                        path,
+                        package.id,
+                        release.tag(),
+                        e
+                    )));
+                }
+                warn!(
+                    package_id = %package.id,
+                    release = %release.tag(),
+                    field,
+                    entry = %path,
+                    error = %e,
+                    "package.json has a malformed legacy path"
+                );
+            }
+        }
+
         for (dependency_name, dependency_version) in &manifest.dependencies {
             if let Err(e) = validation::validate_reverse_domain(dependency_name) {
                 return Err(Error::ConfigValidation(format!(
@@ -207,6 +599,15 @@ impl<G: GitHubApi> PackageFetcher<G> {
                     e
                 )));
             }
+
+            if !self.is_dependency_allowed(dependency_name) {
+                return Err(Error::ConfigValidation(format!(
+                    "package.json field 'vpmDependencies' references '{}', which is not in the dependency allowlist, for package '{}' (release '{}')",
+                    dependency_name,
+                    package.id,
+                    release.tag()
+                )));
+            }
         }
 
         if !manifest.zip_sha256.is_empty() && !Self::is_valid_sha256_hex(&manifest.zip_sha256) {
@@ -221,22 +622,21 @@ impl<G: GitHubApi> PackageFetcher<G> {
     }
 
     #[instrument(skip(self, manifest, lockfile, progress), fields(packages = manifest.packages.len()))]
-    pub async fn fetch<P: FetchProgressReporter>(
+    pub async fn fetch<P: FetchProgressReporter + ?Sized>(
         &self,
         manifest: &Manifest,
         lockfile: &mut Lockfile,
         progress: Option<&P>,
     ) -> Result<()> {
-        self.reconcile_lockfile(manifest, lockfile);
+        lockfile.reconcile(manifest);
 
         if manifest.packages.is_empty() {
             info!("No packages configured; skipping fetch");
             return Ok(());
         }
 
-        let package_concurrency = self.config.max_concurrent.clamp(1, manifest.packages.len());
-        let per_package_download_concurrency =
-            (self.config.max_concurrent / package_concurrency).max(1);
+        let (package_concurrency, per_package_download_concurrency) =
+            self.concurrency(manifest.packages.len());
 
         let existing_packages: HashMap<String, LockedPackage> = lockfile
             .packages
@@ -244,39 +644,82 @@ impl<G: GitHubApi> PackageFetcher<G> {
             .map(|pkg| (pkg.id.clone(), pkg.clone()))
             .collect();
 
-        let mut outcomes: Vec<(usize, Result<PackageFetchResult>)> =
-            stream::iter(manifest.packages.iter().enumerate())
-                .map(|(index, package)| {
-                    let existing_package =
-                        existing_packages
-                            .get(&package.id)
-                            .cloned()
-                            .unwrap_or(LockedPackage {
-                                id: package.id.clone(),
-                                repository: package.repository.clone(),
-                                versions: Vec::new(),
-                            });
-
-                    async move {
-                        (
-                            index,
-                            self.fetch_package(
-                                package,
-                                existing_package,
-                                per_package_download_concurrency,
-                                progress,
-                            )
-                            .await,
-                        )
+        let mut checkpoint = match &self.config.checkpoint_path {
+            Some(path) if self.config.resume => FetchCheckpoint::load(path)?,
+            _ => FetchCheckpoint::default(),
+        };
+        let resumed: HashMap<String, LockedPackage> = manifest
+            .packages
+            .iter()
+            .filter_map(|package| {
+                checkpoint
+                    .completed(package)
+                    .map(|completed| (package.id.clone(), completed.clone()))
+            })
+            .collect();
+        if !resumed.is_empty() {
+            info!(packages = resumed.len(), "Resuming fetch from checkpoint");
+        }
+
+        let mut results = stream::iter(manifest.packages.iter().enumerate())
+            .map(|(index, package)| {
+                let existing_package =
+                    existing_packages
+                        .get(&package.id)
+                        .cloned()
+                        .unwrap_or(LockedPackage {
+                            id: package.id.clone(),
+                            repository: package.repository.clone(),
+                            versions: Vec::new(),
+                            retained: false,
+                        });
+                let resumed_package = resumed.get(&package.id).cloned();
+
+                async move {
+                    if let Some(resumed_package) = resumed_package {
+                        info!(package_id = %package.id, "Package already completed in checkpoint");
+                        return (index, Ok(PackageFetchResult::unchanged(resumed_package)));
                     }
-                })
-                .buffer_unordered(package_concurrency)
-                .collect()
-                .await;
+                    if !self
+                        .should_fetch(&existing_package.id, !existing_package.versions.is_empty())
+                    {
+                        info!(package_id = %package.id, "Skipping package");
+                        return (index, Ok(PackageFetchResult::unchanged(existing_package)));
+                    }
+                    (
+                        index,
+                        self.fetch_package(
+                            package,
+                            existing_package,
+                            per_package_download_concurrency,
+                            progress,
+                        )
+                        .await,
+                    )
+                }
+            })
+            .buffer_unordered(package_concurrency);
+
+        let mut outcomes: Vec<(usize, Result<PackageFetchResult>)> =
+            Vec::with_capacity(manifest.packages.len());
+        while let Some((index, outcome)) = results.next().await {
+            if let (Some(path), Ok(result)) = (&self.config.checkpoint_path, &outcome)
+                && result.failures.is_empty()
+            {
+                checkpoint.record(LockedPackage {
+                    id: result.package_id.clone(),
+                    repository: manifest.packages[index].repository.clone(),
+                    versions: result.versions.clone(),
+                    retained: false,
+                });
+                checkpoint.save(path)?;
+            }
+            outcomes.push((index, outcome));
+        }
 
         outcomes.sort_by_key(|(index, _)| *index);
 
-        let mut total_failed = 0usize;
+        let mut failures = Vec::new();
 
         for (_, outcome) in outcomes {
             let outcome = outcome?;
@@ -291,21 +734,33 @@ impl<G: GitHubApi> PackageFetcher<G> {
 
             locked_pkg.versions = outcome.versions;
             if let Some(progress) = progress {
+                if !outcome.failures.is_empty() {
+                    progress.on_failed(&locked_pkg.id, outcome.failures.len());
+                }
                 progress.on_done(&locked_pkg.id, outcome.existing_count, outcome.new_count);
             }
-            total_failed += outcome.failed_count;
             info!(
                 package_id = %locked_pkg.id,
                 total_versions = locked_pkg.versions.len(),
                 new_versions = outcome.new_count,
-                failed_versions = outcome.failed_count,
+                failed_versions = outcome.failures.len(),
                 "Package fetch completed"
             );
+            failures.extend(outcome.failures);
+        }
+
+        for (url, package_ids) in shared_asset_urls(lockfile) {
+            warn!(
+                url = %url,
+                package_ids = %package_ids.join(", "),
+                "Asset URL is locked under multiple packages"
+            );
         }
 
-        if total_failed > 0 {
+        if !failures.is_empty() {
             return Err(Error::FetchPartialFailure {
-                count: total_failed,
+                failures,
+                lock_saved: false,
             });
         }
 
@@ -316,38 +771,134 @@ impl<G: GitHubApi> PackageFetcher<G> {
         Ok(())
     }
 
-    /// Syncs lockfile with manifest: removes stale packages, inserts new ones,
-    /// clears versions when a repository changes, and reorders to match manifest.
-    fn reconcile_lockfile(&self, manifest: &Manifest, lockfile: &mut Lockfile) {
-        let manifest_order: HashMap<String, usize> = manifest
+    /// Reports what `fetch` would change without downloading assets or
+    /// modifying `lockfile`. Only release listings are requested.
+    ///
+    /// Packages removed from the manifest are listed after the configured ones.
+    #[instrument(skip(self, manifest, lockfile), fields(packages = manifest.packages.len()))]
+    pub async fn plan(&self, manifest: &Manifest, lockfile: &Lockfile) -> Result<Vec<PackagePlan>> {
+        let mut reconciled = lockfile.clone();
+        reconciled.reconcile(manifest);
+
+        let package_concurrency = self.config.max_concurrent.max(1);
+        let planned: Vec<Result<PackagePlan>> =
+            stream::iter(manifest.packages.iter().zip(reconciled.packages.iter()))
+                .map(|(package, reconciled_package)| {
+                    self.plan_package(
+                        package,
+                        lockfile.get_package(&package.id),
+                        reconciled_package,
+                    )
+                })
+                .buffered(package_concurrency)
+                .collect()
+                .await;
+        let mut plans = planned.into_iter().collect::<Result<Vec<_>>>()?;
+
+        for removed in lockfile
             .packages
             .iter()
-            .enumerate()
-            .map(|(index, pkg)| (pkg.id.clone(), index))
+            .filter(|p| reconciled.get_package(&p.id).is_none())
+            .filter(|p| !p.retained)
+        {
+            plans.push(PackagePlan {
+                package_id: removed.id.clone(),
+                pruned: removed.versions.iter().map(|v| v.version.clone()).collect(),
+                ..Default::default()
+            });
+        }
+
+        Ok(plans)
+    }
+
+    async fn plan_package(
+        &self,
+        package: &Package,
+        original: Option<&LockedPackage>,
+        reconciled: &LockedPackage,
+    ) -> Result<PackagePlan> {
+        let mut existing_package = reconciled.clone();
+        let mut pruned: Vec<String> = original
+            .map(|p| p.versions.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|v| reconciled.get_version(&v.version).is_none())
+            .map(|v| v.version.clone())
             .collect();
 
-        lockfile
-            .packages
-            .retain(|pkg| manifest_order.contains_key(&pkg.id));
+        if !self.should_fetch(&existing_package.id, !existing_package.versions.is_empty()) {
+            return Ok(PackagePlan {
+                package_id: package.id.clone(),
+                kept: existing_package
+                    .versions
+                    .iter()
+                    .map(|v| v.version.clone())
+                    .collect(),
+                pruned,
+                ..Default::default()
+            });
+        }
 
-        for package in &manifest.packages {
-            let locked_pkg = lockfile.get_or_insert_package(&package.id, &package.repository);
-            if locked_pkg.repository != package.repository {
-                locked_pkg.repository = package.repository.clone();
-                locked_pkg.versions.clear();
+        existing_package.versions.retain(|v| {
+            let allowed = self.keeps_locked_version(package, &v.version);
+            if !allowed {
+                pruned.push(v.version.clone());
             }
-        }
+            allowed
+        });
+        let existing_versions = existing_package.existing_versions();
 
-        lockfile
-            .packages
-            .sort_by_key(|pkg| manifest_order.get(&pkg.id).copied().unwrap_or(usize::MAX));
+        let mut releases = self
+            .github
+            .get_releases(
+                &package.repository,
+                package.asset_name(&self.config.asset_name),
+            )
+            .await?;
+        releases.retain(|r| self.allows_release(package, r));
+
+        let new_releases: Vec<Release> = if self.config.strip_build_metadata {
+            self.filter_new_stripped(&releases, &existing_package, &mut Vec::new())
+        } else {
+            Release::filter_new(&releases, &existing_versions)
+                .into_iter()
+                .cloned()
+                .collect()
+        };
+
+        Ok(PackagePlan {
+            package_id: package.id.clone(),
+            added: new_releases
+                .iter()
+                .map(|r| self.release_version(r).to_string())
+                .collect(),
+            kept: existing_package
+                .versions
+                .iter()
+                .map(|v| v.version.clone())
+                .collect(),
+            pruned,
+        })
+    }
+
+    /// Whether a release may be fetched for `package`.
+    fn allows_release(&self, package: &Package, release: &Release) -> bool {
+        let version = self.release_version(release);
+        package.allows_version(version) && !(self.config.no_prerelease && is_prerelease(version))
+    }
+
+    /// Whether an already locked version stays in the lock file.
+    fn keeps_locked_version(&self, package: &Package, version: &str) -> bool {
+        let pruned_prerelease =
+            self.config.no_prerelease && !self.config.keep_existing && is_prerelease(version);
+        package.allows_version(version) && !pruned_prerelease
     }
 
     #[instrument(skip(self, existing_package, progress), fields(package_id = %package.id, repo = %package.repository))]
-    async fn fetch_package<P: FetchProgressReporter>(
+    async fn fetch_package<P: FetchProgressReporter + ?Sized>(
         &self,
         package: &Package,
-        existing_package: LockedPackage,
+        mut existing_package: LockedPackage,
         download_concurrency: usize,
         progress: Option<&P>,
     ) -> Result<PackageFetchResult> {
@@ -356,94 +907,161 @@ impl<G: GitHubApi> PackageFetcher<G> {
             progress.on_fetching_releases(&package.id);
         }
 
+        existing_package
+            .versions
+            .retain(|v| self.keeps_locked_version(package, &v.version));
         let existing_versions = existing_package.existing_versions();
         let existing_count = existing_versions.len();
 
-        let releases = self
+        let mut releases = self
             .github
-            .get_releases(&package.repository, &self.config.asset_name)
+            .get_releases(
+                &package.repository,
+                package.asset_name(&self.config.asset_name),
+            )
             .await?;
         info!(releases = releases.len(), "Found releases");
+        if !package.versions.is_empty()
+            || package.version_range.is_some()
+            || self.config.no_prerelease
+        {
+            releases.retain(|r| self.allows_release(package, r));
+            info!(
+                releases = releases.len(),
+                "Releases in versions allowlist or range"
+            );
+        }
 
-        let new_releases: Vec<Release> = Release::filter_new(&releases, &existing_versions)
-            .into_iter()
-            .cloned()
-            .collect();
+        let mut failures = Vec::new();
+        let new_releases: Vec<Release> = if self.config.strip_build_metadata {
+            self.filter_new_stripped(&releases, &existing_package, &mut failures)
+        } else {
+            Release::filter_new(&releases, &existing_versions)
+                .into_iter()
+                .cloned()
+                .collect()
+        };
         info!(new_versions = new_releases.len(), "New versions to fetch");
 
         let mut fetched_versions = Vec::new();
         let planned_count = new_releases.len();
-        let mut failed_count = 0usize;
 
         if !new_releases.is_empty() {
-            let version_list: Vec<_> = new_releases.iter().map(|r| r.version()).collect();
+            let version_list: Vec<_> = new_releases
+                .iter()
+                .map(|r| self.release_version(r))
+                .collect();
             info!(versions = ?version_list, "Downloading package.json files");
             if let Some(progress) = progress {
                 progress.on_downloading(&package.id, planned_count);
             }
 
+            let retry_observer = progress.map(|progress| PackageRetryObserver {
+                package_id: &package.id,
+                progress,
+            });
             let results = self
                 .github
-                .download_assets(new_releases, download_concurrency, self.config.max_retries)
+                .download_assets(
+                    new_releases,
+                    download_concurrency,
+                    self.config.max_retries,
+                    retry_observer.as_ref().map(|o| o as &dyn RetryObserver),
+                )
                 .await;
 
             for (release, result) in results {
+                let mut fail = |reason: String| {
+                    failures.push(FetchFailure {
+                        package_id: package.id.clone(),
+                        tag: release.tag().to_string(),
+                        reason,
+                    })
+                };
                 match result {
                     Ok(raw_content) => {
                         let asset_url = release.asset_url().unwrap_or_default().to_string();
                         match self.parse_package_manifest(&raw_content, release.asset_url()) {
-                            Ok(version_output) => {
+                            Ok(mut version_output) => {
                                 match self.validate_package_manifest(
                                     package,
                                     &release,
                                     &version_output,
                                 ) {
                                     Ok(()) => {
-                                        let locked_version = LockedVersion::new(
+                                        version_output.keywords =
+                                            version_output.normalized_keywords();
+                                        if self.config.strip_build_metadata {
+                                            version_output.version =
+                                                strip_build_metadata(&version_output.version)
+                                                    .to_string();
+                                        }
+                                        let mut locked_version = LockedVersion::new(
                                             release.tag().to_string(),
                                             asset_url,
                                             &raw_content,
                                             version_output,
+                                        )
+                                        .with_hash_algorithm(
+                                            &raw_content,
+                                            self.config.hash_algorithm,
                                         );
+                                        if self.config.store_raw {
+                                            locked_version =
+                                                locked_version.with_raw_manifest(&raw_content);
+                                        }
                                         fetched_versions.push(locked_version);
                                     }
                                     Err(e) => {
-                                        failed_count += 1;
                                         warn!(
                                             version = %release.version(),
                                             error = %e,
                                             "Rejected package.json with invalid metadata"
                                         );
+                                        fail(format!("invalid package.json: {}", e));
                                     }
                                 }
                             }
                             Err(e) => {
-                                failed_count += 1;
                                 warn!(
                                     version = %release.version(),
                                     error = %e,
                                     "Failed to parse package.json"
                                 );
+                                fail(format!("failed to parse package.json: {}", e));
                             }
                         }
                     }
                     Err(e) => {
-                        failed_count += 1;
                         warn!(
                             version = %release.version(),
                             error = %e,
                             "Failed to fetch package.json"
                         );
+                        fail(format!("failed to fetch package.json: {}", e));
                     }
                 }
             }
+
+            if self.config.verify_zip {
+                fetched_versions = self
+                    .verify_zips(
+                        &package.id,
+                        fetched_versions,
+                        download_concurrency,
+                        &mut failures,
+                    )
+                    .await;
+            }
         }
 
         // Maintain release order (newest first) for consistent output
+        let mut ordered_versions = HashSet::new();
         let release_order: Vec<String> = releases
             .iter()
             .filter(|r| r.asset_url().is_some())
-            .map(|r| r.version().to_string())
+            .map(|r| self.release_version(r).to_string())
+            .filter(|version| ordered_versions.insert(version.clone()))
             .collect();
 
         let all_versions: Vec<LockedVersion> = if release_order.is_empty() {
@@ -489,11 +1107,41 @@ impl<G: GitHubApi> PackageFetcher<G> {
             versions: all_versions,
             existing_count,
             new_count,
-            failed_count,
+            failures,
         })
     }
 }
 
+/// Returns asset URLs that are locked under more than one package ID,
+/// paired with those IDs in lockfile order.
+fn shared_asset_urls(lockfile: &Lockfile) -> Vec<(String, Vec<String>)> {
+    let mut owners: IndexMap<&str, Vec<&str>> = IndexMap::new();
+    for package in &lockfile.packages {
+        for version in &package.versions {
+            let ids = owners.entry(version.url.as_str()).or_default();
+            if !ids.contains(&package.id.as_str()) {
+                ids.push(package.id.as_str());
+            }
+        }
+    }
+
+    owners
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(url, ids)| {
+            (
+                url.to_string(),
+                ids.into_iter().map(str::to_string).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Returns true if `version` is semver with a prerelease component, e.g. `1.0.0-rc.1`.
+fn is_prerelease(version: &str) -> bool {
+    Version::parse(version).is_ok_and(|v| !v.pre.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -502,20 +1150,40 @@ mod tests {
     use crate::error::Error;
     use crate::lock::{PackageAuthor, PackageManifest};
     use async_trait::async_trait;
-    use indexmap::IndexMap;
     use std::collections::HashSet;
     use std::sync::Mutex;
     use std::time::Duration;
 
+    #[derive(Default)]
     struct FakeGitHub {
         releases: HashMap<String, Vec<Release>>,
         assets: HashMap<String, String>,
+        files: HashMap<String, Vec<u8>>,
         delays_ms: HashMap<String, u64>,
+        retries: HashMap<String, u32>,
+        failing_repos: HashSet<String>,
+        release_calls: Mutex<Vec<String>>,
+        asset_name_calls: Mutex<Vec<String>>,
+        download_calls: Mutex<usize>,
+    }
+
+    impl FakeGitHub {
+        fn release_calls(&self) -> Vec<String> {
+            self.release_calls.lock().unwrap().clone()
+        }
     }
 
     #[async_trait]
     impl GitHubApi for FakeGitHub {
-        async fn get_releases(&self, repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        async fn get_releases(&self, repo: &Repository, asset_name: &str) -> Result<Vec<Release>> {
+            self.release_calls.lock().unwrap().push(repo.to_string());
+            self.asset_name_calls
+                .lock()
+                .unwrap()
+                .push(asset_name.to_string());
+            if self.failing_repos.contains(&repo.to_string()) {
+                return Err(Error::RepositoryNotFound(repo.to_string()));
+            }
             if let Some(ms) = self.delays_ms.get(&repo.to_string()) {
                 tokio::time::sleep(Duration::from_millis(*ms)).await;
             }
@@ -526,15 +1194,22 @@ mod tests {
                 .unwrap_or_default())
         }
 
-        async fn download_assets(
+        async fn download_assets<'a>(
             &self,
             releases: Vec<Release>,
             _max_concurrent: usize,
             _max_retries: u32,
+            retry_observer: Option<&'a dyn RetryObserver>,
         ) -> Vec<(Release, Result<String>)> {
+            *self.download_calls.lock().unwrap() += 1;
             releases
                 .into_iter()
                 .map(|release| {
+                    if let (Some(url), Some(observer)) = (release.asset_url(), retry_observer) {
+                        for attempt in 1..=self.retries.get(url).copied().unwrap_or(0) {
+                            observer.on_retry(url, attempt);
+                        }
+                    }
                     let result = match release.asset_url() {
                         Some(url) => self.assets.get(url).cloned().ok_or_else(|| {
                             Error::ConfigValidation(format!("missing test asset: {url}"))
@@ -551,12 +1226,21 @@ mod tests {
         async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
             Ok(())
         }
+
+        async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+            self.files
+                .get(url)
+                .cloned()
+                .ok_or_else(|| Error::ConfigValidation(format!("missing test file: {url}")))
+        }
     }
 
     enum Event {
         Fetching(String),
         Downloading(String, usize),
         Done(String, usize, usize),
+        Failed(String, usize),
+        Retrying(String, u32),
     }
 
     #[derive(Default)]
@@ -577,6 +1261,18 @@ mod tests {
                 .collect()
         }
 
+        fn retry_events(&self) -> Vec<(String, u32)> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Retrying(pkg, attempt) => Some((pkg.clone(), *attempt)),
+                    _ => None,
+                })
+                .collect()
+        }
+
         fn seen_fetching_packages(&self) -> HashSet<String> {
             self.events
                 .lock()
@@ -623,6 +1319,20 @@ mod tests {
                 .unwrap()
                 .push(Event::Done(package_id.to_string(), existing, new));
         }
+
+        fn on_failed(&self, package_id: &str, failed: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(Event::Failed(package_id.to_string(), failed));
+        }
+
+        fn on_retrying(&self, package_id: &str, attempt: u32) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(Event::Retrying(package_id.to_string(), attempt));
+        }
     }
 
     fn repo(s: &str) -> Repository {
@@ -677,6 +1387,9 @@ mod tests {
 
     fn manifest_two_packages() -> Manifest {
         Manifest {
+            include: Vec::new(),
+            defaults: Default::default(),
+            registry: Default::default(),
             vpm: Vpm {
                 id: "com.test.vpm".to_string(),
                 name: "Test".to_string(),
@@ -687,12 +1400,24 @@ mod tests {
                 Package {
                     id: "com.test.vpm.pkg1".to_string(),
                     repository: repo("owner1/repo1"),
+                    suffix: None,
+                    tags: Vec::new(),
+                    versions: Vec::new(),
+                    version_range: None,
+                    asset_name: None,
                 },
                 Package {
                     id: "com.test.vpm.pkg2".to_string(),
                     repository: repo("owner2/repo2"),
+                    suffix: None,
+                    tags: Vec::new(),
+                    versions: Vec::new(),
+                    version_range: None,
+                    asset_name: None,
                 },
             ],
+            included: Default::default(),
+            format: Default::default(),
         }
     }
 
@@ -715,15 +1440,36 @@ mod tests {
                     "https://download.example/pkg1-v1.zip",
                 ),
             )],
+            retained: false,
         });
         lockfile.packages.push(LockedPackage {
             id: "com.test.vpm.pkg2".to_string(),
             repository: repo("owner2/repo2"),
             versions: vec![],
+            retained: false,
         });
         lockfile
     }
 
+    #[test]
+    fn concurrency_splits_max_concurrent_unless_overridden() {
+        let concurrency = |config: FetcherConfig, package_count| {
+            PackageFetcher::new(Arc::new(FakeGitHub::default()), config).concurrency(package_count)
+        };
+        let config = |package_concurrency, download_concurrency| FetcherConfig {
+            max_concurrent: 8,
+            package_concurrency,
+            download_concurrency,
+            ..Default::default()
+        };
+
+        assert_eq!(concurrency(config(None, None), 2), (2, 4));
+        assert_eq!(concurrency(config(None, None), 20), (8, 1));
+        assert_eq!(concurrency(config(Some(1), None), 20), (1, 8));
+        assert_eq!(concurrency(config(Some(10), Some(6)), 20), (10, 6));
+        assert_eq!(concurrency(config(Some(0), Some(0)), 20), (1, 1));
+    }
+
     #[tokio::test]
     async fn fetch_reports_progress_and_counts() {
         let manifest = manifest_two_packages();
@@ -780,6 +1526,7 @@ mod tests {
                 ),
             ]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -788,6 +1535,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -864,6 +1612,7 @@ mod tests {
                 ("owner1/repo1".to_string(), 60),
                 ("owner2/repo2".to_string(), 0),
             ]),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -872,6 +1621,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -916,6 +1666,7 @@ mod tests {
                 ),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -924,16 +1675,42 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
+        let progress = TestProgress::default();
         let result = fetcher
-            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .fetch(&manifest, &mut lockfile, Some(&progress))
             .await;
-        assert!(matches!(
-            result,
-            Err(Error::FetchPartialFailure { count: 1 })
-        ));
+        let Err(Error::FetchPartialFailure { failures, .. }) = &result else {
+            panic!("expected a partial failure, got {:?}", result);
+        };
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].package_id, "com.test.vpm.pkg2");
+        assert_eq!(failures[0].tag, "v1.0.0");
+        assert!(
+            failures[0]
+                .reason
+                .starts_with("failed to fetch package.json")
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("\n  - com.test.vpm.pkg2 v1.0.0: failed to fetch package.json")
+        );
+        let failed: Vec<_> = progress
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                Event::Failed(pkg, count) => Some((pkg.clone(), *count)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failed, vec![("com.test.vpm.pkg2".to_string(), 1)]);
     }
 
     #[tokio::test]
@@ -954,6 +1731,7 @@ mod tests {
             ]),
             assets: HashMap::new(),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -962,6 +1740,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1003,6 +1782,7 @@ mod tests {
                 ),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1011,6 +1791,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1026,30 +1807,84 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn fetch_rejects_manifest_with_mismatched_package_name() {
+    async fn fetch_relocks_unverified_versions_from_their_releases() {
         let manifest = manifest_two_packages();
         let mut lockfile = initial_lockfile();
+        let migrated = lockfile.packages[0].versions.remove(0).unverified();
+        lockfile.packages[0].versions.push(migrated);
 
         let github = Arc::new(FakeGitHub {
             releases: HashMap::from([
                 (
                     "owner1/repo1".to_string(),
                     vec![Release::new(
-                        "v2.0.0".to_string(),
-                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v1-real.json".to_string()),
                     )],
                 ),
                 ("owner2/repo2".to_string(), Vec::new()),
             ]),
             assets: HashMap::from([(
-                "https://assets.example/pkg1-v2.json".to_string(),
+                "https://assets.example/pkg1-v1-real.json".to_string(),
                 version_json(
-                    "com.test.vpm.wrong",
-                    "2.0.0",
-                    "https://download.example/pkg1-v2.zip",
-                ),
-            )]),
+                    "com.test.vpm.pkg1",
+                    "1.0.0",
+                    "https://download.example/pkg1-v1.zip",
+                ),
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                max_concurrent: 4,
+                max_retries: 0,
+                asset_name: "package.json".to_string(),
+                ..Default::default()
+            },
+        );
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        assert_eq!(pkg1.versions.len(), 1);
+        assert!(!pkg1.versions[0].unverified);
+        assert_eq!(
+            pkg1.versions[0].url,
+            "https://assets.example/pkg1-v1-real.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_manifest_with_mismatched_package_name() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([
+                (
+                    "owner1/repo1".to_string(),
+                    vec![Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    )],
+                ),
+                ("owner2/repo2".to_string(), Vec::new()),
+            ]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg1-v2.json".to_string(),
+                version_json(
+                    "com.test.vpm.wrong",
+                    "2.0.0",
+                    "https://download.example/pkg1-v2.zip",
+                ),
+            )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1058,6 +1893,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1066,7 +1902,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1099,6 +1935,7 @@ mod tests {
                 ),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1107,6 +1944,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1115,7 +1953,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1153,6 +1991,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1161,6 +2000,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1169,7 +2009,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1206,6 +2046,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1214,6 +2055,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1222,7 +2064,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1260,6 +2102,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1268,6 +2111,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1313,6 +2157,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1321,6 +2166,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1365,6 +2211,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1373,6 +2220,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1381,7 +2229,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1419,6 +2267,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1427,6 +2276,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1471,6 +2321,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1479,6 +2330,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1487,7 +2339,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1526,6 +2378,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1534,6 +2387,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1542,7 +2396,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1580,6 +2434,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1588,6 +2443,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1596,7 +2452,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1634,6 +2490,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1642,6 +2499,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1650,7 +2508,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1688,6 +2546,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1696,6 +2555,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1704,7 +2564,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1745,6 +2605,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1753,6 +2614,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1761,7 +2623,7 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
@@ -1802,6 +2664,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1810,6 +2673,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1855,6 +2719,7 @@ mod tests {
                 .to_string(),
             )]),
             delays_ms: HashMap::new(),
+            ..Default::default()
         });
 
         let fetcher = PackageFetcher::new(
@@ -1863,6 +2728,7 @@ mod tests {
                 max_concurrent: 4,
                 max_retries: 0,
                 asset_name: "package.json".to_string(),
+                ..Default::default()
             },
         );
 
@@ -1871,11 +2737,1264 @@ mod tests {
             .await;
         assert!(matches!(
             result,
-            Err(Error::FetchPartialFailure { count: 1 })
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
         ));
 
         let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
         assert_eq!(pkg1.versions.len(), 1);
         assert_eq!(pkg1.versions[0].version, "1.0.0");
     }
+
+    #[tokio::test]
+    async fn fetch_only_missing_skips_packages_with_locked_versions() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([
+                (
+                    "owner1/repo1".to_string(),
+                    vec![Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    )],
+                ),
+                (
+                    "owner2/repo2".to_string(),
+                    vec![Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg2-v1.json".to_string()),
+                    )],
+                ),
+            ]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg2-v1.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.0.0",
+                    "https://download.example/pkg2-v1.zip",
+                ),
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github.clone(),
+            FetcherConfig {
+                max_concurrent: 4,
+                max_retries: 0,
+                only_missing: true,
+                ..Default::default()
+            },
+        );
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        assert_eq!(github.release_calls(), vec!["owner2/repo2".to_string()]);
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        assert_eq!(pkg1.versions.len(), 1);
+        assert_eq!(pkg1.versions[0].version, "1.0.0");
+
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert_eq!(pkg2.versions.len(), 1);
+        assert_eq!(pkg2.versions[0].version, "1.0.0");
+    }
+
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl LogBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogBuffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_warns_when_asset_url_is_locked_under_multiple_packages() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+        let shared_url = "https://assets.example/pkg1-v1.json";
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some(shared_url.to_string()),
+                )],
+            )]),
+            assets: HashMap::from([(
+                shared_url.to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.0.0",
+                    "https://download.example/pkg2-v1.zip",
+                ),
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                max_retries: 0,
+                ..Default::default()
+            },
+        );
+
+        let logs = LogBuffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .with_max_level(tracing::Level::WARN)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            shared_asset_urls(&lockfile),
+            vec![(
+                shared_url.to_string(),
+                vec![
+                    "com.test.vpm.pkg1".to_string(),
+                    "com.test.vpm.pkg2".to_string(),
+                ],
+            )]
+        );
+
+        let output = logs.contents();
+        assert!(output.contains("Asset URL is locked under multiple packages"));
+        assert!(output.contains(shared_url));
+        assert!(output.contains("com.test.vpm.pkg1, com.test.vpm.pkg2"));
+    }
+
+    #[tokio::test]
+    async fn fetch_reports_download_retries_to_progress() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+        let progress = TestProgress::default();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some("https://assets.example/pkg2-v1.json".to_string()),
+                )],
+            )]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg2-v1.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.0.0",
+                    "https://download.example/pkg2-v1.zip",
+                ),
+            )]),
+            retries: HashMap::from([("https://assets.example/pkg2-v1.json".to_string(), 2)]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(github, FetcherConfig::default());
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, Some(&progress))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            progress.retry_events(),
+            vec![
+                ("com.test.vpm.pkg2".to_string(), 1),
+                ("com.test.vpm.pkg2".to_string(), 2),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_resumes_from_checkpoint_after_interrupted_run() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let checkpoint_path = dir.path().join("voyager.checkpoint");
+        let manifest = manifest_two_packages();
+
+        let releases = HashMap::from([
+            (
+                "owner1/repo1".to_string(),
+                vec![Release::new(
+                    "v2.0.0".to_string(),
+                    Some("https://assets.example/pkg1-v2.json".to_string()),
+                )],
+            ),
+            (
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some("https://assets.example/pkg2-v1.json".to_string()),
+                )],
+            ),
+        ]);
+        let assets = HashMap::from([
+            (
+                "https://assets.example/pkg1-v2.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg1",
+                    "2.0.0",
+                    "https://download.example/pkg1-v2.zip",
+                ),
+            ),
+            (
+                "https://assets.example/pkg2-v1.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.0.0",
+                    "https://download.example/pkg2-v1.zip",
+                ),
+            ),
+        ]);
+
+        let interrupted = Arc::new(FakeGitHub {
+            releases: releases.clone(),
+            assets: assets.clone(),
+            failing_repos: HashSet::from(["owner2/repo2".to_string()]),
+            ..Default::default()
+        });
+        let fetcher = PackageFetcher::new(
+            interrupted.clone(),
+            FetcherConfig {
+                max_concurrent: 1,
+                checkpoint_path: Some(checkpoint_path.clone()),
+                ..Default::default()
+            },
+        );
+        let mut lockfile = initial_lockfile();
+        let result = fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await;
+        assert!(matches!(result, Err(Error::RepositoryNotFound(_))));
+        assert!(checkpoint_path.exists());
+
+        let resumed = Arc::new(FakeGitHub {
+            releases,
+            assets,
+            ..Default::default()
+        });
+        let fetcher = PackageFetcher::new(
+            resumed.clone(),
+            FetcherConfig {
+                max_concurrent: 1,
+                checkpoint_path: Some(checkpoint_path.clone()),
+                resume: true,
+                ..Default::default()
+            },
+        );
+        let mut lockfile = initial_lockfile();
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        assert_eq!(resumed.release_calls(), vec!["owner2/repo2".to_string()]);
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        let pkg1_versions: Vec<_> = pkg1.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(pkg1_versions, vec!["2.0.0", "1.0.0"]);
+
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert_eq!(pkg2.versions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_strips_build_metadata_and_keeps_original_tag() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.2.3+build.5".to_string(),
+                    Some("https://assets.example/pkg2-build5.json".to_string()),
+                )],
+            )]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg2-build5.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.2.3+build.5",
+                    "https://download.example/pkg2-build5.zip",
+                ),
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                strip_build_metadata: true,
+                ..Default::default()
+            },
+        );
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert_eq!(pkg2.versions.len(), 1);
+        assert_eq!(pkg2.versions[0].version, "1.2.3");
+        assert_eq!(pkg2.versions[0].manifest.version, "1.2.3");
+        assert_eq!(pkg2.versions[0].tag, "v1.2.3+build.5");
+    }
+
+    #[tokio::test]
+    async fn fetch_normalizes_keywords_and_rejects_blank_ones_when_strict() {
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some("https://assets.example/pkg2-v1.json".to_string()),
+                )],
+            )]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg2-v1.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg2",
+                    "1.0.0",
+                    "https://download.example/pkg2-v1.zip",
+                )
+                .replace(
+                    r#""unity""#,
+                    r#""keywords": ["Avatar ", "avatar", " "], "unity""#,
+                ),
+            )]),
+            ..Default::default()
+        });
+        let fetch = |strict_keywords| {
+            let github = github.clone();
+            async move {
+                let mut lockfile = initial_lockfile();
+                let fetcher = PackageFetcher::new(
+                    github,
+                    FetcherConfig {
+                        strict_keywords,
+                        ..Default::default()
+                    },
+                );
+                let result = fetcher
+                    .fetch(
+                        &manifest_two_packages(),
+                        &mut lockfile,
+                        None::<&TestProgress>,
+                    )
+                    .await;
+                (result, lockfile)
+            }
+        };
+
+        let (result, lockfile) = fetch(false).await;
+        result.unwrap();
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert_eq!(pkg2.versions[0].manifest.keywords, ["avatar"]);
+
+        let (result, _) = fetch(true).await;
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_no_prerelease_skips_and_prunes_prereleases_unless_kept() {
+        let tags = ["v2.1.0-rc.1", "v2.0.0", "v2.0.0-beta.2", "v1.0.0"];
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                tags.iter()
+                    .map(|tag| {
+                        Release::new(
+                            tag.to_string(),
+                            Some(format!("https://assets.example/pkg1-{tag}.json")),
+                        )
+                    })
+                    .collect(),
+            )]),
+            assets: tags
+                .iter()
+                .map(|tag| {
+                    (
+                        format!("https://assets.example/pkg1-{tag}.json"),
+                        version_json(
+                            "com.test.vpm.pkg1",
+                            &tag[1..],
+                            &format!("https://download.example/pkg1-{tag}.zip"),
+                        ),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        });
+        let fetch = |keep_existing| {
+            let github = github.clone();
+            async move {
+                let mut lockfile = initial_lockfile();
+                lockfile.packages[0].versions.insert(
+                    0,
+                    LockedVersion::new(
+                        "v1.5.0-beta.1".to_string(),
+                        "https://assets.example/pkg1-v1.5.0-beta.1.json".to_string(),
+                        "{}",
+                        version_output(
+                            "com.test.vpm.pkg1",
+                            "1.5.0-beta.1",
+                            "https://download.example/pkg1-v1.5.0-beta.1.zip",
+                        ),
+                    ),
+                );
+                let fetcher = PackageFetcher::new(
+                    github,
+                    FetcherConfig {
+                        no_prerelease: true,
+                        keep_existing,
+                        ..Default::default()
+                    },
+                );
+                fetcher
+                    .fetch(
+                        &manifest_two_packages(),
+                        &mut lockfile,
+                        None::<&TestProgress>,
+                    )
+                    .await
+                    .unwrap();
+                let mut versions = locked_versions(&lockfile);
+                versions.sort();
+                versions
+            }
+        };
+
+        assert_eq!(fetch(false).await, ["1.0.0", "2.0.0"]);
+        assert_eq!(fetch(true).await, ["1.0.0", "1.5.0-beta.1", "2.0.0"]);
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_zip_url_on_other_host_only_when_strict() {
+        let fetch = |zip_url: &'static str, strict_url| async move {
+            let github = Arc::new(FakeGitHub {
+                releases: HashMap::from([(
+                    "owner2/repo2".to_string(),
+                    vec![Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg2-v1.json".to_string()),
+                    )],
+                )]),
+                assets: HashMap::from([(
+                    "https://assets.example/pkg2-v1.json".to_string(),
+                    version_json("com.test.vpm.pkg2", "1.0.0", zip_url),
+                )]),
+                ..Default::default()
+            });
+            let mut lockfile = initial_lockfile();
+            let fetcher = PackageFetcher::new(
+                github,
+                FetcherConfig {
+                    strict_url,
+                    ..Default::default()
+                },
+            );
+            fetcher
+                .fetch(
+                    &manifest_two_packages(),
+                    &mut lockfile,
+                    None::<&TestProgress>,
+                )
+                .await
+        };
+
+        fetch("https://download.example/pkg2-v1.zip", false)
+            .await
+            .unwrap();
+        fetch("https://assets.example/pkg2-v1.zip", true)
+            .await
+            .unwrap();
+        assert!(matches!(
+            fetch("https://download.example/pkg2-v1.zip", true).await,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_backslash_legacy_paths_only_when_strict() {
+        let fetch = |strict| async move {
+            let mut raw: serde_json::Value = serde_json::from_str(&version_json(
+                "com.test.vpm.pkg2",
+                "1.0.0",
+                "https://assets.example/pkg2-v1.zip",
+            ))
+            .unwrap();
+            raw["legacyFolders"] = serde_json::json!({ "Assets\\Example\\Editor": "" });
+            raw["legacyFiles"] = serde_json::json!({ "Assets/Example/Runtime.cs": "" });
+            let github = Arc::new(FakeGitHub {
+                releases: HashMap::from([(
+                    "owner2/repo2".to_string(),
+                    vec![Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg2-v1.json".to_string()),
+                    )],
+                )]),
+                assets: HashMap::from([(
+                    "https://assets.example/pkg2-v1.json".to_string(),
+                    raw.to_string(),
+                )]),
+                ..Default::default()
+            });
+            let mut lockfile = initial_lockfile();
+            let fetcher = PackageFetcher::new(
+                github,
+                FetcherConfig {
+                    strict,
+                    ..Default::default()
+                },
+            );
+            fetcher
+                .fetch(
+                    &manifest_two_packages(),
+                    &mut lockfile,
+                    None::<&TestProgress>,
+                )
+                .await
+                .map(|_| lockfile)
+        };
+
+        let lockfile = fetch(false).await.unwrap();
+        let pkg2 = lockfile
+            .packages
+            .iter()
+            .find(|p| p.id == "com.test.vpm.pkg2")
+            .unwrap();
+        assert!(
+            pkg2.versions[0]
+                .manifest
+                .legacy_folders
+                .contains_key("Assets\\Example\\Editor")
+        );
+        assert!(matches!(
+            fetch(true).await,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_hashes_new_versions_with_configured_algorithm() {
+        let raw = version_json(
+            "com.test.vpm.pkg2",
+            "1.0.0",
+            "https://download.example/pkg2-v1.zip",
+        );
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some("https://assets.example/pkg2-v1.json".to_string()),
+                )],
+            )]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg2-v1.json".to_string(),
+                raw.clone(),
+            )]),
+            ..Default::default()
+        });
+        let mut lockfile = initial_lockfile();
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                hash_algorithm: HashAlgorithm::Sha512,
+                ..Default::default()
+            },
+        );
+
+        fetcher
+            .fetch(
+                &manifest_two_packages(),
+                &mut lockfile,
+                None::<&TestProgress>,
+            )
+            .await
+            .unwrap();
+
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert_eq!(pkg2.versions[0].hash, HashAlgorithm::Sha512.digest(&raw));
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_builds_that_collide_after_stripping_metadata() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner2/repo2".to_string(),
+                vec![
+                    Release::new(
+                        "v1.2.3+build.6".to_string(),
+                        Some("https://assets.example/pkg2-build6.json".to_string()),
+                    ),
+                    Release::new(
+                        "v1.2.3+build.5".to_string(),
+                        Some("https://assets.example/pkg2-build5.json".to_string()),
+                    ),
+                ],
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                strip_build_metadata: true,
+                ..Default::default()
+            },
+        );
+
+        let result = fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 2
+        ));
+
+        let pkg2 = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert!(pkg2.versions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_only_changed_packages_from_diff_source() {
+        struct FakeDiff;
+
+        impl crate::services::DiffSource for FakeDiff {
+            fn diff(&self, _path: &std::path::Path, _git_ref: &str) -> Result<String> {
+                Ok(
+                    "@@ -11 +11 @@\n-repository = \"owner2/old\"\n+repository = \"owner2/repo2\"\n"
+                        .to_string(),
+                )
+            }
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("voyager.toml");
+        std::fs::write(
+            &config_path,
+            r#"[vpm]
+id = "com.test.vpm"
+name = "Test"
+author = "Author"
+url = "https://example.com/index.json"
+
+[[packages]]
+id = "com.test.vpm.pkg1"
+repository = "owner1/repo1"
+
+[[packages]]
+id = "com.test.vpm.pkg2"
+repository = "owner2/repo2"
+"#,
+        )
+        .unwrap();
+
+        let changed =
+            crate::services::packages_changed_since(&FakeDiff, &config_path, "HEAD~1").unwrap();
+
+        let github = Arc::new(FakeGitHub::default());
+        let fetcher = PackageFetcher::new(
+            github.clone(),
+            FetcherConfig {
+                only_packages: Some(changed),
+                ..Default::default()
+            },
+        );
+
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        assert_eq!(github.release_calls(), vec!["owner2/repo2".to_string()]);
+        assert_eq!(
+            lockfile
+                .get_package("com.test.vpm.pkg1")
+                .unwrap()
+                .versions
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_locks_only_allowlisted_versions_and_prunes_others() {
+        let mut manifest = manifest_two_packages();
+        manifest.packages[0].versions = vec!["2.0.0".to_string()];
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                vec![
+                    Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    ),
+                    Release::new(
+                        "v1.5.0".to_string(),
+                        Some("https://assets.example/pkg1-v1.5.json".to_string()),
+                    ),
+                    Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v1.json".to_string()),
+                    ),
+                ],
+            )]),
+            assets: HashMap::from([(
+                "https://assets.example/pkg1-v2.json".to_string(),
+                version_json(
+                    "com.test.vpm.pkg1",
+                    "2.0.0",
+                    "https://download.example/pkg1-v2.zip",
+                ),
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(github, FetcherConfig::default());
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        let versions: Vec<_> = pkg1.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.0"]);
+    }
+
+    #[tokio::test]
+    async fn fetch_locks_only_versions_in_range_and_prunes_others() {
+        let mut manifest = manifest_two_packages();
+        manifest.packages[0].version_range = Some(">=1.5.0, <3.0.0".to_string());
+        let mut lockfile = initial_lockfile();
+
+        let release = |version: &str| {
+            Release::new(
+                format!("v{version}"),
+                Some(format!("https://assets.example/pkg1-v{version}.json")),
+            )
+        };
+        let asset = |version: &str| {
+            (
+                format!("https://assets.example/pkg1-v{version}.json"),
+                version_json(
+                    "com.test.vpm.pkg1",
+                    version,
+                    &format!("https://download.example/pkg1-v{version}.zip"),
+                ),
+            )
+        };
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                vec![
+                    release("3.0.0"),
+                    release("2.0.0"),
+                    release("1.5.0"),
+                    release("1.0.0"),
+                ],
+            )]),
+            assets: HashMap::from([asset("2.0.0"), asset("1.5.0")]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(github, FetcherConfig::default());
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        let versions: Vec<_> = pkg1.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.0", "1.5.0"]);
+    }
+
+    #[tokio::test]
+    async fn fetch_uses_package_asset_name_over_default() {
+        let mut manifest = manifest_two_packages();
+        manifest.packages[1].asset_name = Some("vpm-manifest.json".to_string());
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub::default());
+        let fetcher = PackageFetcher::new(
+            github.clone(),
+            FetcherConfig {
+                max_concurrent: 1,
+                ..Default::default()
+            },
+        );
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *github.asset_name_calls.lock().unwrap(),
+            vec!["package.json", "vpm-manifest.json"]
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_zip_fills_in_hash_and_rejects_mismatched_declared_hash() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                vec![
+                    Release::new(
+                        "v3.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v3.json".to_string()),
+                    ),
+                    Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    ),
+                ],
+            )]),
+            assets: HashMap::from([
+                (
+                    "https://assets.example/pkg1-v3.json".to_string(),
+                    version_json(
+                        "com.test.vpm.pkg1",
+                        "3.0.0",
+                        "https://download.example/pkg1-v3.zip",
+                    )
+                    .replace("\"url\"", "\"zipSHA256\": \"deadbeef\",\n  \"url\""),
+                ),
+                (
+                    "https://assets.example/pkg1-v2.json".to_string(),
+                    version_json(
+                        "com.test.vpm.pkg1",
+                        "2.0.0",
+                        "https://download.example/pkg1-v2.zip",
+                    ),
+                ),
+            ]),
+            files: HashMap::from([
+                (
+                    "https://download.example/pkg1-v3.zip".to_string(),
+                    b"zip-3".to_vec(),
+                ),
+                (
+                    "https://download.example/pkg1-v2.zip".to_string(),
+                    b"zip-2".to_vec(),
+                ),
+            ]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                verify_zip: true,
+                ..Default::default()
+            },
+        );
+        let result = fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        let versions: Vec<_> = pkg1.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.0", "1.0.0"]);
+        assert_eq!(pkg1.versions[0].manifest.zip_sha256, sha256_hex(b"zip-2"));
+    }
+
+    #[tokio::test]
+    async fn fetch_stores_raw_manifest_only_when_requested() {
+        let raw = version_json(
+            "com.test.vpm.pkg1",
+            "2.0.0",
+            "https://download.example/pkg1-v2.zip",
+        );
+        for store_raw in [false, true] {
+            let manifest = manifest_two_packages();
+            let mut lockfile = initial_lockfile();
+            let github = Arc::new(FakeGitHub {
+                releases: HashMap::from([(
+                    "owner1/repo1".to_string(),
+                    vec![Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    )],
+                )]),
+                assets: HashMap::from([(
+                    "https://assets.example/pkg1-v2.json".to_string(),
+                    raw.clone(),
+                )]),
+                ..Default::default()
+            });
+
+            let fetcher = PackageFetcher::new(
+                github,
+                FetcherConfig {
+                    store_raw,
+                    only_packages: Some(HashSet::from(["com.test.vpm.pkg1".to_string()])),
+                    ..Default::default()
+                },
+            );
+            fetcher
+                .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+                .await
+                .unwrap();
+
+            let locked = lockfile
+                .get_package("com.test.vpm.pkg1")
+                .and_then(|p| p.get_version("2.0.0"))
+                .unwrap();
+            assert_eq!(locked.hash, crate::lock::compute_hash(&raw));
+            if store_raw {
+                assert_eq!(locked.raw_manifest.as_deref(), Some(raw.as_str()));
+            } else {
+                assert_eq!(locked.raw_manifest, None);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_vpm_dependencies_outside_allowlist() {
+        let manifest = manifest_two_packages();
+        let mut lockfile = initial_lockfile();
+
+        let with_dependency = |version: &str, dependency: &str| {
+            format!(
+                r#"{{
+  "name": "com.test.vpm.pkg1",
+  "version": "{version}",
+  "displayName": "com.test.vpm.pkg1",
+  "description": "desc",
+  "unity": "2022.3",
+  "author": {{"name": "Author", "email": "author@example.com"}},
+  "vpmDependencies": {{"{dependency}": ">=1.0.0"}},
+  "url": "https://download.example/pkg1-v{version}.zip"
+}}"#
+            )
+        };
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([
+                (
+                    "owner1/repo1".to_string(),
+                    vec![
+                        Release::new(
+                            "v2.0.0".to_string(),
+                            Some("https://assets.example/pkg1-v2.json".to_string()),
+                        ),
+                        Release::new(
+                            "v1.0.0".to_string(),
+                            Some("https://assets.example/pkg1-v1.json".to_string()),
+                        ),
+                    ],
+                ),
+                ("owner2/repo2".to_string(), Vec::new()),
+            ]),
+            assets: HashMap::from([
+                (
+                    "https://assets.example/pkg1-v2.json".to_string(),
+                    with_dependency("2.0.0", "com.untrusted.lib"),
+                ),
+                (
+                    "https://assets.example/pkg1-v1.json".to_string(),
+                    with_dependency("1.0.0", "com.vrchat.base"),
+                ),
+            ]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                allowed_dependency_prefixes: vec!["com.vrchat.*".to_string()],
+                ..Default::default()
+            },
+        );
+
+        let result = fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+
+        let pkg1 = lockfile.get_package("com.test.vpm.pkg1").unwrap();
+        let versions: Vec<_> = pkg1.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.0.0"]);
+    }
+
+    #[tokio::test]
+    async fn plan_reports_new_versions_without_downloading() {
+        let mut manifest = manifest_two_packages();
+        manifest.packages[1].repository = repo("owner2/moved");
+        let mut lockfile = initial_lockfile();
+        lockfile.packages[1].versions = initial_lockfile().packages[0].versions.clone();
+        let before = toml::to_string(&lockfile).unwrap();
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                vec![
+                    Release::new(
+                        "v2.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v2.json".to_string()),
+                    ),
+                    Release::new(
+                        "v1.0.0".to_string(),
+                        Some("https://assets.example/pkg1-v1.json".to_string()),
+                    ),
+                ],
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(github.clone(), FetcherConfig::default());
+        let plans = fetcher.plan(&manifest, &lockfile).await.unwrap();
+
+        assert_eq!(
+            plans,
+            vec![
+                PackagePlan {
+                    package_id: "com.test.vpm.pkg1".to_string(),
+                    added: vec!["2.0.0".to_string()],
+                    kept: vec!["1.0.0".to_string()],
+                    pruned: Vec::new(),
+                },
+                PackagePlan {
+                    package_id: "com.test.vpm.pkg2".to_string(),
+                    added: Vec::new(),
+                    kept: Vec::new(),
+                    pruned: vec!["1.0.0".to_string()],
+                },
+            ]
+        );
+        assert_eq!(*github.download_calls.lock().unwrap(), 0);
+        assert_eq!(toml::to_string(&lockfile).unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn fetch_keeps_retained_packages_missing_from_manifest() {
+        let mut manifest = manifest_two_packages();
+        manifest.packages.truncate(1);
+        let mut lockfile = initial_lockfile();
+        lockfile.packages[1].versions = initial_lockfile().packages[0].versions.clone();
+        lockfile.packages[1].retained = true;
+
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([(
+                "owner1/repo1".to_string(),
+                vec![Release::new(
+                    "v1.0.0".to_string(),
+                    Some("https://assets.example/pkg1-v1.json".to_string()),
+                )],
+            )]),
+            ..Default::default()
+        });
+
+        let fetcher = PackageFetcher::new(github, FetcherConfig::default());
+        let plans = fetcher.plan(&manifest, &lockfile).await.unwrap();
+        assert_eq!(plans.len(), 1);
+
+        fetcher
+            .fetch(&manifest, &mut lockfile, None::<&TestProgress>)
+            .await
+            .unwrap();
+
+        let retained = lockfile.get_package("com.test.vpm.pkg2").unwrap();
+        assert!(retained.retained);
+        assert_eq!(retained.versions.len(), 1);
+    }
+
+    fn unity_gate_fetcher(
+        unity_by_version: &[(&str, &str)],
+        min_unity: Option<&str>,
+        require_unity: bool,
+    ) -> (PackageFetcher<FakeGitHub>, Lockfile) {
+        let releases = unity_by_version
+            .iter()
+            .map(|(version, _)| {
+                Release::new(
+                    format!("v{version}"),
+                    Some(format!("https://assets.example/pkg1-v{version}.json")),
+                )
+            })
+            .collect();
+        let assets = unity_by_version
+            .iter()
+            .map(|(version, unity)| {
+                (
+                    format!("https://assets.example/pkg1-v{version}.json"),
+                    format!(
+                        r#"{{
+  "name": "com.test.vpm.pkg1",
+  "version": "{version}",
+  "displayName": "com.test.vpm.pkg1",
+  "description": "desc",
+  "unity": "{unity}",
+  "author": {{"name": "Author", "email": "author@example.com"}},
+  "url": "https://download.example/pkg1-v{version}.zip"
+}}"#
+                    ),
+                )
+            })
+            .collect();
+        let github = Arc::new(FakeGitHub {
+            releases: HashMap::from([
+                ("owner1/repo1".to_string(), releases),
+                ("owner2/repo2".to_string(), Vec::new()),
+            ]),
+            assets,
+            ..Default::default()
+        });
+
+        let mut lockfile = initial_lockfile();
+        lockfile.packages[0].versions.clear();
+        let fetcher = PackageFetcher::new(
+            github,
+            FetcherConfig {
+                min_unity: min_unity.map(str::to_string),
+                require_unity,
+                ..Default::default()
+            },
+        );
+        (fetcher, lockfile)
+    }
+
+    fn locked_versions(lockfile: &Lockfile) -> Vec<String> {
+        lockfile.packages[0]
+            .versions
+            .iter()
+            .map(|v| v.version.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_versions_below_min_unity() {
+        let (fetcher, mut lockfile) = unity_gate_fetcher(
+            &[
+                ("3.0.0", "6000.0"),
+                ("2.0.0", "2022.3"),
+                ("1.0.0", "2021.3"),
+            ],
+            Some("2022.3"),
+            false,
+        );
+
+        let result = fetcher
+            .fetch(
+                &manifest_two_packages(),
+                &mut lockfile,
+                None::<&TestProgress>,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+        assert_eq!(locked_versions(&lockfile), vec!["3.0.0", "2.0.0"]);
+    }
+
+    #[test]
+    fn selected_packages_applies_the_fetch_filters() {
+        let manifest = manifest_two_packages();
+        let lockfile = initial_lockfile();
+        let selected = |config: FetcherConfig| {
+            PackageFetcher::new(Arc::new(FakeGitHub::default()), config)
+                .selected_packages(&manifest, &lockfile)
+        };
+
+        assert_eq!(selected(FetcherConfig::default()).len(), 2);
+        assert_eq!(
+            selected(FetcherConfig {
+                only_missing: true,
+                ..Default::default()
+            }),
+            HashSet::from(["com.test.vpm.pkg2".to_string()])
+        );
+        assert_eq!(
+            selected(FetcherConfig {
+                only_packages: Some(HashSet::from(["com.test.vpm.pkg1".to_string()])),
+                ..Default::default()
+            }),
+            HashSet::from(["com.test.vpm.pkg1".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_allows_empty_unity_unless_required() {
+        let (fetcher, mut lockfile) = unity_gate_fetcher(&[("1.0.0", "")], Some("2022.3"), false);
+        fetcher
+            .fetch(
+                &manifest_two_packages(),
+                &mut lockfile,
+                None::<&TestProgress>,
+            )
+            .await
+            .unwrap();
+        assert_eq!(locked_versions(&lockfile), vec!["1.0.0"]);
+
+        let (fetcher, mut lockfile) = unity_gate_fetcher(&[("1.0.0", "")], Some("2022.3"), true);
+        let result = fetcher
+            .fetch(
+                &manifest_two_packages(),
+                &mut lockfile,
+                None::<&TestProgress>,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::FetchPartialFailure { ref failures, .. }) if failures.len() == 1
+        ));
+        assert!(locked_versions(&lockfile).is_empty());
+    }
 }
@@ -1,6 +1,9 @@
+use crate::config::validation;
 use crate::error::Result;
-use crate::infra::HttpApi;
+use crate::infra::{HttpApi, RetryObserver, UrlCheckOutcome};
 use crate::output::VpmOutput;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{info, instrument};
 
@@ -8,6 +11,7 @@ pub struct UrlValidator<H: HttpApi> {
     http: Arc<H>,
     max_concurrent: usize,
     max_retries: u32,
+    https_only: bool,
 }
 
 pub struct ValidationResult {
@@ -20,6 +24,80 @@ pub struct InvalidUrl {
     pub package_id: String,
     pub version: String,
     pub url: String,
+    pub outcome: UrlCheckOutcome,
+}
+
+/// Per-URL outcomes of a validation run plus counts, written by
+/// `validate --report`.
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub summary: ValidationSummary,
+    pub urls: Vec<ValidationReportEntry>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationSummary {
+    pub total: usize,
+    pub ok: usize,
+    pub not_found: usize,
+    pub unreachable: usize,
+    pub rate_limited: usize,
+    pub insecure: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReportEntry {
+    pub package_id: String,
+    pub version: String,
+    pub url: String,
+    pub outcome: UrlCheckOutcome,
+}
+
+impl ValidationReport {
+    /// Lists every URL of `output` in index order; URLs missing from
+    /// `result.invalid` are reported as ok.
+    pub fn new(output: &VpmOutput, result: &ValidationResult) -> Self {
+        let failures: HashMap<(&str, &str), UrlCheckOutcome> = result
+            .invalid
+            .iter()
+            .map(|invalid| {
+                (
+                    (invalid.package_id.as_str(), invalid.version.as_str()),
+                    invalid.outcome,
+                )
+            })
+            .collect();
+
+        let mut summary = ValidationSummary::default();
+        let urls: Vec<ValidationReportEntry> = output
+            .collect_urls()
+            .into_iter()
+            .map(|(package_id, version, url)| {
+                let outcome = failures
+                    .get(&(package_id.as_str(), version.as_str()))
+                    .copied()
+                    .unwrap_or(UrlCheckOutcome::Ok);
+                match outcome {
+                    UrlCheckOutcome::Ok => summary.ok += 1,
+                    UrlCheckOutcome::NotFound => summary.not_found += 1,
+                    UrlCheckOutcome::Unreachable => summary.unreachable += 1,
+                    UrlCheckOutcome::RateLimited => summary.rate_limited += 1,
+                    UrlCheckOutcome::Insecure => summary.insecure += 1,
+                }
+                ValidationReportEntry {
+                    package_id,
+                    version,
+                    url,
+                    outcome,
+                }
+            })
+            .collect();
+        summary.total = urls.len();
+
+        Self { summary, urls }
+    }
 }
 
 impl<H: HttpApi> UrlValidator<H> {
@@ -28,11 +106,28 @@ impl<H: HttpApi> UrlValidator<H> {
             http,
             max_concurrent,
             max_retries,
+            https_only: false,
         }
     }
 
-    #[instrument(skip(self, output), fields(package_count = output.packages.len()))]
+    /// Reports URLs that are not https:// as insecure instead of requesting
+    /// them.
+    pub fn with_https_only(mut self, https_only: bool) -> Self {
+        self.https_only = https_only;
+        self
+    }
+
     pub async fn validate(&self, output: &VpmOutput) -> Result<ValidationResult> {
+        self.validate_with_observer(output, None).await
+    }
+
+    /// Validates index URLs, notifying `retry_observer` before each retry wait.
+    #[instrument(skip(self, output, retry_observer), fields(package_count = output.packages.len()))]
+    pub async fn validate_with_observer(
+        &self,
+        output: &VpmOutput,
+        retry_observer: Option<&dyn RetryObserver>,
+    ) -> Result<ValidationResult> {
         let urls = output.collect_urls();
         let total = urls.len();
 
@@ -45,19 +140,26 @@ impl<H: HttpApi> UrlValidator<H> {
             });
         }
 
-        info!(url_count = total, "Checking URL availability");
+        let (urls, insecure): (Vec<_>, Vec<_>) = urls.into_iter().partition(|(_, _, url)| {
+            !self.https_only || validation::validate_https_url(url).is_ok()
+        });
+
+        info!(url_count = urls.len(), "Checking URL availability");
 
-        let invalid_tuples = self
+        let checked = self
             .http
-            .validate_urls(urls, self.max_concurrent, self.max_retries)
+            .validate_urls(urls, self.max_concurrent, self.max_retries, retry_observer)
             .await;
 
-        let invalid: Vec<InvalidUrl> = invalid_tuples
+        let invalid: Vec<InvalidUrl> = insecure
             .into_iter()
-            .map(|(package_id, version, url)| InvalidUrl {
+            .map(|(package_id, version, url)| (package_id, version, url, UrlCheckOutcome::Insecure))
+            .chain(checked)
+            .map(|(package_id, version, url, outcome)| InvalidUrl {
                 package_id,
                 version,
                 url,
+                outcome,
             })
             .collect();
 
@@ -135,6 +237,7 @@ mod tests {
             url: "https://test.com/vpm.json".to_string(),
             author: "Test Author".to_string(),
             packages,
+            extra: IndexMap::new(),
         }
     }
 
@@ -193,6 +296,32 @@ mod tests {
             assert_eq!(result.invalid[0].version, "1.0.0");
         }
 
+        #[tokio::test]
+        async fn https_only_reports_http_urls_without_requesting_them() {
+            if !can_bind_localhost() {
+                return;
+            }
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("HEAD"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(0)
+                .mount(&mock_server)
+                .await;
+
+            let http = Arc::new(HttpClient::new().unwrap());
+            let validator = UrlValidator::new(http, 4, 0).with_https_only(true);
+
+            let url = format!("{}/package", mock_server.uri());
+            let output = create_test_output(vec![("com.test.pkg", "1.0.0", &url)]);
+
+            let result = validator.validate(&output).await.unwrap();
+
+            assert_eq!(result.total, 1);
+            assert_eq!(result.valid, 0);
+            assert_eq!(result.invalid[0].outcome, UrlCheckOutcome::Insecure);
+        }
+
         #[tokio::test]
         async fn handles_empty_output() {
             let http = Arc::new(HttpClient::new().unwrap());
@@ -204,6 +333,7 @@ mod tests {
                 url: "https://test.com".to_string(),
                 author: "Author".to_string(),
                 packages: IndexMap::new(),
+                extra: IndexMap::new(),
             };
 
             let result = validator.validate(&output).await.unwrap();
@@ -250,4 +380,30 @@ mod tests {
             assert_eq!(result.invalid[0].package_id, "com.test.pkg2");
         }
     }
+
+    #[test]
+    fn report_marks_invalid_urls_and_counts_outcomes() {
+        let output = create_test_output(vec![
+            ("com.test.pkg1", "1.0.0", "https://example.com/ok.zip"),
+            ("com.test.pkg2", "1.0.0", "https://example.com/missing.zip"),
+        ]);
+        let result = ValidationResult {
+            total: 2,
+            valid: 1,
+            invalid: vec![InvalidUrl {
+                package_id: "com.test.pkg2".to_string(),
+                version: "1.0.0".to_string(),
+                url: "https://example.com/missing.zip".to_string(),
+                outcome: UrlCheckOutcome::NotFound,
+            }],
+        };
+
+        let report = ValidationReport::new(&output, &result);
+
+        assert_eq!(report.summary.total, 2);
+        assert_eq!(report.summary.ok, 1);
+        assert_eq!(report.summary.not_found, 1);
+        assert_eq!(report.urls[0].outcome, UrlCheckOutcome::Ok);
+        assert_eq!(report.urls[1].outcome, UrlCheckOutcome::NotFound);
+    }
 }
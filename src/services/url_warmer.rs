@@ -0,0 +1,111 @@
+use crate::error::Error;
+use crate::infra::HttpApi;
+use crate::output::VpmOutput;
+use futures::stream::{self, StreamExt};
+use indicatif::ProgressBar;
+use std::collections::HashSet;
+use tracing::{info, instrument};
+
+/// Returns every version URL in the index once, in index order.
+pub fn url_list(output: &VpmOutput) -> Vec<String> {
+    let mut seen = HashSet::new();
+    output
+        .collect_urls()
+        .into_iter()
+        .map(|(_, _, url)| url)
+        .filter(|url| seen.insert(url.clone()))
+        .collect()
+}
+
+/// Requests each URL with at most `max_concurrent` requests in flight,
+/// returning the URLs that could not be fetched.
+#[instrument(skip(http, urls, progress), fields(url_count = urls.len(), max_concurrent))]
+pub async fn warm_urls<H: HttpApi>(
+    http: &H,
+    urls: &[String],
+    max_concurrent: usize,
+    progress: Option<&ProgressBar>,
+) -> Vec<(String, Error)> {
+    info!("Warming URLs");
+
+    stream::iter(urls)
+        .map(|url| async move { http.warm_url(url).await.err().map(|e| (url.clone(), e)) })
+        .buffer_unordered(max_concurrent.max(1))
+        .inspect(|_| {
+            if let Some(bar) = progress {
+                bar.inc(1);
+            }
+        })
+        .filter_map(|failure| async move { failure })
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::MockHttpApi;
+    use mockall::predicate::eq;
+
+    fn index(urls: &[(&str, &str, &str)]) -> VpmOutput {
+        let mut packages = serde_json::Map::new();
+        for (id, version, url) in urls {
+            let package = packages
+                .entry(id.to_string())
+                .or_insert_with(|| serde_json::json!({ "versions": {} }));
+            package["versions"][*version] = serde_json::json!({
+                "name": id,
+                "version": version,
+                "displayName": id,
+                "description": "desc",
+                "url": url,
+                "author": { "name": "Author" }
+            });
+        }
+
+        serde_json::from_value(serde_json::json!({
+            "name": "Example",
+            "id": "com.example.vpm",
+            "url": "https://example.com/index.json",
+            "author": "Author",
+            "packages": packages
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn url_list_contains_each_version_url_once() {
+        let output = index(&[
+            ("com.example.a", "1.0.0", "https://example.com/a-1.zip"),
+            ("com.example.a", "2.0.0", "https://example.com/a-2.zip"),
+            ("com.example.b", "1.0.0", "https://example.com/a-1.zip"),
+        ]);
+
+        assert_eq!(
+            url_list(&output),
+            vec!["https://example.com/a-1.zip", "https://example.com/a-2.zip"]
+        );
+    }
+
+    #[tokio::test]
+    async fn warm_urls_requests_each_url_once() {
+        let urls = vec![
+            "https://example.com/a.zip".to_string(),
+            "https://example.com/b.zip".to_string(),
+        ];
+        let mut http = MockHttpApi::new();
+        http.expect_warm_url()
+            .with(eq("https://example.com/a.zip"))
+            .times(1)
+            .returning(|_| Ok(()));
+        http.expect_warm_url()
+            .with(eq("https://example.com/b.zip"))
+            .times(1)
+            .returning(|_| Err(Error::ConfigValidation("unreachable".to_string())));
+
+        let failures = warm_urls(&http, &urls, 2, None).await;
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "https://example.com/b.zip");
+    }
+}
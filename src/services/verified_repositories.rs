@@ -0,0 +1,99 @@
+use crate::domain::Repository;
+use crate::error::{Error, Result};
+use crate::infra::{read_to_string_if_exists, write_atomic_file};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How long a successful repository verification is trusted by `add`.
+pub const VERIFIED_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Returns the path of the verified-repositories cache next to the given config file.
+pub fn verified_repositories_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("verified")
+}
+
+/// When each repository was last verified to exist, so repeated `add`s
+/// within [`VERIFIED_TTL_SECS`] skip the round-trip.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VerifiedRepositories {
+    /// Unix seconds of the last successful verification, by repository.
+    repositories: BTreeMap<String, u64>,
+}
+
+impl VerifiedRepositories {
+    /// Loads the cache, returning an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string_if_exists(path).map_err(|e| Error::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let Some(content) = content else {
+            return Ok(Self::default());
+        };
+
+        serde_json::from_str(&content).map_err(|e| Error::JsonParse {
+            source: path.display().to_string(),
+            error: e,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)?;
+        write_atomic_file(path, &content).map_err(|e| Error::FileWrite {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    /// Returns whether `repository` was verified within the TTL.
+    pub fn is_fresh(&self, repository: &Repository) -> bool {
+        self.is_fresh_at(repository, unix_now())
+    }
+
+    /// Records `repository` as verified now.
+    pub fn record(&mut self, repository: &Repository) {
+        self.record_at(repository, unix_now());
+    }
+
+    fn is_fresh_at(&self, repository: &Repository, now: u64) -> bool {
+        self.repositories
+            .get(&repository.to_string())
+            .is_some_and(|&verified_at| now.saturating_sub(verified_at) < VERIFIED_TTL_SECS)
+    }
+
+    fn record_at(&mut self, repository: &Repository, now: u64) {
+        self.repositories.insert(repository.to_string(), now);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn verification_expires_after_ttl() {
+        let dir = TempDir::new().unwrap();
+        let path = verified_repositories_path(&dir.path().join("voyager.toml"));
+        let repo = Repository::parse("owner/repo").unwrap();
+
+        let mut verified = VerifiedRepositories::load(&path).unwrap();
+        assert!(!verified.is_fresh_at(&repo, 1_000));
+        verified.record_at(&repo, 1_000);
+        verified.save(&path).unwrap();
+
+        let loaded = VerifiedRepositories::load(&path).unwrap();
+        assert!(loaded.is_fresh_at(&repo, 1_000 + VERIFIED_TTL_SECS - 1));
+        assert!(!loaded.is_fresh_at(&repo, 1_000 + VERIFIED_TTL_SECS));
+        assert!(!loaded.is_fresh_at(&Repository::parse("owner/other").unwrap(), 1_000));
+    }
+}
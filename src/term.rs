@@ -1,4 +1,5 @@
 use crate::cli::ColorChoice;
+use crate::infra::{GitHubAuth, RetryObserver};
 use console::{Emoji, style};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::fmt::Display;
@@ -98,10 +99,11 @@ impl FetchProgress {
 
         let multi = MultiProgress::new();
 
-        let main_bar = multi.add(ProgressBar::new(package_ids.len() as u64));
+        let total = package_ids.len() as u64;
+        let main_bar = multi.add(ProgressBar::new(total));
         main_bar.set_style(
             ProgressStyle::default_bar()
-                .template("{spinner:.cyan} [{bar:30.cyan/dim}] {pos}/{len}")
+                .template(fetch_bar_template(total))
                 .unwrap()
                 .progress_chars("━━─"),
         );
@@ -148,6 +150,18 @@ impl FetchProgress {
         }
     }
 
+    /// Updates a package to "retrying" state while a download backs off.
+    pub fn set_retrying(&self, index: usize, package_id: &str, attempt: u32) {
+        if let Some(bar) = self.package_bars.get(index) {
+            bar.set_message(format!(
+                "{} {}    {}",
+                EMOJI_WORKING,
+                package_id,
+                retry_message(attempt)
+            ));
+        }
+    }
+
     /// Updates a package to completed state.
     pub fn set_done(&self, index: usize, package_id: &str, existing: usize, new: usize) {
         if let Some(bar) = self.package_bars.get(index) {
@@ -187,6 +201,44 @@ impl FetchProgress {
     }
 }
 
+/// Template of the overall fetch bar: elapsed time, ETA and packages per
+/// second, leaving out the ETA and rate when there is no total to estimate from.
+fn fetch_bar_template(total: u64) -> &'static str {
+    if total == 0 {
+        "{spinner:.cyan} [{bar:30.cyan/dim}] {pos}/{len} {elapsed}"
+    } else {
+        "{spinner:.cyan} [{bar:30.cyan/dim}] {pos}/{len} {elapsed} (ETA {eta}, {per_sec})"
+    }
+}
+
+/// Formats the status shown while an operation waits before retrying.
+fn retry_message(attempt: u32) -> String {
+    format!("retrying (attempt {})…", attempt)
+}
+
+/// Shows retry backoff waits in a spinner's message.
+pub struct SpinnerRetryObserver<'a> {
+    spinner: &'a ProgressBar,
+    message: String,
+}
+
+impl<'a> SpinnerRetryObserver<'a> {
+    /// Wraps a spinner, keeping its current message as the prefix.
+    pub fn new(spinner: &'a ProgressBar) -> Self {
+        Self {
+            spinner,
+            message: spinner.message(),
+        }
+    }
+}
+
+impl RetryObserver for SpinnerRetryObserver<'_> {
+    fn on_retry(&self, _url: &str, attempt: u32) {
+        self.spinner
+            .set_message(format!("{} {}", self.message, retry_message(attempt)));
+    }
+}
+
 /// Prints a success message with a green checkmark.
 pub fn success(message: impl Display) {
     if is_quiet() {
@@ -195,6 +247,15 @@ pub fn success(message: impl Display) {
     println!("{} {}", EMOJI_SUCCESS, style(message).green());
 }
 
+/// Prints a success message with a green checkmark to stderr, for commands
+/// whose stdout carries data.
+pub fn success_stderr(message: impl Display) {
+    if is_quiet() {
+        return;
+    }
+    eprintln!("{} {}", EMOJI_SUCCESS, style(message).green());
+}
+
 /// Prints a warning message with a yellow warning sign to stderr.
 pub fn warning(message: impl Display) {
     eprintln!("{} {}", EMOJI_WARNING, style(message).yellow());
@@ -205,6 +266,39 @@ pub fn error(message: impl Display) {
     eprintln!("  {} {}", EMOJI_ERROR, style(message).red());
 }
 
+/// Formats an error followed by each error in its `source()` chain. A cause
+/// whose message already ends the previous one is not repeated.
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut previous = message.clone();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !previous.ends_with(&cause_message) {
+            message.push_str(&format!("\n    caused by: {}", cause_message));
+        }
+        previous = cause_message;
+        source = cause.source();
+    }
+    message
+}
+
+/// Asks for a value until `validate` accepts it. `ask` receives the reason
+/// the previous answer was rejected, if any.
+pub fn prompt_until_valid(
+    mut ask: impl FnMut(Option<&str>) -> crate::error::Result<String>,
+    validate: impl Fn(&str) -> crate::error::Result<()>,
+) -> crate::error::Result<String> {
+    let mut rejection: Option<String> = None;
+    loop {
+        let answer = ask(rejection.as_deref())?;
+        match validate(&answer) {
+            Ok(()) => return Ok(answer),
+            Err(e) => rejection = Some(e.to_string()),
+        }
+    }
+}
+
 /// Prints a blank line.
 pub fn blank() {
     if is_quiet() {
@@ -252,6 +346,11 @@ pub fn red(value: impl Display) -> impl Display {
     style(value).red()
 }
 
+/// Returns a yellow styled value for inline use.
+pub fn yellow(value: impl Display) -> impl Display {
+    style(value).yellow()
+}
+
 /// Returns an underlined styled value for inline use.
 pub fn underlined(value: impl Display) -> impl Display {
     style(value).underlined()
@@ -279,12 +378,103 @@ pub fn indent(level: usize, message: impl Display) {
     println!("{}{}", spaces, message);
 }
 
-/// Warns if GitHub token is not configured.
+/// Warns if neither a GitHub token nor app credentials are configured.
 /// Should be called before making GitHub API requests.
-pub fn warn_if_no_github_token(token: Option<&str>) {
-    if token.is_none() && !is_quiet() {
+pub fn warn_if_anonymous_github(auth: &GitHubAuth) {
+    if *auth == GitHubAuth::Anonymous && !is_quiet() {
+        // Stderr only, so JSON written to stdout stays parseable.
         warning("VOYAGER_GITHUB_TOKEN is not set. API rate limits may apply.");
-        hint("Set VOYAGER_GITHUB_TOKEN or use --github-token option.");
-        blank();
+        eprintln!(
+            "  {}",
+            style("Set VOYAGER_GITHUB_TOKEN, use --github-token or --github-token-file, or pass GitHub App credentials.")
+                .dim()
+        );
+        eprintln!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spinner_retry_observer_shows_attempt_in_message() {
+        let spinner = ProgressBar::hidden();
+        spinner.set_message("Validating URLs...");
+
+        let observer = SpinnerRetryObserver::new(&spinner);
+        observer.on_retry("https://example.com/a.zip", 1);
+        observer.on_retry("https://example.com/a.zip", 2);
+
+        assert_eq!(
+            spinner.message(),
+            "Validating URLs... retrying (attempt 2)…"
+        );
+    }
+
+    #[test]
+    fn fetch_bar_template_shows_eta_only_with_a_total() {
+        for total in [0, 3] {
+            assert!(
+                ProgressStyle::default_bar()
+                    .template(fetch_bar_template(total))
+                    .is_ok()
+            );
+        }
+        assert!(fetch_bar_template(3).contains("{eta}"));
+        assert!(fetch_bar_template(3).contains("{per_sec}"));
+        assert!(!fetch_bar_template(0).contains("{eta}"));
+        assert!(fetch_bar_template(0).contains("{elapsed}"));
+    }
+
+    #[test]
+    fn error_chain_skips_a_source_already_in_the_message() {
+        let error = crate::error::Error::FileRead {
+            path: "voyager.toml".to_string(),
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, "missing"),
+        };
+
+        assert_eq!(
+            error_chain(&error),
+            "Failed to read file 'voyager.toml': missing"
+        );
+    }
+
+    #[test]
+    fn error_chain_lists_each_new_source() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("request failed")]
+        struct Request(#[source] std::io::Error);
+
+        let refused = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        let error = crate::error::Error::FileRead {
+            path: "voyager.toml".to_string(),
+            source: std::io::Error::other(Request(refused)),
+        };
+
+        assert_eq!(
+            error_chain(&error),
+            "Failed to read file 'voyager.toml': request failed\n    caused by: refused"
+        );
+    }
+
+    #[test]
+    fn prompt_until_valid_reprompts_after_rejection() {
+        let mut answers = vec!["com.example.vpm", "Invalid ID"];
+        let mut rejections = Vec::new();
+
+        let value = prompt_until_valid(
+            |rejection| {
+                rejections.push(rejection.map(str::to_string));
+                Ok(answers.pop().unwrap().to_string())
+            },
+            crate::config::validation::validate_reverse_domain,
+        )
+        .unwrap();
+
+        assert_eq!(value, "com.example.vpm");
+        assert_eq!(rejections.len(), 2);
+        assert!(rejections[0].is_none());
+        assert!(rejections[1].as_deref().unwrap().contains("Invalid ID"));
     }
 }
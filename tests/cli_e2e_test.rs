@@ -1,10 +1,12 @@
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 use std::sync::{Arc, Barrier};
 use std::thread;
+use std::time::Duration;
 use tempfile::TempDir;
 use voyager::config::Manifest;
-use voyager::lock::{Lockfile, compute_manifest_hash};
+use voyager::lock::{Lockfile, compute_hash, compute_manifest_hash};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -220,6 +222,103 @@ packages = []
     assert!(stderr.contains("Manifest has been modified outside of voyager"));
 }
 
+#[test]
+fn fetch_only_rejects_unknown_package_id() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+    let lock_before = std::fs::read_to_string(&lock_path).unwrap();
+
+    let output = run_voy(
+        &[
+            "fetch",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--only",
+            "com.test.vpm.package1",
+            "--only",
+            "com.test.vpm.missing",
+        ],
+        dir.path(),
+    );
+
+    assert_eq!(output.status.code(), Some(78));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Package 'com.test.vpm.missing' not found"));
+    assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), lock_before);
+}
+
+#[test]
+fn fetch_dry_run_reports_pruned_versions_without_writing() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_stale_package_versions(&hash));
+    let lock_before = std::fs::read_to_string(&lock_path).unwrap();
+
+    let output = run_voy(
+        &[
+            "fetch",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--dry-run",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("com.test.vpm.stale"));
+    assert!(stdout.contains("- 1.0.0"));
+    assert!(stdout.contains("was not written"));
+    assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), lock_before);
+    assert!(!dir.path().join("voyager.checkpoint").exists());
+    assert!(!dir.path().join("voyager.txn").exists());
+}
+
+#[test]
+fn fetch_dry_run_json_reports_planned_changes_on_stdout() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_stale_package_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "fetch",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--dry-run",
+            "--format",
+            "json",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["packages"],
+        serde_json::json!([{
+            "id": "com.test.vpm.stale",
+            "added": [],
+            "kept": [],
+            "pruned": ["1.0.0"]
+        }])
+    );
+    assert_eq!(json["summary"]["total"]["failed"], 0);
+}
+
 #[test]
 fn fetch_prunes_stale_packages_when_manifest_no_longer_contains_them() {
     let dir = TempDir::new().unwrap();
@@ -249,6 +348,183 @@ fn fetch_prunes_stale_packages_when_manifest_no_longer_contains_them() {
     assert!(lock.packages.is_empty());
 }
 
+#[test]
+fn parallel_fetches_do_not_interleave_lock_writes() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_stale_package_versions(&hash));
+
+    let worker_count = 4usize;
+    let barrier = Arc::new(Barrier::new(worker_count));
+    let handles: Vec<_> = (0..worker_count)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            let cwd = dir.path().to_path_buf();
+            let config = config_path.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                run_voy(
+                    &[
+                        "fetch",
+                        "--config",
+                        config.to_str().unwrap(),
+                        "--max-retries",
+                        "0",
+                    ],
+                    &cwd,
+                )
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let result = handle.join().expect("worker thread panicked");
+        assert_eq!(
+            result.status.code(),
+            Some(0),
+            "{}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+    }
+
+    let lock = Lockfile::load(&lock_path).unwrap();
+    assert_eq!(lock.manifest_hash.as_deref(), Some(hash.as_str()));
+    assert!(lock.packages.is_empty());
+    assert!(!txn_path(&config_path).exists());
+}
+
+#[test]
+fn waiting_for_the_write_lock_names_the_lock_file() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_content(&hash));
+
+    let held = std::fs::File::create(dir.path().join("voyager.lck")).unwrap();
+    held.lock().unwrap();
+
+    let mut child = Command::new(voy_bin())
+        .args(["lock", "--config", config_path.to_str().unwrap()])
+        .current_dir(dir.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run voy");
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        sender.send(line).unwrap();
+        // Keep draining so later output does not hit a closed pipe.
+        std::io::copy(&mut stdout, &mut std::io::sink()).unwrap();
+    });
+    let waiting = receiver.recv_timeout(Duration::from_secs(30));
+
+    drop(held);
+    assert!(child.wait().unwrap().success());
+    let waiting = waiting.expect("voy did not report waiting for the lock");
+    assert!(waiting.contains("voyager.lck"), "{}", waiting);
+}
+
+#[test]
+fn parallel_adds_keep_every_package() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    write(&config_path, &make_manifest_empty("Test"));
+
+    // Each process loads, edits and saves the manifest, so a save made
+    // without holding the write lock since the load would drop the others.
+    let worker_count = 4usize;
+    let barrier = Arc::new(Barrier::new(worker_count));
+    let handles: Vec<_> = (0..worker_count)
+        .map(|i| {
+            let barrier = Arc::clone(&barrier);
+            let cwd = dir.path().to_path_buf();
+            let config = config_path.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                run_voy(
+                    &[
+                        "add",
+                        &format!("owner/repo{i}"),
+                        "--skip-verify",
+                        "--config",
+                        config.to_str().unwrap(),
+                    ],
+                    &cwd,
+                )
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let result = handle.join().expect("worker thread panicked");
+        assert_eq!(
+            result.status.code(),
+            Some(0),
+            "{}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+    }
+
+    let manifest = Manifest::load(&config_path).unwrap();
+    let mut ids: Vec<_> = manifest.packages.iter().map(|p| p.id.as_str()).collect();
+    ids.sort();
+    assert_eq!(
+        ids,
+        [
+            "com.test.vpm.repo0",
+            "com.test.vpm.repo1",
+            "com.test.vpm.repo2",
+            "com.test.vpm.repo3"
+        ]
+    );
+    let check = run_voy(
+        &["lock", "--check", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(check.status.code(), Some(0));
+}
+
+#[test]
+fn fetch_format_json_prints_only_the_summary() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_content(&hash));
+
+    let output = run_voy(
+        &[
+            "fetch",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "packages": [],
+            "total": { "existing": 0, "new": 0, "failed": 0 }
+        })
+    );
+}
+
 #[test]
 fn validate_succeeds_for_empty_index() {
     let dir = TempDir::new().unwrap();
@@ -269,6 +545,34 @@ fn validate_succeeds_for_empty_index() {
     assert_eq!(output.status.code(), Some(0));
 }
 
+#[test]
+fn validate_ignores_an_invalid_manifest_outside_its_registry_table() {
+    let dir = TempDir::new().unwrap();
+    let index_path = dir.path().join("index.json");
+    write(
+        &dir.path().join("voyager.toml"),
+        "[vpm]\nid = \"not a reverse domain\"\n",
+    );
+    write(
+        &index_path,
+        r#"{
+  "name": "Test VPM",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {}
+}"#,
+    );
+
+    let output = run_voy(&["validate", index_path.to_str().unwrap()], dir.path());
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 #[test]
 fn validate_succeeds_when_head_is_blocked_but_get_fallback_works() {
     if !can_bind_localhost() {
@@ -387,73 +691,314 @@ fn validate_fails_when_url_is_unreachable() {
 }
 
 #[test]
-fn validate_fails_on_malformed_json() {
+fn validate_https_only_reports_plain_http_urls_without_requesting_them() {
     let dir = TempDir::new().unwrap();
     let index_path = dir.path().join("index.json");
-    write(&index_path, "{ not-valid-json }");
-
-    let output = run_voy(&["validate", index_path.to_str().unwrap()], dir.path());
-
-    assert_eq!(output.status.code(), Some(65));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Failed to parse JSON"));
-}
-
-#[test]
-fn completions_succeeds_when_transaction_log_is_corrupted() {
-    let dir = TempDir::new().unwrap();
-    write(
-        &txn_path(&dir.path().join("voyager.toml")),
-        "{ this is not valid json }",
-    );
-
-    let output = run_voy(&["completions", "bash"], dir.path());
-
-    assert_eq!(output.status.code(), Some(0));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(!stderr.contains("Failed to parse JSON"));
-}
-
-#[test]
-fn init_force_removes_stale_transaction_log() {
-    let dir = TempDir::new().unwrap();
-    let config_path = dir.path().join("voyager.toml");
 
     write(
-        &txn_path(&config_path),
-        "{ this is stale and should be removed by init --force }",
+        &index_path,
+        r#"{
+  "name": "Test VPM",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {
+    "com.test.vpm.pkg": {
+      "versions": {
+        "1.0.0": {
+          "name": "com.test.vpm.pkg",
+          "version": "1.0.0",
+          "displayName": "Test Package",
+          "description": "desc",
+          "unity": "2022.3",
+          "author": { "name": "Author" },
+          "url": "http://127.0.0.1:9/package.zip"
+        }
+      }
+    }
+  }
+}"#,
     );
 
-    let init_output = run_voy(
+    let report_path = dir.path().join("report.json");
+    let output = run_voy(
         &[
-            "init",
-            "--force",
-            "--config",
-            config_path.to_str().unwrap(),
-            "--name",
-            "Fresh",
-            "--id",
-            "com.fresh.vpm",
-            "--author",
-            "Author",
-            "--url",
-            "https://example.com/index.json",
+            "validate",
+            index_path.to_str().unwrap(),
+            "--https-only",
+            "--report",
+            report_path.to_str().unwrap(),
         ],
         dir.path(),
     );
 
-    assert_eq!(init_output.status.code(), Some(0));
-    assert!(!txn_path(&config_path).exists());
-
-    let list_output = run_voy(
-        &["list", "--config", config_path.to_str().unwrap()],
-        dir.path(),
-    );
-    assert_eq!(list_output.status.code(), Some(0));
+    assert_eq!(output.status.code(), Some(69));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(not https)"), "{}", stdout);
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["summary"]["insecure"], 1);
+    assert_eq!(report["urls"][0]["outcome"], "insecure");
 }
 
 #[test]
-fn init_with_default_config_path_succeeds() {
+fn validate_report_records_broken_url_outcome() {
+    if !can_bind_localhost() {
+        return;
+    }
+
+    let dir = TempDir::new().unwrap();
+    let index_path = dir.path().join("index.json");
+    let report_path = dir.path().join("report.json");
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mock_server = rt.block_on(async { MockServer::start().await });
+    rt.block_on(async {
+        Mock::given(method("HEAD"))
+            .and(path("/ok.zip"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("HEAD"))
+            .and(path("/missing.zip"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+    });
+
+    let version = |version: &str, file: &str| {
+        format!(
+            r#""{version}": {{
+          "name": "com.test.vpm.pkg",
+          "version": "{version}",
+          "displayName": "Test Package",
+          "description": "desc",
+          "author": {{ "name": "Author" }},
+          "url": "{}/{file}"
+        }}"#,
+            mock_server.uri()
+        )
+    };
+    write(
+        &index_path,
+        &format!(
+            r#"{{
+  "name": "Test VPM",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {{
+    "com.test.vpm.pkg": {{
+      "versions": {{
+        {},
+        {}
+      }}
+    }}
+  }}
+}}"#,
+            version("2.0.0", "missing.zip"),
+            version("1.0.0", "ok.zip")
+        ),
+    );
+
+    let output = run_voy(
+        &[
+            "validate",
+            index_path.to_str().unwrap(),
+            "--max-retries",
+            "0",
+            "--report",
+            report_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(69));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["summary"]["total"], 2);
+    assert_eq!(report["summary"]["ok"], 1);
+    assert_eq!(report["summary"]["notFound"], 1);
+    let broken = &report["urls"][0];
+    assert_eq!(broken["version"], "2.0.0");
+    assert_eq!(broken["outcome"], "not-found");
+    assert!(broken["url"].as_str().unwrap().ends_with("/missing.zip"));
+    assert_eq!(report["urls"][1]["outcome"], "ok");
+}
+
+#[test]
+fn validate_concurrency_report_prints_throughput_unless_quiet() {
+    if !can_bind_localhost() {
+        return;
+    }
+
+    let dir = TempDir::new().unwrap();
+    let index_path = dir.path().join("index.json");
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mock_server = rt.block_on(async { MockServer::start().await });
+    rt.block_on(async {
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+    });
+
+    let versions: Vec<String> = ["1.0.0", "1.1.0", "1.2.0"]
+        .iter()
+        .map(|version| {
+            format!(
+                r#""{version}": {{
+          "name": "com.test.vpm.pkg",
+          "version": "{version}",
+          "displayName": "Test Package",
+          "description": "desc",
+          "author": {{ "name": "Author" }},
+          "url": "{}/{version}.zip"
+        }}"#,
+                mock_server.uri()
+            )
+        })
+        .collect();
+    write(
+        &index_path,
+        &format!(
+            r#"{{
+  "name": "Test VPM",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {{
+    "com.test.vpm.pkg": {{
+      "versions": {{
+        {}
+      }}
+    }}
+  }}
+}}"#,
+            versions.join(",\n")
+        ),
+    );
+
+    let args = [
+        "validate",
+        index_path.to_str().unwrap(),
+        "--max-concurrent",
+        "2",
+        "--concurrency-report",
+    ];
+    let output = run_voy(&args, dir.path());
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checked 3 URL(s) in"));
+    assert!(stdout.contains("URLs/s at --max-concurrent 2"));
+
+    let quiet: Vec<&str> = args.iter().copied().chain(["--quiet"]).collect();
+    let output = run_voy(&quiet, dir.path());
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("URLs/s"));
+}
+
+#[test]
+fn validate_fails_on_malformed_json() {
+    let dir = TempDir::new().unwrap();
+    let index_path = dir.path().join("index.json");
+    write(&index_path, "{ not-valid-json }");
+
+    let output = run_voy(&["validate", index_path.to_str().unwrap()], dir.path());
+
+    assert_eq!(output.status.code(), Some(65));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to parse JSON"));
+}
+
+#[test]
+fn completions_succeeds_when_transaction_log_is_corrupted() {
+    let dir = TempDir::new().unwrap();
+    write(
+        &txn_path(&dir.path().join("voyager.toml")),
+        "{ this is not valid json }",
+    );
+
+    let output = run_voy(&["completions", "bash"], dir.path());
+
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Failed to parse JSON"));
+}
+
+#[test]
+fn completions_out_dir_writes_files_for_one_or_every_shell() {
+    let dir = TempDir::new().unwrap();
+    let out_dir = dir.path().join("completions/nested");
+
+    let output = run_voy(
+        &[
+            "completions",
+            "bash",
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(out_dir.join("voy.bash").exists());
+    assert!(!out_dir.join("_voy").exists());
+
+    let output = run_voy(
+        &["completions", "--out-dir", out_dir.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    for name in ["voy.bash", "_voy", "voy.fish", "_voy.ps1", "voy.elv"] {
+        assert!(out_dir.join(name).exists(), "missing {name}");
+    }
+
+    let output = run_voy(&["completions"], dir.path());
+    assert_ne!(output.status.code(), Some(0));
+}
+
+#[test]
+fn init_force_removes_stale_transaction_log() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+
+    write(
+        &txn_path(&config_path),
+        "{ this is stale and should be removed by init --force }",
+    );
+
+    let init_output = run_voy(
+        &[
+            "init",
+            "--force",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--name",
+            "Fresh",
+            "--id",
+            "com.fresh.vpm",
+            "--author",
+            "Author",
+            "--url",
+            "https://example.com/index.json",
+        ],
+        dir.path(),
+    );
+
+    assert_eq!(init_output.status.code(), Some(0));
+    assert!(!txn_path(&config_path).exists());
+
+    let list_output = run_voy(
+        &["list", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(list_output.status.code(), Some(0));
+}
+
+#[test]
+fn init_with_default_config_path_succeeds() {
     let dir = TempDir::new().unwrap();
 
     let output = run_voy(
@@ -629,98 +1174,249 @@ fn lock_check_fails_when_manifest_hash_mismatch() {
 }
 
 #[test]
-fn lock_updates_manifest_hash_when_manifest_changes() {
+fn lock_check_error_format_json_writes_only_the_report_to_stderr() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
     let lock_path = dir.path().join("voyager.lock");
 
-    write(&config_path, &make_manifest_empty("Old"));
-    let old_hash = compute_manifest_hash(&config_path).unwrap();
-    write(&lock_path, &make_lock_content(&old_hash));
-
-    write(&config_path, &make_manifest_empty("New"));
-    let expected_hash = compute_manifest_hash(&config_path).unwrap();
+    write(&config_path, &make_manifest_empty("Test"));
+    write(&lock_path, &make_lock_content("definitely-wrong-hash"));
 
     let output = run_voy(
-        &["lock", "--config", config_path.to_str().unwrap()],
+        &[
+            "lock",
+            "--check",
+            "--error-format",
+            "json",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
         dir.path(),
     );
-    assert_eq!(output.status.code(), Some(0));
 
-    let lock = Lockfile::load(&lock_path).unwrap();
-    assert_eq!(lock.manifest_hash.as_deref(), Some(expected_hash.as_str()));
+    assert_eq!(output.status.code(), Some(78));
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(report["code"], "ManifestHashMismatch");
 }
 
 #[test]
-fn lock_check_fails_when_lock_missing_manifest_hash() {
+fn error_format_json_prints_code_message_and_exit_code() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
     let lock_path = dir.path().join("voyager.lock");
 
     write(&config_path, &make_manifest_empty("Test"));
-    write(
-        &lock_path,
-        r#"version = 1
-packages = []
-"#,
-    );
+    write(&lock_path, &make_lock_content("definitely-wrong-hash"));
 
     let output = run_voy(
-        &["lock", "--check", "--config", config_path.to_str().unwrap()],
+        &[
+            "generate",
+            "--error-format",
+            "json",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
         dir.path(),
     );
 
     assert_eq!(output.status.code(), Some(78));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Manifest hash does not match lock file"));
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(report["code"], "ManifestHashMismatch");
+    assert_eq!(report["exit_code"], 78);
+    assert!(
+        report["message"]
+            .as_str()
+            .unwrap()
+            .contains("Manifest has been modified outside of voyager")
+    );
 }
 
 #[test]
-fn fetch_succeeds_with_empty_packages_and_matching_hash() {
+fn lock_updates_manifest_hash_when_manifest_changes() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
     let lock_path = dir.path().join("voyager.lock");
 
-    write(&config_path, &make_manifest_empty("Test"));
-    let hash = compute_manifest_hash(&config_path).unwrap();
-    write(&lock_path, &make_lock_content(&hash));
+    write(&config_path, &make_manifest_empty("Old"));
+    let old_hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_content(&old_hash));
+
+    write(&config_path, &make_manifest_empty("New"));
+    let expected_hash = compute_manifest_hash(&config_path).unwrap();
 
     let output = run_voy(
-        &[
-            "fetch",
-            "--config",
-            config_path.to_str().unwrap(),
-            "--max-retries",
-            "0",
-            "--max-concurrent",
-            "1",
-        ],
+        &["lock", "--config", config_path.to_str().unwrap()],
         dir.path(),
     );
     assert_eq!(output.status.code(), Some(0));
 
     let lock = Lockfile::load(&lock_path).unwrap();
-    assert_eq!(lock.manifest_hash.as_deref(), Some(hash.as_str()));
-    assert!(lock.packages.is_empty());
+    assert_eq!(lock.manifest_hash.as_deref(), Some(expected_hash.as_str()));
 }
 
 #[test]
-fn init_then_generate_produces_valid_empty_index() {
+fn lock_fix_drops_stale_packages_and_updates_hash() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
-    let index_path = dir.path().join("index.json");
+    let lock_path = dir.path().join("voyager.lock");
 
-    let init = run_voy(
-        &[
-            "init",
-            "--force",
-            "--config",
-            config_path.to_str().unwrap(),
-            "--name",
-            "Fresh",
-            "--id",
-            "com.fresh.vpm",
-            "--author",
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    let lock = format!(
+        "{}{}",
+        make_lock_with_stale_package_versions(&hash),
+        make_lock_with_single_package(&hash)
+            .split_once("[[packages]]")
+            .map(|(_, package)| format!("\n[[packages]]{package}"))
+            .unwrap()
+    );
+    write(&lock_path, &lock);
+
+    write(&config_path, &make_manifest_single_package("Renamed"));
+    let expected_hash = compute_manifest_hash(&config_path).unwrap();
+
+    let output = run_voy(
+        &["lock", "--fix", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Removed stale package com.test.vpm.stale"));
+    assert!(stdout.contains("Updated manifest hash"));
+
+    let lock = Lockfile::load(&lock_path).unwrap();
+    assert_eq!(lock.manifest_hash.as_deref(), Some(expected_hash.as_str()));
+    let ids: Vec<&str> = lock.packages.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["com.test.vpm.package1"]);
+    assert!(!dir.path().join("voyager.txn").exists());
+}
+
+#[test]
+fn lock_print_outputs_updated_lock_without_writing() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Old"));
+    let old_lock = make_lock_content(&compute_manifest_hash(&config_path).unwrap());
+    write(&lock_path, &old_lock);
+
+    write(&config_path, &make_manifest_empty("New"));
+    let expected_hash = compute_manifest_hash(&config_path).unwrap();
+
+    let output = run_voy(
+        &["lock", "--print", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let printed_path = dir.path().join("printed.lock");
+    write(&printed_path, &String::from_utf8_lossy(&output.stdout));
+    let printed = Lockfile::load(&printed_path).unwrap();
+    assert_eq!(
+        printed.manifest_hash.as_deref(),
+        Some(expected_hash.as_str())
+    );
+
+    assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), old_lock);
+    assert!(!dir.path().join("voyager.txn").exists());
+}
+
+#[test]
+fn lock_print_outputs_the_reconciled_lock() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    let lock = make_lock_with_stale_package_versions(&hash);
+    write(&lock_path, &lock);
+
+    let output = run_voy(
+        &["lock", "--print", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let printed_path = dir.path().join("printed.lock");
+    write(&printed_path, &String::from_utf8_lossy(&output.stdout));
+    let printed = Lockfile::load(&printed_path).unwrap();
+    let ids: Vec<&str> = printed.packages.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["com.test.vpm.package1"]);
+    assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), lock);
+}
+
+#[test]
+fn lock_check_fails_when_lock_missing_manifest_hash() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    write(
+        &lock_path,
+        r#"version = 1
+packages = []
+"#,
+    );
+
+    let output = run_voy(
+        &["lock", "--check", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+
+    assert_eq!(output.status.code(), Some(78));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Manifest has been modified outside of voyager"));
+}
+
+#[test]
+fn fetch_succeeds_with_empty_packages_and_matching_hash() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_content(&hash));
+
+    let output = run_voy(
+        &[
+            "fetch",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--max-retries",
+            "0",
+            "--max-concurrent",
+            "1",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let lock = Lockfile::load(&lock_path).unwrap();
+    assert_eq!(lock.manifest_hash.as_deref(), Some(hash.as_str()));
+    assert!(lock.packages.is_empty());
+}
+
+#[test]
+fn init_then_generate_produces_valid_empty_index() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let index_path = dir.path().join("index.json");
+
+    let init = run_voy(
+        &[
+            "init",
+            "--force",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--name",
+            "Fresh",
+            "--id",
+            "com.fresh.vpm",
+            "--author",
             "Author",
             "--url",
             "https://example.com/index.json",
@@ -806,6 +1502,54 @@ fn remove_updates_manifest_and_lockfile() {
     assert_eq!(lock.manifest_hash.as_deref(), Some(expected_hash.as_str()));
 }
 
+#[test]
+fn prune_keeps_latest_versions_and_updates_lockfile() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "prune",
+            "com.test.vpm.package1",
+            "--keep-latest",
+            "1",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Pruned 1 version(s) from com.test.vpm.package1"));
+
+    let lock = Lockfile::load(&lock_path).unwrap();
+    let versions: Vec<_> = lock.packages[0]
+        .versions
+        .iter()
+        .map(|v| v.version.as_str())
+        .collect();
+    assert_eq!(versions, vec!["2.0.0"]);
+    assert_eq!(lock.manifest_hash.as_deref(), Some(hash.as_str()));
+
+    let output = run_voy(
+        &[
+            "prune",
+            "com.test.vpm.missing",
+            "--keep-latest",
+            "1",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(78));
+}
+
 #[test]
 fn add_fails_fast_for_invalid_repository_format() {
     let dir = TempDir::new().unwrap();
@@ -903,6 +1647,147 @@ fn info_prints_versions_from_lockfile() {
     assert!(stdout.contains("1.0.0"));
 }
 
+#[test]
+fn info_raw_prints_stored_hash_and_manifest() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "info",
+            "com.test.vpm.package1",
+            "--raw",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let versions = json.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[1]["version"], "1.0.0");
+    assert_eq!(versions[1]["hash"], "sha256:111");
+    assert_eq!(versions[1]["manifest"]["displayName"], "Test Package");
+    assert_eq!(versions[1]["manifest"]["version"], "1.0.0");
+}
+
+#[test]
+fn info_compare_reports_changed_fields_as_json() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "info",
+            "com.test.vpm.package1",
+            "--compare",
+            "1.0.0",
+            "2.0.0",
+            "--format",
+            "json",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["from"], "1.0.0");
+    assert_eq!(json["to"], "2.0.0");
+    let fields: Vec<_> = json["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, vec!["url", "version"]);
+}
+
+#[test]
+fn lock_comment_header_is_written_and_lockfile_still_loads() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    let output = run_voy(
+        &[
+            "init",
+            "--force",
+            "--lock-comment",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--name",
+            "Commented",
+            "--id",
+            "com.commented.vpm",
+            "--author",
+            "Author",
+            "--url",
+            "https://example.com/index.json",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let content = std::fs::read_to_string(&lock_path).unwrap();
+    assert!(content.starts_with("# This file is generated by voyager."));
+    assert!(Lockfile::load(&lock_path).unwrap().has_header_comment());
+
+    let output = run_voy(
+        &["list", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn urls_writes_exactly_the_locked_version_urls() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let list_path = dir.path().join("urls.txt");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "urls",
+            "--output",
+            list_path.to_str().unwrap(),
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let content = std::fs::read_to_string(&list_path).unwrap();
+    let mut urls: Vec<&str> = content.lines().collect();
+    urls.sort();
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/package-1.0.0.zip",
+            "https://example.com/package-2.0.0.zip",
+        ]
+    );
+}
+
 #[test]
 fn info_shows_fetch_hint_when_versions_are_not_fetched_yet() {
     let dir = TempDir::new().unwrap();
@@ -979,6 +1864,118 @@ fn lock_check_succeeds_when_manifest_hash_matches() {
     assert!(stdout.contains("Manifest hash matches lock file"));
 }
 
+#[test]
+fn lock_check_validates_manifest_from_stdin() {
+    let dir = TempDir::new().unwrap();
+    let check = |manifest: &str| {
+        let mut child = Command::new(voy_bin())
+            .args(["lock", "--check", "--config", "-"])
+            .current_dir(dir.path())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("failed to run voy");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(manifest.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let output = check(&make_manifest_single_package("Test"));
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 package(s)"));
+    assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+
+    let invalid = make_manifest_single_package("Test").replace(
+        "id = \"com.test.vpm.package1\"",
+        "id = \"org.other.package1\"",
+    );
+    let output = check(&invalid);
+    assert_eq!(output.status.code(), Some(78));
+
+    let output = run_voy(&["lock", "--config", "-"], dir.path());
+    assert_eq!(output.status.code(), Some(78));
+}
+
+#[test]
+fn lock_check_accepts_legacy_unprefixed_manifest_hash() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    let legacy = hash.strip_prefix("sha256:").unwrap();
+    write(&lock_path, &make_lock_content(legacy));
+
+    let output = run_voy(
+        &["lock", "--check", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_voy(
+        &["lock", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let lockfile = Lockfile::load(&lock_path).unwrap();
+    assert_eq!(lockfile.manifest_hash.as_deref(), Some(hash.as_str()));
+}
+
+#[test]
+fn lock_manifest_hash_algo_switch_is_detected() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_empty("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_content(&hash));
+
+    let config = config_path.to_str().unwrap();
+    let output = run_voy(
+        &[
+            "lock",
+            "--check",
+            "--manifest-hash-algo",
+            "sha512",
+            "--config",
+            config,
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(78));
+
+    let output = run_voy(
+        &["lock", "--manifest-hash-algo", "sha512", "--config", config],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let lockfile = Lockfile::load(&lock_path).unwrap();
+    assert!(
+        lockfile
+            .manifest_hash
+            .as_deref()
+            .is_some_and(|h| h.starts_with("sha512:"))
+    );
+
+    let output = run_voy(&["lock", "--check", "--config", config], dir.path());
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_voy(&["list", "--config", config], dir.path());
+    assert_eq!(output.status.code(), Some(0));
+}
+
 #[test]
 fn lock_fails_when_config_file_is_missing() {
     let dir = TempDir::new().unwrap();
@@ -1006,19 +2003,275 @@ fn lock_fails_when_lock_file_is_missing() {
         dir.path(),
     );
 
-    assert_eq!(output.status.code(), Some(78));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Lock file"));
-    assert!(stderr.contains("Run 'voy fetch' first."));
+    assert_eq!(output.status.code(), Some(78));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lock file"));
+    assert!(stderr.contains("Run 'voy fetch' first."));
+}
+
+#[test]
+fn generate_fails_when_lock_file_is_missing() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let output_path = dir.path().join("index.json");
+
+    write(&config_path, &make_manifest_empty("Test"));
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+
+    assert_eq!(output.status.code(), Some(78));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lock file"));
+    assert!(stderr.contains("Run 'voy fetch' first."));
+}
+
+#[test]
+fn generate_rolls_back_partial_transaction_before_missing_lock_error() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let output_path = dir.path().join("index.json");
+
+    let old_manifest = make_manifest_empty("Old");
+    let new_manifest = make_manifest_empty("New");
+
+    write(&config_path, &new_manifest);
+    let new_hash = compute_manifest_hash(&config_path).unwrap();
+    let new_lock = make_lock_content(&new_hash);
+
+    write_txn(&config_path, &old_manifest, None, &new_manifest, &new_lock);
+
+    if lock_path.exists() {
+        std::fs::remove_file(&lock_path).unwrap();
+    }
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+
+    assert_eq!(output.status.code(), Some(78));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lock file"));
+    assert!(stderr.contains("Run 'voy fetch' first."));
+
+    let persisted_manifest = std::fs::read_to_string(&config_path).unwrap();
+    assert_eq!(persisted_manifest, old_manifest);
+    assert!(!txn_path(&config_path).exists());
+}
+
+#[test]
+fn generate_outputs_versions_from_lockfile() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let output_path = dir.path().join("index.json");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let output_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(output_path).unwrap()).unwrap();
+
+    let versions = &output_json["packages"]["com.test.vpm.package1"]["versions"];
+    assert!(versions.get("2.0.0").is_some());
+    assert!(versions.get("1.0.0").is_some());
+}
+
+#[test]
+fn generate_offline_succeeds_without_network() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let output_path = dir.path().join("index.json");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    // Route every request to a closed port so any network access would fail.
+    let output = Command::new(voy_bin())
+        .args([
+            "generate",
+            "--offline",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ])
+        .current_dir(dir.path())
+        .env("HTTP_PROXY", "http://127.0.0.1:9")
+        .env("HTTPS_PROXY", "http://127.0.0.1:9")
+        .env("ALL_PROXY", "http://127.0.0.1:9")
+        .env_remove("NO_PROXY")
+        .output()
+        .expect("failed to run voy");
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(output_path).unwrap()).unwrap();
+    let versions = &output_json["packages"]["com.test.vpm.package1"]["versions"];
+    assert!(versions.get("2.0.0").is_some());
+}
+
+#[test]
+fn generate_minify_writes_compact_json() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let output_path = dir.path().join("index.json");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+            "--minify",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let index = std::fs::read_to_string(&output_path).unwrap();
+    assert!(!index.contains('\n'));
+    let output_json: serde_json::Value = serde_json::from_str(&index).unwrap();
+    assert!(
+        output_json["packages"]["com.test.vpm.package1"]["versions"]
+            .get("2.0.0")
+            .is_some()
+    );
+}
+
+#[test]
+fn generate_output_dash_writes_index_to_stdout() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            "-",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let output_json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(
+        output_json["packages"]["com.test.vpm.package1"]["versions"]
+            .get("2.0.0")
+            .is_some()
+    );
+    assert!(!dir.path().join("-").exists());
+}
+
+#[test]
+fn generate_assets_lists_every_locked_version() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let assets_path = dir.path().join("assets.json");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    // The locked package.json URL differs from the zip URL the index lists.
+    let lock = make_lock_with_two_versions(&hash)
+        .replacen(
+            "url = \"https://example.com/package-2.0.0.zip\"\nhash",
+            "url = \"https://example.com/v2.0.0/package.json\"\nhash",
+            1,
+        )
+        .replacen(
+            "license = \"MIT\"\nurl = \"https://example.com/package-2.0.0.zip\"",
+            &format!(
+                "license = \"MIT\"\nzipSHA256 = \"{}\"\nurl = \"https://example.com/package-2.0.0.zip\"",
+                "ab".repeat(32)
+            ),
+            1,
+        );
+    write(&lock_path, &lock);
+
+    let output = run_voy(
+        &[
+            "generate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--assets",
+            assets_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let assets: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&assets_path).unwrap()).unwrap();
+    let lock = Lockfile::load(&lock_path).unwrap();
+    let total: usize = lock.packages.iter().map(|p| p.versions.len()).sum();
+    assert_eq!(assets.as_array().unwrap().len(), total);
+    assert_eq!(assets[0]["package_id"], "com.test.vpm.package1");
+    assert_eq!(assets[0]["version"], "2.0.0");
+    assert_eq!(assets[0]["url"], "https://example.com/package-2.0.0.zip");
+    assert_eq!(assets[0]["hash"], "ab".repeat(32));
+    assert_eq!(assets[1]["hash"], serde_json::Value::Null);
 }
 
 #[test]
-fn generate_fails_when_lock_file_is_missing() {
+fn generate_writes_output_hash_file_matching_index_bytes() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
     let output_path = dir.path().join("index.json");
 
-    write(&config_path, &make_manifest_empty("Test"));
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
 
     let output = run_voy(
         &[
@@ -1027,35 +2280,28 @@ fn generate_fails_when_lock_file_is_missing() {
             config_path.to_str().unwrap(),
             "--output",
             output_path.to_str().unwrap(),
+            "--output-hash-file",
         ],
         dir.path(),
     );
+    assert_eq!(output.status.code(), Some(0));
 
-    assert_eq!(output.status.code(), Some(78));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Lock file"));
-    assert!(stderr.contains("Run 'voy fetch' first."));
+    let index = std::fs::read_to_string(&output_path).unwrap();
+    let sidecar = std::fs::read_to_string(dir.path().join("index.json.sha256")).unwrap();
+    assert_eq!(sidecar.trim_end(), compute_hash(&index));
 }
 
 #[test]
-fn generate_rolls_back_partial_transaction_before_missing_lock_error() {
+fn generate_writes_bare_hex_hash_to_explicit_path() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
     let lock_path = dir.path().join("voyager.lock");
     let output_path = dir.path().join("index.json");
+    let hash_path = dir.path().join("checksums").join("index.hex");
 
-    let old_manifest = make_manifest_empty("Old");
-    let new_manifest = make_manifest_empty("New");
-
-    write(&config_path, &new_manifest);
-    let new_hash = compute_manifest_hash(&config_path).unwrap();
-    let new_lock = make_lock_content(&new_hash);
-
-    write_txn(&config_path, &old_manifest, None, &new_manifest, &new_lock);
-
-    if lock_path.exists() {
-        std::fs::remove_file(&lock_path).unwrap();
-    }
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
 
     let output = run_voy(
         &[
@@ -1064,49 +2310,65 @@ fn generate_rolls_back_partial_transaction_before_missing_lock_error() {
             config_path.to_str().unwrap(),
             "--output",
             output_path.to_str().unwrap(),
+            "--output-hash-file",
+            hash_path.to_str().unwrap(),
+            "--hash-format",
+            "hex",
         ],
         dir.path(),
     );
+    assert_eq!(output.status.code(), Some(0));
 
-    assert_eq!(output.status.code(), Some(78));
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Lock file"));
-    assert!(stderr.contains("Run 'voy fetch' first."));
-
-    let persisted_manifest = std::fs::read_to_string(&config_path).unwrap();
-    assert_eq!(persisted_manifest, old_manifest);
-    assert!(!txn_path(&config_path).exists());
+    let index = std::fs::read_to_string(&output_path).unwrap();
+    let sidecar = std::fs::read_to_string(&hash_path).unwrap();
+    let expected = compute_hash(&index);
+    assert_eq!(
+        sidecar.trim_end(),
+        expected.strip_prefix("sha256:").unwrap()
+    );
 }
 
 #[test]
-fn generate_outputs_versions_from_lockfile() {
+fn remove_uses_explicit_lock_path_and_its_transaction_log() {
     let dir = TempDir::new().unwrap();
     let config_path = dir.path().join("voyager.toml");
-    let lock_path = dir.path().join("voyager.lock");
-    let output_path = dir.path().join("index.json");
+    let lock_path = dir.path().join("index-a.lock");
+    let stale_txn_path = dir.path().join("index-a.txn");
 
     write(&config_path, &make_manifest_single_package("Test"));
     let hash = compute_manifest_hash(&config_path).unwrap();
-    write(&lock_path, &make_lock_with_two_versions(&hash));
+    write(&lock_path, &make_lock_with_single_package(&hash));
+    let lock_content = std::fs::read_to_string(&lock_path).unwrap();
+    // A transaction that never started writing is rolled back to these files.
+    let manifest_content = std::fs::read_to_string(&config_path).unwrap();
+    write(
+        &stale_txn_path,
+        &serde_json::json!({
+            "old_manifest": manifest_content,
+            "old_lock": lock_content,
+            "new_manifest": "unused",
+            "new_lock": "unused",
+        })
+        .to_string(),
+    );
 
     let output = run_voy(
         &[
-            "generate",
+            "remove",
+            "com.test.vpm.package1",
             "--config",
             config_path.to_str().unwrap(),
-            "--output",
-            output_path.to_str().unwrap(),
+            "--lock",
+            lock_path.to_str().unwrap(),
         ],
         dir.path(),
     );
     assert_eq!(output.status.code(), Some(0));
 
-    let output_json: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(output_path).unwrap()).unwrap();
-
-    let versions = &output_json["packages"]["com.test.vpm.package1"]["versions"];
-    assert!(versions.get("2.0.0").is_some());
-    assert!(versions.get("1.0.0").is_some());
+    let lock = Lockfile::load(&lock_path).unwrap();
+    assert!(lock.packages.is_empty());
+    assert!(!stale_txn_path.exists());
+    assert!(!dir.path().join("voyager.lock").exists());
 }
 
 #[test]
@@ -1186,6 +2448,101 @@ fn list_package_shows_versions_in_descending_order() {
     assert!(pos_v2 < pos_v1);
 }
 
+#[test]
+fn list_json_prints_version_strings() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &["list", "--json", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "id": "com.test.vpm.package1",
+            "repository": "testowner/testrepo",
+            "versions": ["2.0.0", "1.0.0"],
+        }])
+    );
+}
+
+#[test]
+fn list_json_without_lock_lists_manifest_packages_with_no_versions() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+
+    let output = run_voy(
+        &["list", "--json", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "id": "com.test.vpm.package1",
+            "repository": "testowner/testrepo",
+            "versions": [],
+        }])
+    );
+}
+
+#[test]
+fn list_json_detailed_includes_url_and_hash_per_version() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+
+    let output = run_voy(
+        &[
+            "list",
+            "--json",
+            "--detailed",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let package = &json[0];
+    assert_eq!(package["repository"], "testowner/testrepo");
+    assert_eq!(
+        package["versions"],
+        serde_json::json!([
+            {
+                "version": "2.0.0",
+                "tag": "v2.0.0",
+                "url": "https://example.com/package-2.0.0.zip",
+                "hash": "sha256:222",
+            },
+            {
+                "version": "1.0.0",
+                "tag": "v1.0.0",
+                "url": "https://example.com/package-1.0.0.zip",
+                "hash": "sha256:111",
+            },
+        ])
+    );
+}
+
 #[test]
 fn list_shows_package_with_no_versions_when_lock_is_missing() {
     let dir = TempDir::new().unwrap();
@@ -1340,3 +2697,278 @@ fn generate_is_safe_under_parallel_writes() {
         leftovers
     );
 }
+
+#[test]
+fn diff_against_published_index_reports_added_version() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let published_path = dir.path().join("published.json");
+
+    write(&config_path, &make_manifest_single_package("Test"));
+    let hash = compute_manifest_hash(&config_path).unwrap();
+    write(&lock_path, &make_lock_with_two_versions(&hash));
+    write(
+        &published_path,
+        r#"{
+  "name": "Test",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {
+    "com.test.vpm.package1": {
+      "versions": {
+        "1.0.0": {
+          "name": "com.test.vpm.package1",
+          "version": "1.0.0",
+          "displayName": "Test Package",
+          "description": "A test package",
+          "unity": "2022.3",
+          "license": "MIT",
+          "author": { "name": "Test Author" },
+          "url": "https://example.com/package-1.0.0.zip"
+        }
+      }
+    }
+  }
+}"#,
+    );
+
+    let output = run_voy(
+        &[
+            "diff",
+            "--against",
+            published_path.to_str().unwrap(),
+            "--format",
+            "json",
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "packages": [{
+                "id": "com.test.vpm.package1",
+                "change": "modified",
+                "added": ["2.0.0"],
+                "removed": [],
+                "changed": [],
+            }]
+        })
+    );
+}
+
+#[test]
+fn diff_two_index_files_exits_zero_only_when_identical() {
+    let dir = TempDir::new().unwrap();
+    let old_path = dir.path().join("old.json");
+    let new_path = dir.path().join("new.json");
+    let index = |url: &str| {
+        format!(
+            r#"{{
+  "name": "Test",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Author",
+  "packages": {{
+    "com.test.vpm.package1": {{
+      "versions": {{
+        "1.0.0": {{
+          "name": "com.test.vpm.package1",
+          "version": "1.0.0",
+          "displayName": "Test Package",
+          "description": "A test package",
+          "author": {{ "name": "Test Author" }},
+          "url": "{url}"
+        }}
+      }}
+    }}
+  }}
+}}"#
+        )
+    };
+    write(&old_path, &index("https://example.com/package-1.0.0.zip"));
+    write(&new_path, &index("https://example.com/package-1.0.0.zip"));
+    let (old, new) = (old_path.to_str().unwrap(), new_path.to_str().unwrap());
+
+    let identical = run_voy(&["diff", old, new], dir.path());
+    assert_eq!(identical.status.code(), Some(0));
+
+    write(
+        &new_path,
+        &index("https://mirror.example.com/package-1.0.0.zip"),
+    );
+    let changed = run_voy(&["diff", old, new, "--format", "json"], dir.path());
+    assert_eq!(changed.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&changed.stdout).unwrap();
+    assert_eq!(json["packages"][0]["changed"], serde_json::json!(["1.0.0"]));
+}
+
+#[test]
+fn error_context_prints_source_chain_only_when_requested() {
+    if !can_bind_localhost() {
+        return;
+    }
+    // A port that was just free refuses the connection, so reqwest reports
+    // an error whose causes are not part of the summary.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let dir = TempDir::new().unwrap();
+    let url = format!("http://127.0.0.1:{}/index.json", port);
+    let run = |extra: &[&str]| {
+        Command::new(voy_bin())
+            .args(["diff", "--against", &url, "index.json"])
+            .args(extra)
+            .current_dir(dir.path())
+            .env_remove("HTTP_PROXY")
+            .env_remove("HTTPS_PROXY")
+            .env_remove("ALL_PROXY")
+            .env_remove("http_proxy")
+            .env_remove("https_proxy")
+            .env_remove("all_proxy")
+            .output()
+            .expect("failed to run voy")
+    };
+
+    let summary = run(&[]);
+    let detailed = run(&["--error-context"]);
+
+    assert_eq!(summary.status.code(), detailed.status.code());
+    let summary_stderr = String::from_utf8_lossy(&summary.stderr);
+    let detailed_stderr = String::from_utf8_lossy(&detailed.stderr);
+    assert!(summary_stderr.contains("HTTP request failed"));
+    assert!(!summary_stderr.contains("caused by:"));
+    assert!(detailed_stderr.contains("HTTP request failed"));
+    assert!(detailed_stderr.contains("caused by:"));
+}
+
+#[test]
+fn migrate_imports_index_and_refuses_to_overwrite_without_force() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let index_path = dir.path().join("index.json");
+
+    write(
+        &index_path,
+        r#"{
+  "name": "Test",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Test Author",
+  "packages": {
+    "com.test.vpm.package1": {
+      "versions": {
+        "1.0.0": {
+          "name": "com.test.vpm.package1",
+          "version": "1.0.0",
+          "displayName": "Package 1",
+          "description": "Test package",
+          "author": { "name": "Test Author" },
+          "url": "https://github.com/testowner/testrepo/releases/download/v1.0.0/package.zip"
+        }
+      }
+    }
+  }
+}"#,
+    );
+
+    let args = [
+        "migrate",
+        index_path.to_str().unwrap(),
+        "--config",
+        config_path.to_str().unwrap(),
+    ];
+    let output = run_voy(&args, dir.path());
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let manifest = Manifest::load(&config_path).unwrap();
+    assert_eq!(manifest.vpm.id, "com.test.vpm");
+    assert_eq!(
+        manifest.packages[0].repository.to_string(),
+        "testowner/testrepo"
+    );
+    let lockfile = Lockfile::load(&lock_path).unwrap();
+    assert_eq!(
+        lockfile.manifest_hash.as_deref(),
+        Some(compute_manifest_hash(&config_path).unwrap().as_str())
+    );
+    assert_eq!(lockfile.packages[0].versions[0].tag, "v1.0.0");
+
+    let output = run_voy(&args, dir.path());
+    assert_ne!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+    let mut forced = args.to_vec();
+    forced.push("--force");
+    let output = run_voy(&forced, dir.path());
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn verify_after_migrate_reports_versions_that_need_a_fetch() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("voyager.toml");
+    let lock_path = dir.path().join("voyager.lock");
+    let index_path = dir.path().join("index.json");
+
+    write(
+        &index_path,
+        r#"{
+  "name": "Test",
+  "id": "com.test.vpm",
+  "url": "https://example.com/index.json",
+  "author": "Test Author",
+  "packages": {
+    "com.test.vpm.package1": {
+      "versions": {
+        "1.0.0": {
+          "name": "com.test.vpm.package1",
+          "version": "1.0.0",
+          "displayName": "Package 1",
+          "description": "Test package",
+          "author": { "name": "Test Author" },
+          "url": "https://github.com/testowner/testrepo/releases/download/v1.0.0/package.zip"
+        }
+      }
+    }
+  }
+}"#,
+    );
+
+    let output = run_voy(
+        &[
+            "migrate",
+            index_path.to_str().unwrap(),
+            "--config",
+            config_path.to_str().unwrap(),
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(Lockfile::load(&lock_path).unwrap().packages[0].versions[0].unverified);
+
+    // Unverified versions are reported without being downloaded, so this
+    // needs no network.
+    let output = run_voy(
+        &["verify", "--config", config_path.to_str().unwrap()],
+        dir.path(),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_ne!(output.status.code(), Some(0), "{}", stdout);
+    assert!(stdout.contains("voy fetch"), "{}", stdout);
+    assert!(!stdout.contains("download failed"), "{}", stdout);
+}
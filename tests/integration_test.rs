@@ -1,18 +1,24 @@
 mod common;
 
 use async_trait::async_trait;
+use clap::Parser;
 use common::{SAMPLE_CONFIG, SAMPLE_LOCKFILE, SAMPLE_LOCKFILE_NO_HASH, TestEnv};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use voyager::cli::{AddArgs, ConfigPaths, LockArgs, RemoveArgs};
+use voyager::cli::{
+    AddArgs, Cli, Commands, ConfigPaths, GenerateArgs, GitHubAuthArgs, HashFormat, LockArgs,
+    OutdatedArgs, OutputFormat, PackageOrderArg, RemoveArgs, VersionOrderArg,
+};
 use voyager::commands;
-use voyager::config::{Manifest, Package, Vpm};
+use voyager::config::{ConfigFormat, Manifest, Package, Vpm};
 use voyager::context::AppContext;
 use voyager::domain::{Release, Repository};
-use voyager::error::{Error, Result};
-use voyager::infra::GitHubApi;
-use voyager::lock::{LockedPackage, Lockfile, compute_manifest_hash_from_manifest};
-use voyager::services::{check_and_load, generate_from_lockfile};
+use voyager::error::{Error, ExitCode, Result};
+use voyager::infra::{GitHubApi, MemorySink, RetryObserver};
+use voyager::lock::{LockedPackage, LockedVersion, Lockfile, compute_manifest_hash_from_manifest};
+use voyager::services::{
+    DEFAULT_MAX_INDEX_SIZE, FetcherConfig, check_and_load, generate_from_lockfile,
+};
 
 struct TestGitHub;
 
@@ -22,11 +28,12 @@ impl GitHubApi for TestGitHub {
         Ok(Vec::new())
     }
 
-    async fn download_assets(
+    async fn download_assets<'a>(
         &self,
         _releases: Vec<Release>,
         _max_concurrent: usize,
         _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
     ) -> Vec<(Release, Result<String>)> {
         Vec::new()
     }
@@ -34,6 +41,42 @@ impl GitHubApi for TestGitHub {
     async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
         Ok(())
     }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
+}
+
+/// Fails every request, as if GitHub were unreachable.
+struct OfflineGitHub;
+
+#[async_trait]
+impl GitHubApi for OfflineGitHub {
+    async fn get_releases(&self, repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        Err(Error::RepositoryNotFound(repo.to_string()))
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        _releases: Vec<Release>,
+        _max_concurrent: usize,
+        _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        Vec::new()
+    }
+
+    async fn verify_repository(&self, repo: &Repository) -> Result<()> {
+        Err(Error::RepositoryNotFound(repo.to_string()))
+    }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
 }
 
 struct MutatingGitHub {
@@ -46,11 +89,12 @@ impl GitHubApi for MutatingGitHub {
         Ok(Vec::new())
     }
 
-    async fn download_assets(
+    async fn download_assets<'a>(
         &self,
         _releases: Vec<Release>,
         _max_concurrent: usize,
         _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
     ) -> Vec<(Release, Result<String>)> {
         Vec::new()
     }
@@ -63,10 +107,203 @@ impl GitHubApi for MutatingGitHub {
         changed.save(&self.config_path)?;
         Ok(())
     }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
+}
+
+/// Lists a single release whose package.json is named `name`.
+struct NamedPackageGitHub {
+    name: &'static str,
+}
+
+#[async_trait]
+impl GitHubApi for NamedPackageGitHub {
+    async fn get_releases(&self, _repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        Ok(vec![
+            Release::new("v2.0.0".to_string(), None),
+            Release::new(
+                "v1.0.0".to_string(),
+                Some("https://assets.example/v1.0.0.json".to_string()),
+            ),
+        ])
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        _max_concurrent: usize,
+        _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        releases
+            .into_iter()
+            .map(|release| {
+                let content = format!(r#"{{"name": "{}", "version": "1.0.0"}}"#, self.name);
+                (release, Ok(content))
+            })
+            .collect()
+    }
+
+    async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
+        Ok(())
+    }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
+}
+
+/// Lists v1.0.0 and v2.0.0 for every repository; only the v1.0.0 asset downloads.
+struct PartiallyFailingGitHub;
+
+#[async_trait]
+impl GitHubApi for PartiallyFailingGitHub {
+    async fn get_releases(&self, _repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        Ok(["v2.0.0", "v1.0.0"]
+            .into_iter()
+            .map(|tag| {
+                Release::new(
+                    tag.to_string(),
+                    Some(format!("https://assets.example/{tag}.json")),
+                )
+            })
+            .collect())
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        _max_concurrent: usize,
+        _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        releases
+            .into_iter()
+            .map(|release| {
+                let result = if release.version() == "1.0.0" {
+                    Ok(r#"{
+  "name": "com.test.vpm.pkg",
+  "version": "1.0.0",
+  "displayName": "Pkg",
+  "description": "desc",
+  "unity": "2022.3",
+  "author": {"name": "Author", "email": "author@example.com"},
+  "url": "https://download.example/pkg-1.0.0.zip"
+}"#
+                    .to_string())
+                } else {
+                    Err(Error::PackageJsonNotFound {
+                        tag: release.tag().to_string(),
+                    })
+                };
+                (release, result)
+            })
+            .collect()
+    }
+
+    async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
+        Ok(())
+    }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
+}
+
+/// Lists no releases, recording each repository it is asked about.
+#[derive(Default)]
+struct RecordingGitHub {
+    release_calls: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl GitHubApi for RecordingGitHub {
+    async fn get_releases(&self, repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        self.release_calls.lock().unwrap().push(repo.to_string());
+        Ok(Vec::new())
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        _releases: Vec<Release>,
+        _max_concurrent: usize,
+        _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        Vec::new()
+    }
+
+    async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
+        Ok(())
+    }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
+}
+
+/// Lists v1.0.0 for every repository with a complete package.json asset.
+struct SingleVersionGitHub;
+
+#[async_trait]
+impl GitHubApi for SingleVersionGitHub {
+    async fn get_releases(&self, _repo: &Repository, _asset_name: &str) -> Result<Vec<Release>> {
+        Ok(vec![Release::new(
+            "v1.0.0".to_string(),
+            Some("https://assets.example/v1.0.0.json".to_string()),
+        )])
+    }
+
+    async fn download_assets<'a>(
+        &self,
+        releases: Vec<Release>,
+        _max_concurrent: usize,
+        _max_retries: u32,
+        _retry_observer: Option<&'a dyn RetryObserver>,
+    ) -> Vec<(Release, Result<String>)> {
+        releases
+            .into_iter()
+            .map(|release| {
+                let content = r#"{
+  "name": "com.test.vpm.pkg",
+  "version": "1.0.0",
+  "displayName": "Pkg",
+  "description": "desc",
+  "unity": "2022.3",
+  "author": {"name": "Author", "email": "author@example.com"},
+  "url": "https://download.example/pkg-1.0.0.zip"
+}"#;
+                (release, Ok(content.to_string()))
+            })
+            .collect()
+    }
+
+    async fn verify_repository(&self, _repo: &Repository) -> Result<()> {
+        Ok(())
+    }
+
+    async fn download_file(&self, url: &str, _max_retries: u32) -> Result<Vec<u8>> {
+        Err(Error::ConfigValidation(format!(
+            "unexpected download: {url}"
+        )))
+    }
 }
 
 fn sample_manifest(name: &str, packages: &[(&str, &str)]) -> Manifest {
     Manifest {
+        include: Vec::new(),
+        defaults: Default::default(),
+        registry: Default::default(),
         vpm: Vpm {
             id: "com.test.vpm".to_string(),
             name: name.to_string(),
@@ -78,32 +315,39 @@ fn sample_manifest(name: &str, packages: &[(&str, &str)]) -> Manifest {
             .map(|(id, repo)| Package {
                 id: (*id).to_string(),
                 repository: Repository::parse(repo).unwrap(),
+                suffix: None,
+                tags: Vec::new(),
+                versions: Vec::new(),
+                version_range: None,
+                asset_name: None,
             })
             .collect(),
+        included: Default::default(),
+        format: Default::default(),
     }
 }
 
 fn lockfile_with_packages(manifest_hash: &str, packages: &[(&str, &str)]) -> Lockfile {
-    Lockfile {
-        version: 1,
-        manifest_hash: Some(manifest_hash.to_string()),
-        packages: packages
-            .iter()
-            .map(|(id, repo)| LockedPackage {
-                id: (*id).to_string(),
-                repository: Repository::parse(repo).unwrap(),
-                versions: Vec::new(),
-            })
-            .collect(),
-    }
+    let mut lockfile = Lockfile::new();
+    lockfile.manifest_hash = Some(manifest_hash.to_string());
+    lockfile.packages = packages
+        .iter()
+        .map(|(id, repo)| LockedPackage {
+            id: (*id).to_string(),
+            repository: Repository::parse(repo).unwrap(),
+            versions: Vec::new(),
+            retained: false,
+        })
+        .collect();
+    lockfile
 }
 
-fn txn_path(config_path: &Path) -> PathBuf {
-    config_path.with_extension("txn")
+fn txn_path(lock_path: &Path) -> PathBuf {
+    lock_path.with_extension("txn")
 }
 
 fn write_txn(
-    config_path: &Path,
+    lock_path: &Path,
     old_manifest: &str,
     old_lock: Option<&str>,
     new_manifest: &str,
@@ -116,7 +360,7 @@ fn write_txn(
         "new_lock": new_lock,
     });
     std::fs::write(
-        txn_path(config_path),
+        txn_path(lock_path),
         serde_json::to_string_pretty(&tx_json).unwrap(),
     )
     .unwrap();
@@ -142,7 +386,7 @@ async fn add_recovers_partial_transaction_before_writing() -> Result<()> {
     let new_lock_content = toml::to_string_pretty(&new_lock).unwrap();
 
     write_txn(
-        &env.config_path,
+        &env.lock_path,
         &old_manifest_content,
         Some(&old_lock_content),
         &new_manifest_content,
@@ -156,13 +400,18 @@ async fn add_recovers_partial_transaction_before_writing() -> Result<()> {
         AddArgs {
             repository: "owner/repo".to_string(),
             id: Some("com.test.vpm.added".to_string()),
-            github_token: None,
+            infer_id: false,
+            skip_verify: false,
+            github_auth: GitHubAuthArgs::default(),
+            gitlab_token: None,
+            gitea_token: None,
+            format: OutputFormat::Text,
         },
         &ctx,
     )
     .await?;
 
-    assert!(!txn_path(&env.config_path).exists());
+    assert!(!txn_path(&env.lock_path).exists());
 
     let manifest = Manifest::load(&env.config_path)?;
     assert_eq!(manifest.vpm.name, "Old");
@@ -176,6 +425,209 @@ async fn add_recovers_partial_transaction_before_writing() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn verify_recovers_partial_transaction_before_reading_lock() -> Result<()> {
+    let env = TestEnv::new();
+
+    let old_manifest = sample_manifest("Old", &[]);
+    old_manifest.save(&env.config_path)?;
+    let old_manifest_content = std::fs::read_to_string(&env.config_path).unwrap();
+    let old_hash = compute_manifest_hash_from_manifest(&old_manifest, &env.config_path)?;
+    lockfile_with_packages(&old_hash, &[]).save(&env.lock_path)?;
+    let old_lock_content = std::fs::read_to_string(&env.lock_path).unwrap();
+
+    let new_manifest = sample_manifest("New", &[("com.test.vpm.temp", "owner/temp")]);
+    let new_manifest_content = toml::to_string_pretty(&new_manifest).unwrap();
+    let new_hash = compute_manifest_hash_from_manifest(&new_manifest, &env.config_path)?;
+    let new_lock = lockfile_with_packages(&new_hash, &[("com.test.vpm.temp", "owner/temp")]);
+    write_txn(
+        &env.lock_path,
+        &old_manifest_content,
+        Some(&old_lock_content),
+        &new_manifest_content,
+        &toml::to_string_pretty(&new_lock).unwrap(),
+    );
+    std::fs::write(&env.config_path, &new_manifest_content).unwrap();
+
+    let cli = Cli::try_parse_from(["voy", "verify"]).unwrap();
+    let Commands::Verify(args) = cli.command else {
+        panic!("expected verify");
+    };
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, Arc::new(TestGitHub));
+    commands::verify::execute(args, &ctx).await?;
+
+    assert!(!txn_path(&env.lock_path).exists());
+    assert_eq!(Manifest::load(&env.config_path)?.vpm.name, "Old");
+    assert_eq!(
+        std::fs::read_to_string(&env.lock_path).unwrap(),
+        old_lock_content
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_json_outcome_includes_resolved_id_and_repository() -> Result<()> {
+    let env = TestEnv::new();
+    let manifest = sample_manifest("Test", &[]);
+    manifest.save(&env.config_path)?;
+
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, Arc::new(TestGitHub));
+    let outcome = commands::add::add_package(
+        AddArgs {
+            repository: "owner/My-Tool".to_string(),
+            id: None,
+            infer_id: false,
+            skip_verify: false,
+            github_auth: GitHubAuthArgs::default(),
+            gitlab_token: None,
+            gitea_token: None,
+            format: OutputFormat::Json,
+        },
+        &ctx,
+    )
+    .await?;
+
+    let json = serde_json::to_value(&outcome).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "id": "com.test.vpm.my_tool",
+            "repository": "owner/My-Tool",
+            "verified": true
+        })
+    );
+
+    Ok(())
+}
+
+fn add_args(repository: &str, id: &str, skip_verify: bool) -> AddArgs {
+    AddArgs {
+        repository: repository.to_string(),
+        id: Some(id.to_string()),
+        infer_id: false,
+        skip_verify,
+        github_auth: GitHubAuthArgs::default(),
+        gitlab_token: None,
+        gitea_token: None,
+        format: OutputFormat::Json,
+    }
+}
+
+#[tokio::test]
+async fn add_skip_verify_adds_package_without_contacting_github() -> Result<()> {
+    let env = TestEnv::new();
+    sample_manifest("Test", &[]).save(&env.config_path)?;
+    let manifest = Manifest::load(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &[]).save(&env.lock_path)?;
+
+    let ctx = AppContext::with_github(
+        ConfigPaths::new(env.config_path.clone()),
+        Arc::new(OfflineGitHub),
+    );
+    let outcome =
+        commands::add::add_package(add_args("owner/repo", "com.test.vpm.offline", true), &ctx)
+            .await?;
+    assert!(!outcome.verified);
+
+    let duplicate =
+        commands::add::add_package(add_args("owner/other", "com.test.vpm.offline", true), &ctx)
+            .await;
+    assert!(
+        matches!(duplicate, Err(Error::ConfigValidation(msg)) if msg.contains("already exists"))
+    );
+
+    let outside_prefix =
+        commands::add::add_package(add_args("owner/other", "com.other.pkg", true), &ctx).await;
+    assert!(outside_prefix.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_reuses_recent_repository_verification() -> Result<()> {
+    let env = TestEnv::new();
+    sample_manifest("Test", &[]).save(&env.config_path)?;
+    let manifest = Manifest::load(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &[]).save(&env.lock_path)?;
+    let paths = || ConfigPaths::new(env.config_path.clone());
+
+    let online = AppContext::with_github(paths(), Arc::new(TestGitHub));
+    commands::add::add_package(add_args("owner/repo", "com.test.vpm.first", false), &online)
+        .await?;
+
+    let offline = AppContext::with_github(paths(), Arc::new(OfflineGitHub));
+    let outcome = commands::add::add_package(
+        add_args("owner/repo", "com.test.vpm.second", false),
+        &offline,
+    )
+    .await?;
+    assert!(outcome.verified);
+
+    let unverified = commands::add::add_package(
+        add_args("owner/unknown", "com.test.vpm.third", false),
+        &offline,
+    )
+    .await;
+    assert!(matches!(unverified, Err(Error::RepositoryNotFound(_))));
+    Ok(())
+}
+
+fn infer_id_args() -> AddArgs {
+    AddArgs {
+        repository: "owner/repo".to_string(),
+        id: None,
+        infer_id: true,
+        skip_verify: false,
+        github_auth: GitHubAuthArgs::default(),
+        gitlab_token: None,
+        gitea_token: None,
+        format: OutputFormat::Json,
+    }
+}
+
+#[tokio::test]
+async fn add_infer_id_uses_latest_package_json_name() -> Result<()> {
+    let env = TestEnv::new();
+    sample_manifest("Test", &[]).save(&env.config_path)?;
+
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let github = NamedPackageGitHub {
+        name: "com.test.vpm.real_name",
+    };
+    let ctx = AppContext::with_github(paths, Arc::new(github));
+    let outcome = commands::add::add_package(infer_id_args(), &ctx).await?;
+
+    assert_eq!(outcome.id, "com.test.vpm.real_name");
+    assert_eq!(
+        Manifest::load(&env.config_path)?.packages[0].id,
+        "com.test.vpm.real_name"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_infer_id_rejects_name_outside_vpm_prefix() -> Result<()> {
+    let env = TestEnv::new();
+    sample_manifest("Test", &[]).save(&env.config_path)?;
+
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let github = NamedPackageGitHub {
+        name: "org.other.pkg",
+    };
+    let ctx = AppContext::with_github(paths, Arc::new(github));
+    let err = commands::add::add_package(infer_id_args(), &ctx)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidPackageId(_)));
+    assert!(Manifest::load(&env.config_path)?.packages.is_empty());
+    Ok(())
+}
+
 #[test]
 fn remove_recovers_partial_transaction_before_writing() -> Result<()> {
     let env = TestEnv::new();
@@ -208,7 +660,7 @@ fn remove_recovers_partial_transaction_before_writing() -> Result<()> {
     let new_lock_content = toml::to_string_pretty(&new_lock).unwrap();
 
     write_txn(
-        &env.config_path,
+        &env.lock_path,
         &old_manifest_content,
         Some(&old_lock_content),
         &new_manifest_content,
@@ -220,11 +672,12 @@ fn remove_recovers_partial_transaction_before_writing() -> Result<()> {
     commands::remove::execute(
         RemoveArgs {
             package_id: "com.test.vpm.target".to_string(),
+            keep_lock: false,
         },
         &paths,
     )?;
 
-    assert!(!txn_path(&env.config_path).exists());
+    assert!(!txn_path(&env.lock_path).exists());
 
     let manifest = Manifest::load(&env.config_path)?;
     assert_eq!(manifest.vpm.name, "Old");
@@ -240,6 +693,63 @@ fn remove_recovers_partial_transaction_before_writing() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn remove_keep_lock_retains_locked_versions() -> Result<()> {
+    let env = TestEnv::new();
+
+    let manifest = sample_manifest(
+        "Test",
+        &[
+            ("com.test.vpm.target", "owner/target"),
+            ("com.test.vpm.keep", "owner/keep"),
+        ],
+    );
+    manifest.save(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    let mut lock = lockfile_with_packages(
+        &hash,
+        &[
+            ("com.test.vpm.target", "owner/target"),
+            ("com.test.vpm.keep", "owner/keep"),
+        ],
+    );
+    lock.packages[0].versions.push(LockedVersion::new(
+        "v1.0.0".to_string(),
+        "https://example.com/target-1.0.0.zip".to_string(),
+        "{}",
+        serde_json::from_value(serde_json::json!({
+            "name": "com.test.vpm.target",
+            "version": "1.0.0",
+            "displayName": "Target",
+            "description": "desc",
+            "url": "https://example.com/target-1.0.0.zip",
+            "author": { "name": "Author" }
+        }))
+        .unwrap(),
+    ));
+    lock.save(&env.lock_path)?;
+
+    commands::remove::execute(
+        RemoveArgs {
+            package_id: "com.test.vpm.target".to_string(),
+            keep_lock: true,
+        },
+        &ConfigPaths::new(env.config_path.clone()),
+    )?;
+
+    let manifest = Manifest::load(&env.config_path)?;
+    assert_eq!(manifest.packages.len(), 1);
+    assert_eq!(manifest.packages[0].id, "com.test.vpm.keep");
+
+    let lock = Lockfile::load(&env.lock_path)?;
+    let retained = lock.get_package("com.test.vpm.target").unwrap();
+    assert!(retained.retained);
+    assert_eq!(retained.versions.len(), 1);
+    assert_eq!(retained.versions[0].version, "1.0.0");
+
+    Ok(())
+}
+
 #[test]
 fn check_and_load_works_with_valid_files() -> Result<()> {
     let env = TestEnv::new();
@@ -247,7 +757,7 @@ fn check_and_load_works_with_valid_files() -> Result<()> {
     // Use lockfile without manifest_hash to skip hash validation
     env.write_lockfile(SAMPLE_LOCKFILE_NO_HASH);
 
-    let result = check_and_load(&env.config_path, &env.lock_path)?;
+    let result = check_and_load(&env.config_path, &env.lock_path, ConfigFormat::Auto)?;
 
     assert_eq!(result.manifest.vpm.id, "com.test.vpm");
     assert_eq!(result.manifest.vpm.name, "Test VPM");
@@ -264,7 +774,7 @@ fn check_and_load_creates_lockfile_if_missing() -> Result<()> {
 
     assert!(!env.lock_exists());
 
-    let result = check_and_load(&env.config_path, &env.lock_path)?;
+    let result = check_and_load(&env.config_path, &env.lock_path, ConfigFormat::Auto)?;
 
     assert_eq!(result.manifest.vpm.id, "com.test.vpm");
     assert!(result.lockfile.packages.is_empty());
@@ -334,6 +844,49 @@ fn generate_from_lockfile_creates_valid_output() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn generate_writes_serialized_index_through_output_sink() -> Result<()> {
+    let env = TestEnv::new();
+    env.write_config(SAMPLE_CONFIG);
+    env.write_lockfile(SAMPLE_LOCKFILE_NO_HASH);
+
+    let sink = MemorySink::new();
+    let output_path = PathBuf::from("index.json");
+    commands::generate::execute_with_sink(
+        GenerateArgs {
+            output: output_path.clone(),
+            sort: PackageOrderArg::Manifest,
+            sort_versions: VersionOrderArg::Lock,
+            tag: None,
+            min_versions: 0,
+            output_hash_file: None,
+            hash_format: HashFormat::Prefixed,
+            assets: None,
+            max_index_size: DEFAULT_MAX_INDEX_SIZE,
+            strict: false,
+            offline: false,
+            emit_empty_packages: true,
+            no_duplicate_display_names: false,
+            sort_keys: false,
+            minify: false,
+            drop_extra: false,
+            emit_metadata: false,
+            redact_email: false,
+        },
+        &ConfigPaths::new(env.config_path.clone()),
+        &sink,
+    )?;
+
+    let manifest = Manifest::load(&env.config_path)?;
+    let lockfile = Lockfile::load(&env.lock_path)?;
+    let expected = serde_json::to_string_pretty(&generate_from_lockfile(&manifest, &lockfile)?)
+        .expect("index serializes");
+
+    assert_eq!(sink.get(&output_path), Some(expected));
+
+    Ok(())
+}
+
 #[test]
 fn config_paths_derives_lock_from_config() {
     let paths = ConfigPaths::new("custom/path/my-config.toml".into());
@@ -347,6 +900,52 @@ fn config_paths_derives_lock_from_config() {
     );
 }
 
+#[test]
+fn config_paths_respects_custom_lock_extension() {
+    let paths =
+        ConfigPaths::new("custom/path/my-config.toml".into()).with_lock_extension("lock.toml");
+    assert_eq!(
+        paths.lock_path().to_str().unwrap(),
+        "custom/path/my-config.lock.toml"
+    );
+}
+
+#[test]
+fn transaction_recovery_uses_log_next_to_custom_lock_file() -> Result<()> {
+    let env = TestEnv::new();
+    let paths = ConfigPaths::new(env.config_path.clone()).with_lock_extension("lock.toml");
+
+    let old_manifest = sample_manifest("Old", &[]);
+    old_manifest.save(paths.config_path())?;
+    let old_manifest_content = std::fs::read_to_string(paths.config_path()).unwrap();
+    let old_hash = compute_manifest_hash_from_manifest(&old_manifest, paths.config_path())?;
+    let old_lock_content = toml::to_string_pretty(&lockfile_with_packages(&old_hash, &[])).unwrap();
+    std::fs::write(paths.lock_path(), &old_lock_content).unwrap();
+
+    let new_manifest = sample_manifest("New", &[]);
+    let new_manifest_content = toml::to_string_pretty(&new_manifest).unwrap();
+    write_txn(
+        paths.lock_path(),
+        &old_manifest_content,
+        Some(&old_lock_content),
+        &new_manifest_content,
+        "partial",
+    );
+    std::fs::write(paths.config_path(), &new_manifest_content).unwrap();
+
+    let result = check_and_load(paths.config_path(), paths.lock_path(), ConfigFormat::Auto)?;
+
+    assert!(!txn_path(paths.lock_path()).exists());
+    assert_eq!(result.manifest.vpm.name, "Old");
+    assert_eq!(
+        std::fs::read_to_string(paths.lock_path()).unwrap(),
+        old_lock_content
+    );
+    assert!(!env.lock_path.exists());
+
+    Ok(())
+}
+
 #[test]
 fn config_paths_default_uses_voyager_toml() {
     let paths = ConfigPaths::default();
@@ -452,7 +1051,12 @@ async fn lock_rejects_manifest_changes_during_repository_verification() -> Resul
     let result = commands::lock::execute(
         LockArgs {
             check: false,
-            github_token: None,
+            print: false,
+            fix: false,
+            manifest_hash_algo: None,
+            github_auth: GitHubAuthArgs::default(),
+            gitlab_token: None,
+            gitea_token: None,
         },
         &ctx,
     )
@@ -465,3 +1069,202 @@ async fn lock_rejects_manifest_changes_during_repository_verification() -> Resul
 
     Ok(())
 }
+
+async fn fetch_with_partial_failure(on_failure: &str) -> Result<(TestEnv, Result<()>)> {
+    let env = TestEnv::new();
+    let manifest = sample_manifest("Test", &[("com.test.vpm.pkg", "owner/repo")]);
+    manifest.save(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &[("com.test.vpm.pkg", "owner/repo")]).save(&env.lock_path)?;
+
+    let cli = Cli::try_parse_from(["voy", "fetch", "--on-failure", on_failure]).unwrap();
+    let Commands::Fetch(args) = cli.command else {
+        panic!("expected fetch");
+    };
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, Arc::new(PartiallyFailingGitHub));
+    let result = commands::fetch::execute(*args, &ctx).await;
+    Ok((env, result))
+}
+
+#[tokio::test]
+async fn fetch_on_failure_keep_saves_successful_versions() -> Result<()> {
+    let (env, result) = fetch_with_partial_failure("keep").await?;
+
+    let error = result.unwrap_err();
+    assert!(matches!(
+        error,
+        Error::FetchPartialFailure {
+            ref failures,
+            lock_saved: true,
+        } if failures.len() == 1
+    ));
+    assert!(
+        error
+            .to_string()
+            .contains("successfully fetched versions were saved to the lockfile")
+    );
+    let lock = Lockfile::load(&env.lock_path)?;
+    let versions: Vec<_> = lock.packages[0]
+        .versions
+        .iter()
+        .map(|v| v.version.as_str())
+        .collect();
+    assert_eq!(versions, vec!["1.0.0"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_on_failure_revert_leaves_lockfile_untouched() -> Result<()> {
+    let (env, result) = fetch_with_partial_failure("revert").await?;
+
+    let error = result.unwrap_err();
+    assert!(matches!(
+        error,
+        Error::FetchPartialFailure {
+            ref failures,
+            lock_saved: false,
+        } if failures.len() == 1
+    ));
+    assert!(error.to_string().contains("lockfile was not updated"));
+    let lock = Lockfile::load(&env.lock_path)?;
+    assert!(lock.packages[0].versions.is_empty());
+
+    Ok(())
+}
+
+async fn fetch_contacting(env: &TestEnv, args: &[&str]) -> Result<Vec<String>> {
+    let cli = Cli::try_parse_from(["voy", "fetch"].iter().chain(args)).unwrap();
+    let Commands::Fetch(args) = cli.command else {
+        panic!("expected fetch");
+    };
+    let github = Arc::new(RecordingGitHub::default());
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, github.clone());
+    commands::fetch::execute(*args, &ctx).await?;
+    let mut calls = github.release_calls.lock().unwrap().clone();
+    calls.sort();
+    Ok(calls)
+}
+
+#[tokio::test]
+async fn fetch_only_new_repos_still_contacts_repositories_skipped_by_a_filtered_fetch() -> Result<()>
+{
+    let env = TestEnv::new();
+    let packages = [
+        ("com.test.vpm.pkg1", "owner/repo1"),
+        ("com.test.vpm.pkg2", "owner/repo2"),
+    ];
+    let manifest = sample_manifest("Test", &packages);
+    manifest.save(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &packages).save(&env.lock_path)?;
+
+    let filtered = fetch_contacting(&env, &["--only", "com.test.vpm.pkg1"]).await?;
+    assert_eq!(filtered, vec!["owner/repo1"]);
+
+    let new_repos = fetch_contacting(&env, &["--only-new-repos"]).await?;
+    assert_eq!(new_repos, vec!["owner/repo2"]);
+
+    let again = fetch_contacting(&env, &["--only-new-repos"]).await?;
+    assert!(again.is_empty());
+
+    Ok(())
+}
+
+async fn fetch_with_url_rewrite(extra_args: &[&str]) -> Result<Lockfile> {
+    let env = TestEnv::new();
+    let mut manifest = sample_manifest("Test", &[("com.test.vpm.pkg", "owner/repo")]);
+    manifest.registry.url_rewrite.insert(
+        "https://assets.example/".to_string(),
+        "https://mirror.example/assets/".to_string(),
+    );
+    manifest.save(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &[("com.test.vpm.pkg", "owner/repo")]).save(&env.lock_path)?;
+
+    let cli = Cli::try_parse_from(["voy", "fetch"].iter().chain(extra_args)).unwrap();
+    let Commands::Fetch(args) = cli.command else {
+        panic!("expected fetch");
+    };
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, Arc::new(SingleVersionGitHub));
+    commands::fetch::execute(*args, &ctx).await?;
+    Lockfile::load(&env.lock_path)
+}
+
+#[tokio::test]
+async fn fetch_keeps_canonical_urls_unless_persist_rewrite_is_set() -> Result<()> {
+    let canonical = fetch_with_url_rewrite(&[]).await?;
+    assert_eq!(canonical.packages[0].versions.len(), 1);
+    assert_eq!(
+        canonical.packages[0].versions[0].url,
+        "https://assets.example/v1.0.0.json"
+    );
+
+    let persisted = fetch_with_url_rewrite(&["--persist-rewrite"]).await?;
+    assert_eq!(
+        persisted.packages[0].versions[0].url,
+        "https://mirror.example/assets/v1.0.0.json"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn outdated_reports_unlocked_releases_without_touching_lockfile() -> Result<()> {
+    let env = TestEnv::new();
+    let manifest = sample_manifest("Test", &[("com.test.vpm.pkg", "owner/repo")]);
+    manifest.save(&env.config_path)?;
+    let hash = compute_manifest_hash_from_manifest(&manifest, &env.config_path)?;
+    lockfile_with_packages(&hash, &[("com.test.vpm.pkg", "owner/repo")]).save(&env.lock_path)?;
+    let lock_before = std::fs::read_to_string(&env.lock_path).unwrap();
+
+    let paths = ConfigPaths::new(env.config_path.clone());
+    let ctx = AppContext::with_github(paths, Arc::new(PartiallyFailingGitHub));
+    let result = commands::outdated::execute(
+        OutdatedArgs {
+            github_auth: GitHubAuthArgs::default(),
+            gitlab_token: None,
+            gitea_token: None,
+            max_concurrent: 5,
+            asset_name: "package.json".to_string(),
+            format: OutputFormat::Json,
+        },
+        &ctx,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::Outdated { count: 1 })));
+    assert_eq!(result.unwrap_err().exit_code(), ExitCode::DATA);
+    assert_eq!(
+        std::fs::read_to_string(&env.lock_path).unwrap(),
+        lock_before
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_in_memory_locks_versions_without_touching_disk() -> Result<()> {
+    let manifest = sample_manifest("Test", &[("com.test.vpm.pkg", "owner/repo")]);
+
+    let lockfile = voyager::api::fetch_in_memory(
+        &manifest,
+        Lockfile::new(),
+        Arc::new(SingleVersionGitHub),
+        FetcherConfig {
+            checkpoint_path: Some(PathBuf::from("must-not-be-written.json")),
+            ..FetcherConfig::default()
+        },
+        None,
+    )
+    .await?;
+
+    let package = lockfile.get_package("com.test.vpm.pkg").unwrap();
+    assert_eq!(package.versions.len(), 1);
+    assert_eq!(package.versions[0].version, "1.0.0");
+    assert!(!Path::new("must-not-be-written.json").exists());
+    Ok(())
+}